@@ -87,6 +87,13 @@ extern "C" fn driver_entry(_driver: PVOID, _registry_path: PVOID) -> NtStatus {
         }
     }
 
+    match services::async_services::init() {
+        Ok(_) => {}
+        Err(err) => {
+            panic!("Failed to start async worker: {}", err);
+        }
+    }
+
     NtStatus::Success
 }
 
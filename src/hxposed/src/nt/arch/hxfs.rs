@@ -50,6 +50,13 @@ impl Registers {
         self.r8 = response.arg1;
         self.r9 = response.arg2;
         self.r10 = response.arg3;
+
+        if response.result.extended_results_present() {
+            self.xmm0 = response.extended_arg1;
+            self.xmm1 = response.extended_arg2;
+            self.xmm2 = response.extended_arg3;
+            self.xmm3 = response.extended_arg4;
+        }
     }
 }
 
@@ -68,7 +68,7 @@ impl NtCallback {
             return;
         } else if !info.is_null()
             && !process.is_hx_info_present()
-            && HxGuard::is_valid_caller(process.get_path_hash())
+            && HxGuard::is_valid_caller(process.get_path_hashcode())
         {
             // we are not in context of the process that is being created
             // we are in context of the parent
@@ -87,7 +87,7 @@ impl NtCallback {
                     continue;
                 }
 
-                let obj = ObjectType::Process(id as _).into_raw();
+                let obj = ObjectType::Process(id as _).into_raw_enum();
                 let callback_info = CallbackInformation {
                     object_type: obj.0,
                     object_value: obj.1,
@@ -121,7 +121,7 @@ impl NtCallback {
                     continue;
                 }
 
-                let obj = ObjectType::Thread(thread_id as _).into_raw();
+                let obj = ObjectType::Thread(thread_id as _).into_raw_enum();
                 let callback_info = CallbackInformation {
                     object_type: obj.0,
                     object_value: obj.1,
@@ -1,11 +1,14 @@
 use crate::HX_GUARD;
 use crate::nt::registry::NtKey;
 use crate::win::Boolean;
+use hxposed_core::hxposed::permissions::PluginPermissions;
 use core::arch::x86_64::{
     __m128i, _mm_cmpeq_epi64, _mm_loadu_si128, _mm_movemask_pd, _mm_set1_epi64x,
 };
 
 pub static mut VALID_CALLERS: [u64; 256] = [0; 256];
+// parallel to VALID_CALLERS.
+pub static mut CALLER_PERMISSIONS: [u64; 256] = [0; 256];
 //pub static VALID_CALLER_COUNT: AtomicU32 = AtomicU32::new(0);
 
 pub enum RegistryProtection {
@@ -28,14 +31,43 @@ impl HxGuard {
         }
     }
 
-    // not my code
     #[allow(static_mut_refs)]
     pub fn is_valid_caller(hash: u64) -> bool {
         unsafe {
             if !HX_GUARD.caller_verification {
                 return true;
             }
+        }
+
+        Self::find_caller(hash).is_some()
+    }
+
+    ///
+    /// # Get Permissions
+    ///
+    /// Gets the permissions of the caller with `hash`.
+    ///
+    /// ## Return
+    /// * [`PluginPermissions::all`] - Caller verification is off.
+    /// * [`PluginPermissions::NONE`] - Caller is not verified.
+    #[allow(static_mut_refs)]
+    pub fn get_permissions(hash: u64) -> PluginPermissions {
+        unsafe {
+            if !HX_GUARD.caller_verification {
+                return PluginPermissions::all();
+            }
 
+            match Self::find_caller(hash) {
+                Some(i) => PluginPermissions::from_bits_truncate(CALLER_PERMISSIONS[i]),
+                None => PluginPermissions::NONE,
+            }
+        }
+    }
+
+    // not my code
+    #[allow(static_mut_refs)]
+    fn find_caller(hash: u64) -> Option<usize> {
+        unsafe {
             let mut i = 0;
             let len = VALID_CALLERS.len();
 
@@ -49,13 +81,13 @@ impl HxGuard {
                 let mask = _mm_movemask_pd(core::mem::transmute(cmp));
 
                 if mask != 0 {
-                    return true;
+                    return Some(i + mask.trailing_zeros() as usize);
                 }
 
                 i += 2;
             }
 
-            false
+            None
         }
     }
 
@@ -103,13 +135,27 @@ impl HxGuard {
                 }
             };
 
-            // SAFETY: no one touches VALID_CALLERS yet.
+            // missing means nobody gets anything.
+            let mut default_permissions: [u64; 256] = [0; 256];
+            let permissions = match key.get_value::<[u64; 256]>("CallerPermissions") {
+                Ok(x) => x,
+                Err(err) => {
+                    &mut default_permissions
+                }
+            };
+
+            // SAFETY: no one touches VALID_CALLERS or CALLER_PERMISSIONS yet.
             unsafe {
                 core::ptr::copy_nonoverlapping::<u64>(
                     values.as_ptr(),
                     VALID_CALLERS.as_mut_ptr(),
                     values.len(),
                 );
+                core::ptr::copy_nonoverlapping::<u64>(
+                    permissions.as_ptr(),
+                    CALLER_PERMISSIONS.as_mut_ptr(),
+                    permissions.len(),
+                );
             }
         }
         match self.registry_protection {
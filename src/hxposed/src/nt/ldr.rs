@@ -0,0 +1,123 @@
+use crate::nt::mm::user::{read_user, read_user_string};
+use alloc::vec::Vec;
+
+// PEB and loader layouts are user mode ABI. they don't move between builds.
+const PEB_LDR: u64 = 0x18;
+const LDR_IN_LOAD_ORDER_MODULE_LIST: u64 = 0x10;
+const ENTRY_DLL_BASE: u64 = 0x30;
+const ENTRY_ENTRY_POINT: u64 = 0x38;
+const ENTRY_SIZE_OF_IMAGE: u64 = 0x40;
+const ENTRY_FULL_DLL_NAME: u64 = 0x48;
+
+const PEB32_LDR: u64 = 0xc;
+const LDR32_IN_LOAD_ORDER_MODULE_LIST: u64 = 0xc;
+const ENTRY32_DLL_BASE: u64 = 0x18;
+const ENTRY32_ENTRY_POINT: u64 = 0x1c;
+const ENTRY32_SIZE_OF_IMAGE: u64 = 0x20;
+const ENTRY32_FULL_DLL_NAME: u64 = 0x24;
+
+/// The list is in user memory. Nothing stops it from looping.
+const MAX_MODULES: usize = 0x1000;
+
+///
+/// # Nt Module
+///
+/// A `LDR_DATA_TABLE_ENTRY`, copied out of user memory.
+///
+pub struct NtModule {
+    pub base: u64,
+    pub entry_point: u64,
+    pub size: u32,
+    pub path: Vec<u16>,
+    pub wow64: bool,
+}
+
+impl NtModule {
+    ///
+    /// # Walk
+    ///
+    /// Walks `Ldr->InLoadOrderModuleList` of `peb`.
+    ///
+    /// ## Arguments
+    /// * `peb` - `PEB`, or `PEB32` if `wow64`.
+    /// * `wow64` - Whether to walk with 32-bit layouts.
+    ///
+    /// ## Remarks
+    /// - Must be attached to the process.
+    /// - Every read is SEH guarded, see [`read_user`]. List ends at the first one that faults.
+    ///
+    /// ## Return
+    /// * [`Vec<NtModule>`] - Modules, in load order.
+    pub fn walk(peb: u64, wow64: bool) -> Vec<NtModule> {
+        let mut modules = Vec::new();
+
+        let ldr = match wow64 {
+            true => read_user::<u32>(peb + PEB32_LDR).map(|x| x as u64),
+            false => read_user::<u64>(peb + PEB_LDR),
+        };
+        let head = match ldr {
+            Some(x) if x != 0 => match wow64 {
+                true => x + LDR32_IN_LOAD_ORDER_MODULE_LIST,
+                false => x + LDR_IN_LOAD_ORDER_MODULE_LIST,
+            },
+            _ => return modules,
+        };
+
+        // InLoadOrderLinks is at the start of the entry. so links are entries.
+        let mut current = match flink(head, wow64) {
+            Some(x) => x,
+            None => return modules,
+        };
+
+        while current != head && current != 0 && modules.len() < MAX_MODULES {
+            let module = match wow64 {
+                true => Self::read_entry32(current),
+                false => Self::read_entry(current),
+            };
+            match module {
+                Some(x) => modules.push(x),
+                None => break,
+            }
+
+            current = match flink(current, wow64) {
+                Some(x) => x,
+                None => break,
+            };
+        }
+
+        modules
+    }
+
+    fn read_entry(entry: u64) -> Option<NtModule> {
+        Some(NtModule {
+            base: read_user::<u64>(entry + ENTRY_DLL_BASE)?,
+            entry_point: read_user::<u64>(entry + ENTRY_ENTRY_POINT)?,
+            size: read_user::<u32>(entry + ENTRY_SIZE_OF_IMAGE)?,
+            path: read_user_string(
+                read_user::<u16>(entry + ENTRY_FULL_DLL_NAME)?,
+                read_user::<u64>(entry + ENTRY_FULL_DLL_NAME + 8)?,
+            )?,
+            wow64: false,
+        })
+    }
+
+    fn read_entry32(entry: u64) -> Option<NtModule> {
+        Some(NtModule {
+            base: read_user::<u32>(entry + ENTRY32_DLL_BASE)? as _,
+            entry_point: read_user::<u32>(entry + ENTRY32_ENTRY_POINT)? as _,
+            size: read_user::<u32>(entry + ENTRY32_SIZE_OF_IMAGE)?,
+            path: read_user_string(
+                read_user::<u16>(entry + ENTRY32_FULL_DLL_NAME)?,
+                read_user::<u32>(entry + ENTRY32_FULL_DLL_NAME + 4)? as _,
+            )?,
+            wow64: true,
+        })
+    }
+}
+
+fn flink(link: u64, wow64: bool) -> Option<u64> {
+    match wow64 {
+        true => read_user::<u32>(link).map(|x| x as u64),
+        false => read_user::<u64>(link),
+    }
+}
@@ -1,6 +1,6 @@
 use crate::win::{
     ExAcquirePushLockExclusiveEx, ExAcquirePushLockSharedEx, ExReleasePushLockExclusiveEx,
-    ExReleasePushLockSharedEx,
+    ExReleasePushLockSharedEx, KeEnterCriticalRegion, KeLeaveCriticalRegion,
 };
 
 #[derive(Debug)]
@@ -41,6 +41,8 @@ impl Drop for PushLockGuard {
             LockType::Exclusive => unsafe { ExReleasePushLockExclusiveEx(self.lock, 0) },
             LockType::Shared => unsafe { ExReleasePushLockSharedEx(self.lock, 0) },
         }
+
+        unsafe { KeLeaveCriticalRegion() };
     }
 }
 
@@ -48,6 +50,9 @@ impl PushLockGuard {
     pub(crate) fn new(lock: *mut u64, lock_type: LockType) -> Self {
         let me = Self { lock, lock_type };
 
+        // no suspending us with someone else's lock held.
+        unsafe { KeEnterCriticalRegion() };
+
         match me.lock_type {
             LockType::Exclusive => unsafe { ExAcquirePushLockExclusiveEx(lock, 0) },
             LockType::Shared => unsafe { ExAcquirePushLockSharedEx(lock, 0) },
@@ -42,6 +42,11 @@ pub enum MapStatus {
 
 impl Drop for MemoryDescriptor {
     fn drop(&mut self) {
+        // unmap before the pages go away.
+        if let MapStatus::Mapped(location) = self.status {
+            unsafe { MmUnmapLockedPages(location as _, self.mdl.ptr) }
+        }
+
         match self.owns {
             OwnType::NonPaged(addr) => unsafe { ExFreePool(addr as _) },
             OwnType::MmAllocatePages => unsafe { MmFreePagesFromMdl(self.mdl.ptr) },
@@ -52,9 +57,6 @@ impl Drop for MemoryDescriptor {
                 // do nothing
             }
         }
-        if let MapStatus::Mapped(location) = self.status {
-            unsafe { MmUnmapLockedPages(location as _, self.mdl.ptr) }
-        }
 
         // free the mdl at the end
         unsafe {
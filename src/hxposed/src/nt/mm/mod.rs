@@ -1,2 +1,4 @@
 pub(crate) mod mdl;
 pub(crate) mod rmd;
+pub(crate) mod vad;
+pub(crate) mod user;
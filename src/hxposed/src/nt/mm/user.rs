@@ -0,0 +1,76 @@
+use alloc::vec::Vec;
+
+/// `MmUserProbeAddress`. Pointers read out of user memory are the process's word, and it may point them at us.
+pub(crate) const USER_LIMIT: u64 = 0x7FFF_FFFF_0000;
+
+///
+/// # Is User Range
+///
+/// Checks `count` of [`T`] starting at `address` are all below [`USER_LIMIT`].
+///
+/// ## Remarks
+/// - Doesn't check the range is mapped. Access it under `microseh::try_seh`.
+///
+pub fn is_user_range<T>(address: u64, count: usize) -> bool {
+    count
+        .checked_mul(size_of::<T>())
+        .and_then(|size| address.checked_add(size as u64))
+        .is_some_and(|end| end <= USER_LIMIT)
+}
+
+///
+/// # Read User
+///
+/// Reads a [`T`] from user memory of the current address space.
+///
+/// ## Return
+/// * [`Some`] - The value.
+/// * [`None`] - Not a user address, or it faulted.
+pub fn read_user<T: Copy>(address: u64) -> Option<T> {
+    if !is_user_range::<T>(address, 1) {
+        return None;
+    }
+
+    microseh::try_seh(|| unsafe { (address as *const T).read_unaligned() }).ok()
+}
+
+///
+/// # Read User Slice
+///
+/// Same as [`read_user`], for `count` of them.
+///
+pub fn read_user_slice<T: Copy + Default>(address: u64, count: usize) -> Option<Vec<T>> {
+    if count == 0 {
+        return Some(Vec::new());
+    }
+    if !is_user_range::<T>(address, count) {
+        return None;
+    }
+
+    let mut buffer = alloc::vec![T::default(); count];
+    microseh::try_seh(|| unsafe {
+        core::ptr::copy_nonoverlapping(address as *const T, buffer.as_mut_ptr(), count)
+    })
+    .ok()?;
+
+    Some(buffer)
+}
+
+///
+/// # Read User String
+///
+/// Reads what a `UNICODE_STRING` in user memory points to.
+///
+/// ## Arguments
+/// * `length` - `Length`. In bytes.
+/// * `buffer` - `Buffer`.
+///
+/// ## Return
+/// * [`Some`] - The string. Empty if `buffer` is null.
+/// * [`None`] - Not a user address, or it faulted.
+pub fn read_user_string(length: u16, buffer: u64) -> Option<Vec<u16>> {
+    match buffer {
+        0 => Some(Vec::new()),
+        _ => read_user_slice::<u16>(buffer, length as usize / 2),
+    }
+}
@@ -0,0 +1,182 @@
+use crate::nt::{
+    ControlAreaField, FileObjectField, SubsectionField, VadField, get_control_area_field,
+    get_file_object_field, get_subsection_field, get_vad_field,
+};
+use crate::win::{ObfDereferenceObject, ObfReferenceObject, PMMVAD, PVOID, UNICODE_STRING};
+use crate::win::unicode_string::UnicodeString;
+use alloc::vec::Vec;
+use bitfield_struct::bitfield;
+
+/// AVL trees of 2^64 nodes are at most ~90 deep. Anything deeper is a tree changing under us.
+const MAX_DEPTH: usize = 96;
+/// Same for a tree that loops.
+const MAX_VADS: usize = 1 << 20;
+
+///
+/// # Vad Flags
+///
+/// `_MMVAD_FLAGS`.
+///
+#[bitfield(u32)]
+pub struct VadFlags {
+    pub lock: bool,
+    pub lock_contended: bool,
+    pub delete_in_progress: bool,
+    pub no_change: bool,
+    #[bits(3)]
+    pub vad_type: u8,
+    #[bits(5)]
+    pub protection: u8,
+    #[bits(6)]
+    pub preferred_node: u8,
+    #[bits(2)]
+    pub page_size: u8,
+    pub private_memory: bool,
+    #[bits(11)]
+    __: u16,
+}
+
+///
+/// # Vad Flags 1
+///
+/// `_MMVAD_FLAGS1`.
+///
+#[bitfield(u32)]
+pub struct VadFlags1 {
+    #[bits(31)]
+    pub commit_charge: u32,
+    pub mem_commit: bool,
+}
+
+///
+/// # Nt Vad
+///
+/// A node of the VAD tree. `_MMVAD`, or `_MMVAD_SHORT` for private memory.
+///
+/// Not owning. VADs belong to the address space, and go away with it, or with an unmap.
+/// Hold [`NtProcess::lock_address_space`](crate::nt::process::NtProcess::lock_address_space) for as long as you use them.
+///
+#[derive(Copy, Clone)]
+pub struct NtVad {
+    pub vad: PMMVAD,
+}
+
+impl NtVad {
+    ///
+    /// # Walk
+    ///
+    /// Walks the tree under `root`, in order. That's address order.
+    ///
+    /// ## Remarks
+    /// - Caller holds `AddressCreationLock` of the owner. See [`NtProcess::lock_address_space`](crate::nt::process::NtProcess::lock_address_space).
+    /// - Gives up on branches deeper than any AVL tree can be, and on trees bigger than any address space has.
+    ///
+    /// ## Return
+    /// * [`Vec<NtVad>`] - VADs, lowest address first.
+    /// * [`bool`] - Gave up somewhere. VADs are missing.
+    pub fn walk(root: PMMVAD) -> (Vec<NtVad>, bool) {
+        let mut vads = Vec::new();
+        let mut stack = Vec::new();
+        let mut current = root;
+        let mut truncated = false;
+
+        while !current.is_null() || !stack.is_empty() {
+            if vads.len() == MAX_VADS {
+                truncated = true;
+                break;
+            }
+
+            while !current.is_null() {
+                if stack.len() == MAX_DEPTH {
+                    // the left subtree is lost. the node above it isn't.
+                    truncated = true;
+                    break;
+                }
+                stack.push(current);
+                current = unsafe { *get_vad_field::<PMMVAD>(VadField::Left, current) };
+            }
+
+            let vad = match stack.pop() {
+                Some(x) => x,
+                None => break,
+            };
+
+            vads.push(NtVad { vad });
+            current = unsafe { *get_vad_field::<PMMVAD>(VadField::Right, vad) };
+        }
+
+        (vads, truncated)
+    }
+
+    pub fn get_start_vpn(&self) -> u64 {
+        unsafe {
+            *get_vad_field::<u32>(VadField::StartingVpn, self.vad) as u64
+                | (*get_vad_field::<u8>(VadField::StartingVpnHigh, self.vad) as u64) << 32
+        }
+    }
+
+    pub fn get_end_vpn(&self) -> u64 {
+        unsafe {
+            *get_vad_field::<u32>(VadField::EndingVpn, self.vad) as u64
+                | (*get_vad_field::<u8>(VadField::EndingVpnHigh, self.vad) as u64) << 32
+        }
+    }
+
+    pub fn get_flags(&self) -> VadFlags {
+        unsafe { *get_vad_field::<VadFlags>(VadField::VadFlags, self.vad) }
+    }
+
+    pub fn get_commit_charge(&self) -> u64 {
+        unsafe {
+            (*get_vad_field::<VadFlags1>(VadField::VadFlags1, self.vad)).commit_charge() as u64
+                | (*get_vad_field::<u8>(VadField::CommitChargeHigh, self.vad) as u64) << 31
+        }
+    }
+
+    ///
+    /// # Get File Name
+    ///
+    /// Follows `Subsection->ControlArea->FilePointer` to the backing file.
+    ///
+    /// ## Remarks
+    /// - Same lock as [`Self::walk`]. The file is referenced while we read its name.
+    ///
+    /// ## Return
+    /// * [`Some`] - Name of the file, relative to its volume.
+    /// * [`None`] - Private memory, or a section backed by the page file.
+    pub fn get_file_name(&self) -> Option<UnicodeString> {
+        if self.get_flags().private_memory() {
+            return None;
+        }
+
+        unsafe {
+            let subsection = *get_vad_field::<PVOID>(VadField::Subsection, self.vad);
+            if subsection.is_null() {
+                return None;
+            }
+
+            let control_area =
+                *get_subsection_field::<PVOID>(SubsectionField::ControlArea, subsection);
+            if control_area.is_null() {
+                return None;
+            }
+
+            // _EX_FAST_REF. low bits are the ref count.
+            let file = (*get_control_area_field::<u64>(ControlAreaField::FilePointer, control_area)
+                & !0xF) as PVOID;
+            if file.is_null() {
+                return None;
+            }
+
+            ObfReferenceObject(file);
+            let name = &*get_file_object_field::<UNICODE_STRING>(FileObjectField::FileName, file);
+            let name = match name.Buffer.is_null() || name.Length == 0 {
+                true => None,
+                false => Some(UnicodeString::from_unicode_string(name)),
+            };
+            ObfDereferenceObject(file);
+
+            name
+        }
+    }
+}
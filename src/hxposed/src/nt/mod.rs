@@ -7,6 +7,7 @@ pub(crate) mod callback;
 pub(crate) mod context;
 pub(crate) mod event;
 pub(crate) mod guard;
+pub(crate) mod ldr;
 pub(crate) mod lock;
 pub(crate) mod mm;
 pub(crate) mod object;
@@ -16,6 +17,7 @@ pub(crate) mod thread;
 pub(crate) mod token;
 
 use crate::nt::registry::NtKey;
+use crate::win::unicode_string::UnicodeString;
 use crate::utils::logger::LogEvent;
 use crate::win::*;
 use crate::{GLOBAL_LOGGER, scoped_log, utils};
@@ -87,6 +89,9 @@ pub(crate) fn get_nt_info() -> Result<(), ()> {
         NT_PS_TERMINATE_THREAD =
             get_nt_proc::<PsTerminateThreadType>(NtProcedure::PspTerminateThreadByPointer) as _;
 
+        NT_PS_SUSPEND_THREAD = get_nt_proc::<u64>(NtProcedure::PsSuspendThread) as _;
+        NT_PS_RESUME_THREAD = get_nt_proc::<u64>(NtProcedure::PsResumeThread) as _;
+
         NT_KI_SYSTEM_CALL64 = get_nt_proc::<u64>(NtProcedure::KiSystemCall64) as _;
         NT_KI_GENERAL_PROTECTION_FAULT = get_nt_proc::<u64>(NtProcedure::KiGeneralProtectionFault) as _;
 
@@ -161,10 +166,11 @@ pub(crate) unsafe fn get_pcr_field<T>(field: PcrField, pcr: *mut _KPCR) -> *mut
 ///
 /// ## Return
 /// * An absolute pointer to [`T`], if found.
+/// * Null, if it's not located on this build yet.
 ///
 pub(crate) unsafe fn get_nt_proc<T>(proc: NtProcedure) -> *mut T {
     unsafe {
-        (NT_BASE as *mut u8).add(match (NT_BUILD, NT_UBR) {
+        let offset = match (NT_BUILD, NT_UBR) {
             (26100, 6584) /* 25H2 */ => {
                 match proc {
                     NtProcedure::PsTerminateProcessProc => 0x91f3d4,
@@ -175,10 +181,18 @@ pub(crate) unsafe fn get_nt_proc<T>(proc: NtProcedure) -> *mut T {
                     NtProcedure::ExCreateHandle => 0xa1b200,
                     NtProcedure::KiSystemCall64 => 0x6b2b40,
                     NtProcedure::KiGeneralProtectionFault => 0x6ae4c0,
+                    // TODO: not exported, and not located on this build yet. suspend and resume services are off until they are.
+                    NtProcedure::PsSuspendThread => 0,
+                    NtProcedure::PsResumeThread => 0,
                 }
             }
             _ => unreachable!(),
-        }) as *mut T
+        };
+
+        match offset {
+            0 => core::ptr::null_mut(),
+            x => (NT_BASE as *mut u8).add(x) as *mut T,
+        }
     }
 }
 
@@ -206,6 +220,7 @@ pub(crate) unsafe fn get_eprocess_field<T: 'static>(
                 match field {
                     EProcessField::CreateTime => 0x1f8,
                     EProcessField::Token => 0x248,
+                    EProcessField::AddressCreationLock => 0x258,
                     EProcessField::SectionObject => 0x2f8,
                     EProcessField::SectionBaseAddress => 0x2b0,
                     EProcessField::Peb => 0x2e0,
@@ -223,6 +238,8 @@ pub(crate) unsafe fn get_eprocess_field<T: 'static>(
                     EProcessField::Pad => 0xc0,
                     EProcessField::DirectoryTableBase => 0x28,
                     EProcessField::UserDirectoryTableBase => 0x158,
+                    EProcessField::InheritedFromUniqueProcessId => 0x2d0,
+                    EProcessField::ImageFileName => 0x338,
                 }
             }
             _ => unreachable!(),
@@ -336,6 +353,129 @@ pub(crate) unsafe fn get_logon_session_field<T: 'static>(
     }
 }
 
+///
+/// # Get `_MMVAD` Field
+///
+/// Gets pointer to field of `_MMVAD` depending on NT version.
+///
+/// ## Arguments
+/// * `field` - Field you want to acquire pointer to. See [`VadField`]
+/// * `vad` - VAD to get pointer from.
+///
+/// ## Panic
+/// - This function panics if the NT version is not supported.
+///
+/// ## Returns
+/// - Absolute **pointer** to the field, in [`T`].
+pub(crate) unsafe fn get_vad_field<T: 'static>(field: VadField, vad: PMMVAD) -> *mut T {
+    unsafe {
+        (vad as *mut u8).byte_offset(match (NT_BUILD, NT_UBR) {
+            (26100, 6584) /* 25H2 */ => {
+                match field {
+                    VadField::Left => 0x0,
+                    VadField::Right => 0x8,
+                    VadField::StartingVpn => 0x18,
+                    VadField::EndingVpn => 0x1c,
+                    VadField::StartingVpnHigh => 0x20,
+                    VadField::EndingVpnHigh => 0x21,
+                    VadField::CommitChargeHigh => 0x22,
+                    VadField::VadFlags => 0x30,
+                    VadField::VadFlags1 => 0x34,
+                    VadField::Subsection => 0x48,
+                }
+            }
+            _ => unreachable!(),
+        }) as *mut T
+    }
+}
+
+///
+/// # Get `_SUBSECTION` Field
+///
+/// Gets pointer to field of `_SUBSECTION` depending on NT version.
+///
+/// ## Arguments
+/// * `field` - Field you want to acquire pointer to. See [`SubsectionField`]
+/// * `subsection` - Subsection to get pointer from.
+///
+/// ## Panic
+/// - This function panics if the NT version is not supported.
+///
+/// ## Returns
+/// - Absolute **pointer** to the field, in [`T`].
+pub(crate) unsafe fn get_subsection_field<T: 'static>(
+    field: SubsectionField,
+    subsection: PVOID,
+) -> *mut T {
+    unsafe {
+        (subsection as *mut u8).byte_offset(match (NT_BUILD, NT_UBR) {
+            (26100, 6584) /* 25H2 */ => {
+                match field {
+                    SubsectionField::ControlArea => 0x0,
+                }
+            }
+            _ => unreachable!(),
+        }) as *mut T
+    }
+}
+
+///
+/// # Get `_CONTROL_AREA` Field
+///
+/// Gets pointer to field of `_CONTROL_AREA` depending on NT version.
+///
+/// ## Arguments
+/// * `field` - Field you want to acquire pointer to. See [`ControlAreaField`]
+/// * `control_area` - Control area to get pointer from.
+///
+/// ## Panic
+/// - This function panics if the NT version is not supported.
+///
+/// ## Returns
+/// - Absolute **pointer** to the field, in [`T`].
+pub(crate) unsafe fn get_control_area_field<T: 'static>(
+    field: ControlAreaField,
+    control_area: PVOID,
+) -> *mut T {
+    unsafe {
+        (control_area as *mut u8).byte_offset(match (NT_BUILD, NT_UBR) {
+            (26100, 6584) /* 25H2 */ => {
+                match field {
+                    ControlAreaField::FilePointer => 0x40,
+                }
+            }
+            _ => unreachable!(),
+        }) as *mut T
+    }
+}
+
+///
+/// # Get `_FILE_OBJECT` Field
+///
+/// Gets pointer to field of `_FILE_OBJECT` depending on NT version.
+///
+/// ## Arguments
+/// * `field` - Field you want to acquire pointer to. See [`FileObjectField`]
+/// * `file` - File object to get pointer from.
+///
+/// ## Panic
+/// - This function panics if the NT version is not supported.
+///
+/// ## Returns
+/// - Absolute **pointer** to the field, in [`T`].
+pub(crate) unsafe fn get_file_object_field<T: 'static>(field: FileObjectField, file: PVOID) -> *mut T {
+    unsafe {
+        (file as *mut u8).byte_offset(match (NT_BUILD, NT_UBR) {
+            (26100, 6584) /* 25H2 */ => {
+                match field {
+                    FileObjectField::FileName => 0x58,
+                }
+            }
+            _ => unreachable!(),
+        }) as *mut T
+    }
+}
+
 #[repr(transparent)]
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct ObjectHeader(pub *mut u64);
@@ -372,7 +512,9 @@ pub enum NtProcedure {
     ExpLookupHandleTableEntry,
     ExCreateHandle,
     KiSystemCall64,
-    KiGeneralProtectionFault
+    KiGeneralProtectionFault,
+    PsSuspendThread,
+    PsResumeThread,
 }
 
 pub enum LogonSessionField {
@@ -403,11 +545,39 @@ pub enum EThreadField {
     FirstArgument
 }
 
+pub enum VadField {
+    Left,
+    Right,
+    StartingVpn,
+    EndingVpn,
+    StartingVpnHigh,
+    EndingVpnHigh,
+    CommitChargeHigh,
+    VadFlags,
+    VadFlags1,
+    /// Only in `_MMVAD`, not `_MMVAD_SHORT`. Check [`VadFlags::private_memory`](crate::nt::mm::vad::VadFlags) first.
+    Subsection,
+}
+
+pub enum SubsectionField {
+    ControlArea,
+}
+
+pub enum ControlAreaField {
+    /// `_EX_FAST_REF`. Mask off the low bits.
+    FilePointer,
+}
+
+pub enum FileObjectField {
+    FileName,
+}
+
 /// TODO: Document what those return
 pub enum EProcessField {
     Lock,
     CreateTime,
     Token,
+    AddressCreationLock,
     SectionObject,
     SectionBaseAddress,
     Peb,
@@ -424,4 +594,6 @@ pub enum EProcessField {
     Pad,
     DirectoryTableBase,
     UserDirectoryTableBase,
+    InheritedFromUniqueProcessId,
+    ImageFileName,
 }
@@ -1,11 +1,24 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 
-use crate::nt::{get_object_body, get_object_header, ObjectBody, ObjectHeader};
+use crate::nt::process::NtProcess;
+use crate::nt::{
+    get_file_object_field, get_object_body, get_object_header, FileObjectField, ObjectBody,
+    ObjectHeader,
+};
 use crate::utils::intrin::{interlocked_decrement, interlocked_increment};
-use crate::win::HANDLE;
+use crate::win::unicode_string::UnicodeString;
+use crate::win::{
+    Boolean, ExEnumHandleTable, ExfUnblockPushLock, HANDLE, MmIsAddressValid, NtStatus, ObCloseHandle,
+    ObGetObjectType, ObOpenObjectByPointer, ObQueryNameString, ObReferenceObjectSafe, ObfDereferenceObject,
+    ProcessorMode, PVOID, UNICODE_STRING,
+};
 use crate::win::{ExpLookupHandleTableEntry, PHANDLE_TABLE, _EXHANDLE};
+use alloc::vec;
+use alloc::vec::Vec;
 use bit_field::BitField;
 use core::ffi::c_void;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicU64, Ordering};
 use hxposed_core::hxposed::Handle;
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
 
@@ -18,10 +31,20 @@ pub struct NtHandle;
 
 pub type HandleTableEntry = *mut u64;
 
+/// A handle as [`NtHandle::walk`] saw it, while its entry was locked.
+pub struct HandleInfo {
+    pub handle: Handle,
+    /// Referenced. Outlives the handle, if it has to.
+    pub object: NtObject,
+    pub granted_access: u32,
+    pub attributes: u8,
+}
+
 impl Drop for NtObject {
     fn drop(&mut self) {
+        // not a raw decrement. if this was the last reference, the object manager has to free it.
         unsafe {
-            Self::decrement_ref_count(get_object_header(self.object_addr as _) as _);
+            ObfDereferenceObject(self.object_addr.0 as _);
         }
     }
 }
@@ -34,6 +57,11 @@ impl NtObject {
         Self { object_addr: ptr }
     }
 
+    /// Takes over a reference the caller already holds, e.g. from `ObReferenceObjectSafe`.
+    pub unsafe fn from_referenced(ptr: ObjectBody) -> Self {
+        Self { object_addr: ptr }
+    }
+
     pub unsafe fn increment_ref_count(obj_header: ObjectHeader) {
         // actually, fetch should be interlocked too
         HxLogger::serial_log(LogType::Trace, LogEvent::IncrementRefCount(obj_header.0 as _, *obj_header.0));
@@ -80,28 +108,143 @@ impl NtObject {
         Some(Self::from_handle_entry(entry))
     }
 
-    // cannot be used due to occurring apc issues. deprecated
-    /*pub fn create_handle(object: *mut T, table: PHANDLE_TABLE) -> Result<HANDLE, ()> {
-        let handle = unsafe { ExCreateHandle(table, get_object_header(object as _) as _) };
+    ///
+    /// # Get Object Type
+    ///
+    /// Checks that `object` looks like an object body, and gets its `_OBJECT_TYPE`.
+    ///
+    /// ## Remarks
+    /// - Against garbage, not against races. A freed object nobody reused yet passes.
+    ///
+    /// ## Return
+    /// * [`Some`] - `_OBJECT_TYPE` of the object.
+    /// * [`None`] - Not an aligned kernel address, header not readable, no references, or no type behind it.
+    pub fn get_object_type(object: ObjectBody) -> Option<PVOID> {
+        // handle entries keep the header shifted by 4, with the upper bits implied.
+        // so anything else couldn't even be written there.
+        let address = object.0 as u64;
+        if address < 0xFFFF_8000_0000_0000 || address & 0xF != 0 {
+            return None;
+        }
 
-        let exhandle = _EXHANDLE { Value: handle as _ };
+        unsafe {
+            let header = get_object_header(object);
+            // header is 0x30 bytes, so it can start on the page before the body.
+            for page in [header.0 as PVOID, object.0 as PVOID] {
+                if let Boolean::False = MmIsAddressValid(page) {
+                    return None;
+                }
+            }
 
-        // so we need to look up the entry again, because ExCreateHandle grants 0 accesses.
-        let entry = unsafe{&mut*Self::get_handle_entry(exhandle, table).unwrap()};
-        Self::upgrade_handle(entry, Self::HANDLE_ALL_ACCESS)?;
+            // PointerCount. an object nobody references is on its way out, or never was one.
+            if *(header.0 as *const i64) <= 0 {
+                return None;
+            }
 
-        let object_pointer = entry.get_bits(20..64);
-        let object_header = (object_pointer << 4 | 0xffff000000000000) as *mut c_void;
-        let object = unsafe { get_object_body(object_header) };
+            let object_type = ObGetObjectType(object.0 as _);
+            match object_type.is_null() {
+                true => None,
+                false => match MmIsAddressValid(object_type) {
+                    Boolean::True => Some(object_type),
+                    Boolean::False => None,
+                },
+            }
+        }
+    }
+
+    ///
+    /// # Get Type Name
+    ///
+    /// `_OBJECT_TYPE.Name` of the object. `Process`, `File`, `Key` and such.
+    ///
+    pub fn get_type_name(&self) -> UnicodeString {
+        // _OBJECT_TYPE.Name. right after TypeList, since forever.
+        const OBJECT_TYPE_NAME: usize = 0x10;
 
-        // we have to increment BOTH handle and pointer count since ExCreateHandle does none of that
         unsafe {
-            Self::increment_handle_count(object);
-            Self::increment_ref_count(object);
+            let object_type = ObGetObjectType(self.object_addr.0 as _);
+            UnicodeString::from_unicode_string(
+                &*(object_type.byte_add(OBJECT_TYPE_NAME) as *const UNICODE_STRING),
+            )
+        }
+    }
+
+    ///
+    /// # Get Name
+    ///
+    /// Asks the object manager for the name of the object.
+    ///
+    /// ## Remarks
+    /// - Files are not asked. `ObQueryNameString` goes to the file system for them, which can block forever on pipes.
+    ///   `_FILE_OBJECT.FileName` is used instead, which is relative to the volume.
+    ///
+    /// ## Return
+    /// * [`Some`] - The name.
+    /// * [`None`] - Object has no name.
+    pub fn get_name(&self) -> Option<UnicodeString> {
+        // names are limited to u16 bytes anyway.
+        const MAX_NAME: u32 = 0x10000 + size_of::<UNICODE_STRING>() as u32;
+
+        if self.get_type_name().to_alloc_string() == "File" {
+            let name = unsafe {
+                &*get_file_object_field::<UNICODE_STRING>(
+                    FileObjectField::FileName,
+                    self.object_addr.0 as _,
+                )
+            };
+            return match name.Buffer.is_null() || name.Length == 0 {
+                true => None,
+                false => Some(UnicodeString::from_unicode_string(name)),
+            };
+        }
+
+        // u64s to keep the UNICODE_STRING at the start aligned.
+        let mut buffer = vec![0u64; MAX_NAME as usize / 8];
+        let mut length = 0u32;
+        let info = buffer.as_mut_ptr() as *mut UNICODE_STRING;
+
+        match unsafe { ObQueryNameString(self.object_addr.0 as _, info, MAX_NAME, &mut length) } {
+            NtStatus::Success => {}
+            _ => return None,
         }
 
-        Ok(handle as _)
-    }*/
+        let name = unsafe { &*info };
+        match name.Buffer.is_null() || name.Length == 0 {
+            true => None,
+            false => Some(UnicodeString::from_unicode_string(name)),
+        }
+    }
+
+    ///
+    /// # Create Handle
+    ///
+    /// Opens a handle to `object` in the handle table of the current process.
+    ///
+    /// ## Remarks
+    /// - Goes through `ObOpenObjectByPointer` in kernel mode. So the object manager does the referencing,
+    ///   and no access checks are made. `ExCreateHandle` did neither, and ran into APC issues.
+    /// - Open procedures still run. Handles to protected processes are not stripped for kernel mode.
+    ///
+    /// ## Return
+    /// * [`HANDLE`] - The handle. Owned by the current process.
+    /// * [`NtStatus`] - `ObOpenObjectByPointer` failed.
+    pub fn create_handle(object: PVOID, access_mask: u32) -> Result<HANDLE, NtStatus> {
+        let mut handle = HANDLE::default();
+        match unsafe {
+            ObOpenObjectByPointer(
+                object,
+                0,
+                null_mut(),
+                access_mask,
+                null_mut(),
+                ProcessorMode::KernelMode,
+                &mut handle,
+            )
+        } {
+            NtStatus::Success => Ok(handle),
+            err => Err(err),
+        }
+    }
 }
 
 impl NtHandle {
@@ -117,6 +260,76 @@ impl NtHandle {
         unsafe { entry.add(1).read_unaligned() }.get_bits(0..25) as _
     }
 
+    /// `OBJ_PROTECT_CLOSE`, `OBJ_INHERIT` and `OBJ_AUDIT_OBJECT_CLOSE`. In that order.
+    pub fn get_attributes(entry: HandleTableEntry) -> u8 {
+        unsafe { *entry }.get_bits(17..20) as _
+    }
+
+    ///
+    /// # Walk
+    ///
+    /// Every handle in `table`, with a reference to its object.
+    ///
+    /// ## Remarks
+    /// - Goes through `ExEnumHandleTable`, which hands each entry over locked. The object is referenced
+    ///   with `ObReferenceObjectSafe` before the entry is unlocked, so it can't be freed under us.
+    /// - Entries whose object is already being deleted are skipped.
+    /// - Nothing else is done under the lock. Names are for the caller to ask, after.
+    ///
+    /// ## Return
+    /// * Handles in use. In handle order.
+    pub fn walk(table: PHANDLE_TABLE) -> Vec<HandleInfo> {
+        // a process that exited already swept its table.
+        if table.is_null() {
+            return Vec::new();
+        }
+
+        let mut handles = Vec::<HandleInfo>::new();
+        unsafe {
+            ExEnumHandleTable(
+                table,
+                Self::walk_callback as _,
+                &mut handles as *mut _ as _,
+                null_mut(),
+            );
+        }
+        handles
+    }
+
+    unsafe extern "C" fn walk_callback(
+        table: PHANDLE_TABLE,
+        entry: HandleTableEntry,
+        handle: HANDLE,
+        context: PVOID,
+    ) -> Boolean {
+        let handles = &mut *(context as *mut Vec<HandleInfo>);
+        let object = Self::get_object_ptr(entry);
+
+        // the handle's own reference can't go while we hold the entry. but the object may be on its way out already.
+        if let Boolean::True = ObReferenceObjectSafe(object.0 as _) {
+            handles.push(HandleInfo {
+                handle: handle as _,
+                object: NtObject::from_referenced(object),
+                granted_access: Self::get_granted_access(entry),
+                attributes: Self::get_attributes(entry),
+            });
+        }
+
+        Self::unlock_entry(table, entry);
+
+        // true stops the enumeration.
+        Boolean::False
+    }
+
+    /// `ExUnlockHandleTableEntry`, which is not exported. Sets Unlocked back, and wakes whoever waits on the table.
+    unsafe fn unlock_entry(table: PHANDLE_TABLE, entry: HandleTableEntry) {
+        AtomicU64::from_ptr(entry).fetch_add(1, Ordering::Release);
+
+        if (*table).HandleContentionEvent != 0 {
+            ExfUnblockPushLock(&raw mut (*table).HandleContentionEvent, null_mut());
+        }
+    }
+
     pub fn set_object_ptr(entry: HandleTableEntry, ptr: ObjectBody) {
         let header = unsafe { get_object_header(ptr as _) };
         let old_object = unsafe { get_object_header(Self::get_object_ptr(entry) as _) };
@@ -149,6 +362,29 @@ impl NtHandle {
         }
     }
 
+    /// Only ever removes rights. The ones not in `access_mask` go.
+    pub fn downgrade_handle(entry: HandleTableEntry, access_mask: u32) {
+        Self::upgrade_handle(entry, Self::get_granted_access(entry) & access_mask);
+    }
+
+    ///
+    /// # Close
+    ///
+    /// Closes `handle` in the handle table of `process`, the way its owner would.
+    ///
+    /// ## Remarks
+    /// - Attaches to `process` and goes through `ObCloseHandle` in kernel mode. So the close procedure runs,
+    ///   the table's count drops, the slot goes back on the free list and the object is freed if that was the last of it.
+    /// - Kernel mode bugchecks on `OBJ_PROTECT_CLOSE`, instead of refusing. Check [`Self::get_attributes`] before.
+    ///
+    /// ## Return
+    /// * [`NtStatus::Success`] - Closed.
+    /// * [`NtStatus::InvalidHandle`] - No such handle.
+    pub fn close(process: &NtProcess, handle: Handle) -> NtStatus {
+        let _ctx = process.begin_context();
+        unsafe { ObCloseHandle(handle as _, ProcessorMode::KernelMode) }
+    }
+
     pub fn get_handle_entry(handle: Handle, table: PHANDLE_TABLE) -> Option<HandleTableEntry> {
         let entry = unsafe { ExpLookupHandleTableEntry(table, _EXHANDLE { Value: handle }) };
         if entry.is_null() { None } else { Some(entry) }
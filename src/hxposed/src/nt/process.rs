@@ -1,6 +1,11 @@
 use crate::nt::context::ApcProcessContext;
-use crate::nt::lock::pushlock::PushLock;
+use crate::nt::guard::hxguard::HxGuard;
+use crate::nt::ldr::NtModule;
+use crate::nt::lock::pushlock::{LockType, PushLock, PushLockGuard};
+use crate::nt::mm::user::{read_user, read_user_slice, read_user_string};
+use crate::nt::mm::vad::NtVad;
 use crate::nt::object::NtObject;
+use crate::nt::thread::NtThread;
 use crate::nt::{EProcessField, EThreadField, get_eprocess_field, get_ethread_field};
 use crate::objects::ObjectTracker;
 use crate::utils::danger::DangerPtr;
@@ -8,7 +13,8 @@ use crate::utils::handlebox::HandleBox;
 use crate::win::unicode_string::UnicodeString;
 use crate::win::{
     IoGetCurrentProcess, LIST_ENTRY, NtStatus, PACCESS_TOKEN, PEPROCESS, PETHREAD, PHANDLE_TABLE,
-    PsGetProcessId, PsGetThreadId, PsLookupProcessByProcessId, PsTerminateProcess, UNICODE_STRING,
+    PMMVAD, PsGetProcessId, PsGetProcessWow64Process, PsGetThreadId, PsLookupProcessByProcessId, PsTerminateProcess, UNICODE_STRING,
+    ZwQuerySystemInformation,
 };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
@@ -16,7 +22,7 @@ use core::hash::{Hash, Hasher};
 use core::ops::BitAnd;
 use hxposed_core::hxposed::requests::memory::Pa;
 use hxposed_core::services::types::process_fields::{
-    MitigationOptions, ProcessProtection, ProcessSignatureLevels,
+    MitigationOptions, MitigationOptions3, ProcessProtection, ProcessSignatureLevels,
 };
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
 
@@ -145,9 +151,14 @@ impl NtProcess {
             get_eprocess_field::<u64>(EProcessField::Pad, self.nt_process).byte_offset(16)
         };
 
+        // nothing has written the hash yet. work it out, then look the caller up by it.
+        let hash = self.get_path_hashcode();
+        unsafe { hash_ptr.write(hash) };
+
+        let permissions = HxGuard::get_permissions(hash);
+
         unsafe {
-            (tracker_ptr as *mut u64).write(ObjectTracker::alloc_new() as _);
-            hash_ptr.write(Self::get_path_hash(self))
+            (tracker_ptr as *mut u64).write(ObjectTracker::alloc_new(permissions) as _);
         };
 
         Ok(())
@@ -213,7 +224,7 @@ impl NtProcess {
         unsafe { *get_eprocess_field::<u64>(EProcessField::Pad, self.nt_process).byte_offset(16) }
     }
 
-    fn get_path_hashcode(&self) -> u64 {
+    pub fn get_path_hashcode(&self) -> u64 {
         let path = self.get_nt_path();
         wyhash::wyhash(
             unsafe { core::slice::from_raw_parts(path.as_ptr() as *const u8, path.len() * 2) },
@@ -229,6 +240,207 @@ impl NtProcess {
         unsafe { *get_eprocess_field::<PHANDLE_TABLE>(EProcessField::ObjectTable, self.nt_process) }
     }
 
+    pub fn get_create_time(&self) -> u64 {
+        unsafe { *get_eprocess_field::<u64>(EProcessField::CreateTime, self.nt_process) }
+    }
+
+    pub fn get_parent_id(&self) -> u32 {
+        unsafe {
+            *get_eprocess_field::<u64>(EProcessField::InheritedFromUniqueProcessId, self.nt_process)
+                as _
+        }
+    }
+
+    pub fn get_image_file_name(&self) -> [u8; 15] {
+        unsafe { *get_eprocess_field::<[u8; 15]>(EProcessField::ImageFileName, self.nt_process) }
+    }
+
+    ///
+    /// # Get Modules
+    ///
+    /// Walks the loader lists of the PEB. Then of the 32-bit PEB, if the process is WoW64.
+    ///
+    /// ## Remarks
+    /// - Attaches to the process. See [`NtModule::walk`].
+    ///
+    pub fn get_modules(&self) -> Vec<NtModule> {
+        let peb = unsafe { *get_eprocess_field::<u64>(EProcessField::Peb, self.nt_process) };
+        let peb32 = unsafe { PsGetProcessWow64Process(self.nt_process) } as u64;
+
+        let _ctx = self.begin_context();
+        let mut modules = match peb {
+            0 => Vec::new(),
+            x => NtModule::walk(x, false),
+        };
+        if peb32 != 0 {
+            modules.extend(NtModule::walk(peb32, true));
+        }
+
+        modules
+    }
+
+    ///
+    /// # Get Process Parameter
+    ///
+    /// Reads a string out of `PEB->ProcessParameters`.
+    ///
+    /// ## Remarks
+    /// - Attaches to the process. Reads are SEH guarded, see [`read_user`].
+    ///
+    /// ## Return
+    /// * [`Some`] - The string. Empty if the process has no PEB or parameters, like `System`.
+    /// * [`None`] - Something on the way faulted.
+    pub fn get_process_parameter(&self, parameter: ProcessParameter) -> Option<Vec<u16>> {
+        const CURRENT_DIRECTORY: u64 = 0x38;
+        const COMMAND_LINE: u64 = 0x70;
+        const WINDOW_TITLE: u64 = 0xb0;
+
+        let _ctx = self.begin_context();
+        let parameters = match self.get_process_parameters()? {
+            0 => return Some(Vec::new()),
+            x => x,
+        };
+
+        let string = match parameter {
+            ProcessParameter::CommandLine => COMMAND_LINE,
+            ProcessParameter::CurrentDirectory => CURRENT_DIRECTORY,
+            ProcessParameter::WindowTitle => WINDOW_TITLE,
+        };
+
+        read_user_string(
+            read_user::<u16>(parameters + string)?,
+            read_user::<u64>(parameters + string + 8)?,
+        )
+    }
+
+    ///
+    /// # Get Environment
+    ///
+    /// Reads the environment block out of `PEB->ProcessParameters`. Null separated, double null terminated.
+    ///
+    /// ## Arguments
+    /// * `limit` - Most characters to read. Blocks are limited to 32767, but processes write their own.
+    ///
+    /// ## Remarks
+    /// - Same as [`Self::get_process_parameter`].
+    /// - A block cut at `limit` is not terminated, and may end in the middle of a variable.
+    ///
+    /// ## Return
+    /// * [`Some`] - The block, and whether it was cut. Empty if the process has no PEB or parameters.
+    /// * [`None`] - Something on the way faulted.
+    pub fn get_environment(&self, limit: usize) -> Option<(Vec<u16>, bool)> {
+        const ENVIRONMENT: u64 = 0x80;
+        const ENVIRONMENT_SIZE: u64 = 0x3f0;
+
+        let _ctx = self.begin_context();
+        let parameters = match self.get_process_parameters()? {
+            0 => return Some((Vec::new(), false)),
+            x => x,
+        };
+
+        let size = read_user::<u64>(parameters + ENVIRONMENT_SIZE)? / 2;
+        let count = size.min(limit as u64);
+        match read_user::<u64>(parameters + ENVIRONMENT)? {
+            0 => Some((Vec::new(), false)),
+            x => Some((read_user_slice::<u16>(x, count as _)?, count < size)),
+        }
+    }
+
+    /// `PEB->ProcessParameters`, 0 if there is none. Must be attached.
+    fn get_process_parameters(&self) -> Option<u64> {
+        // RTL_USER_PROCESS_PARAMETERS. user mode ABI, doesn't move between builds.
+        const PEB_PROCESS_PARAMETERS: u64 = 0x20;
+
+        match unsafe { *get_eprocess_field::<u64>(EProcessField::Peb, self.nt_process) } {
+            0 => Some(0),
+            peb => read_user::<u64>(peb + PEB_PROCESS_PARAMETERS),
+        }
+    }
+
+    ///
+    /// # Lock Address Space
+    ///
+    /// Takes `AddressCreationLock` shared. Nothing gets mapped or unmapped until the guard drops.
+    /// Kernel APCs are off meanwhile. See [`PushLockGuard`].
+    ///
+    /// ## Return
+    /// * [`PushLockGuard`] - Releases on drop.
+    pub fn lock_address_space(&self) -> PushLockGuard {
+        PushLockGuard::new(
+            unsafe { get_eprocess_field::<u64>(EProcessField::AddressCreationLock, self.nt_process) },
+            LockType::Shared,
+        )
+    }
+
+    ///
+    /// # Get Vads
+    ///
+    /// Walks `VadRoot`. See [`NtVad::walk`].
+    ///
+    /// ## Remarks
+    /// - Hold [`Self::lock_address_space`] from before this until you're done with them.
+    ///
+    pub fn get_vads(&self) -> (Vec<NtVad>, bool) {
+        NtVad::walk(unsafe { *get_eprocess_field::<PMMVAD>(EProcessField::VadRoot, self.nt_process) })
+    }
+
+    ///
+    /// # Get Active Processes
+    ///
+    /// Takes a snapshot of the process ids with `ZwQuerySystemInformation`, then looks each one up.
+    ///
+    /// ## Remarks
+    /// - Every process is referenced. One exiting meanwhile stays readable until it's dropped.
+    /// - Ones gone by the time we look them up are left out. So is the idle process.
+    ///
+    /// ## Return
+    /// * [`Vec<NtProcess>`] - Processes. Owning.
+    pub fn get_active_processes() -> Vec<NtProcess> {
+        // SYSTEM_PROCESS_INFORMATION. user mode ABI, doesn't move between builds.
+        const SYSTEM_PROCESS_INFORMATION: u32 = 5;
+        const NEXT_ENTRY_OFFSET: usize = 0x0;
+        const UNIQUE_PROCESS_ID: usize = 0x50;
+
+        // u64s, so entries are aligned.
+        let mut buffer = Vec::<u64>::new();
+        let mut length = 0u32;
+        loop {
+            match unsafe {
+                ZwQuerySystemInformation(
+                    SYSTEM_PROCESS_INFORMATION,
+                    buffer.as_mut_ptr() as _,
+                    (buffer.len() * 8) as _,
+                    &mut length,
+                )
+            } {
+                NtStatus::Success => break,
+                // more processes by the time we ask again. leave some room.
+                NtStatus::InfoLengthMismatch => buffer.resize(length as usize / 8 + 0x200, 0),
+                _ => return Vec::new(),
+            }
+        }
+
+        let base = buffer.as_ptr() as *const u8;
+        let mut processes = Vec::new();
+        let mut offset = 0usize;
+
+        loop {
+            let entry = unsafe { base.add(offset) };
+            let id = unsafe { *(entry.add(UNIQUE_PROCESS_ID) as *const u64) };
+
+            if let Some(process) = Self::from_id(id) {
+                processes.push(process);
+            }
+
+            match unsafe { *(entry.add(NEXT_ENTRY_OFFSET) as *const u32) } {
+                0 => break,
+                x => offset += x as usize,
+            }
+        }
+
+        processes
+    }
+
     pub fn get_protection(&self) -> ProcessProtection {
         unsafe {
             *get_eprocess_field::<ProcessProtection>(EProcessField::Protection, self.nt_process)
@@ -253,6 +465,15 @@ impl NtProcess {
         }
     }
 
+    pub fn get_mitigations3(&self) -> MitigationOptions3 {
+        unsafe {
+            *get_eprocess_field::<MitigationOptions3>(
+                EProcessField::MitigationFlags3,
+                self.nt_process,
+            )
+        }
+    }
+
     pub fn get_token(&self) -> PACCESS_TOKEN {
         // 0xF to clear EX_FAST_REF reference count
         unsafe { *get_eprocess_field::<u64>(EProcessField::Token, self.nt_process)}.bitand(!0xF) as _
@@ -288,6 +509,17 @@ impl NtProcess {
         unsafe { ptr.write(mitigations) }
     }
 
+    pub fn set_mitigations3(&mut self, mitigations: MitigationOptions3) {
+        let ptr = unsafe {
+            get_eprocess_field::<MitigationOptions3>(
+                EProcessField::MitigationFlags3,
+                self.nt_process,
+            )
+        };
+
+        unsafe { ptr.write(mitigations) }
+    }
+
     pub fn set_token(&mut self, token: PACCESS_TOKEN) {
         let ptr = unsafe { get_eprocess_field::<u64>(EProcessField::Token, self.nt_process) };
 
@@ -335,10 +567,83 @@ impl NtProcess {
         thread_numbers
     }
 
-    pub fn kill(self, exit_code: NtStatus) -> Result<(), NtStatus> {
+    // ids from the list may be gone, or someone else's by now.
+    fn get_own_threads(&self) -> impl Iterator<Item = NtThread> {
+        self.get_threads()
+            .into_iter()
+            .filter_map(|x| NtThread::from_id(x as _))
+            .filter(|x| x.belongs_to(self))
+    }
+
+    // protection is checked when opening handles. we have none, so it doesn't apply.
+    pub fn kill(&self, exit_code: u32) -> Result<(), NtStatus> {
         match unsafe { PsTerminateProcess(self.nt_process, exit_code) } {
             NtStatus::Success => Ok(()),
             err => Err(err),
         }
     }
+
+    ///
+    /// # Suspend
+    ///
+    /// Suspends every thread of the process, one at a time. See [`NtThread::suspend`].
+    ///
+    /// ## Remarks
+    /// - Threads that start meanwhile are not suspended. Ones exiting meanwhile are skipped.
+    /// - So are ids reused by another process's thread since the snapshot.
+    /// - If any other fails, the ones already suspended are resumed.
+    ///
+    /// ## Return
+    /// * [`u32`] - Highest suspend count the threads had before. 0 means it was running.
+    /// * [`NtStatus`] - Suspending a thread failed.
+    pub fn suspend(&self) -> Result<u32, NtStatus> {
+        let mut suspended = Vec::<NtThread>::new();
+        let mut previous = 0;
+
+        for thread in self.get_own_threads() {
+            match thread.suspend() {
+                Ok(x) => {
+                    previous = previous.max(x);
+                    suspended.push(thread);
+                }
+                Err(NtStatus::ThreadIsTerminating) => continue,
+                Err(err) => {
+                    suspended.iter().for_each(|x| {
+                        let _ = x.resume();
+                    });
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(previous)
+    }
+
+    ///
+    /// # Resume
+    ///
+    /// Undoes one [`Self::suspend`]. See [`NtThread::resume`].
+    ///
+    /// ## Return
+    /// * [`u32`] - Highest suspend count the threads had before.
+    /// * [`NtStatus`] - Resuming a thread failed. The ones before it stay resumed.
+    pub fn resume(&self) -> Result<u32, NtStatus> {
+        let mut previous = 0;
+
+        for thread in self.get_own_threads() {
+            match thread.resume() {
+                Ok(x) => previous = previous.max(x),
+                Err(NtStatus::ThreadIsTerminating) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(previous)
+    }
+}
+
+pub enum ProcessParameter {
+    CommandLine,
+    CurrentDirectory,
+    WindowTitle,
 }
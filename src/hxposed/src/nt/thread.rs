@@ -5,13 +5,15 @@ use crate::nt::process::NtProcess;
 use crate::nt::{EThreadField, get_ethread_field};
 use crate::utils::handlebox::HandleBox;
 use crate::win::{
-    Boolean, HANDLE, KeGetCurrentThread, NtStatus, PACCESS_TOKEN, PETHREAD, PVOID,
-    PsCreateSystemThread, PsGetThreadId, PsLookupThreadByThreadId, PsReferenceImpersonationToken,
-    PspTerminateThread, SecurityImpersonationLevel, ThreadAccessRights,
+    Boolean, HANDLE, KeGetCurrentThread, NtStatus, PACCESS_TOKEN, PETHREAD, PVOID, ProcessorMode,
+    PsCreateSystemThread, PsGetContextThread, PsGetThreadId, PsGetThreadProcess, PsLookupThreadByThreadId, PsReferenceImpersonationToken,
+    PsResumeThread, PsSuspendThread, PspTerminateThread, SecurityImpersonationLevel,
+    ThreadAccessRights,
 };
 use bit_field::BitField;
 use core::hash::{Hash, Hasher};
 use core::ptr::null_mut;
+use hxposed_core::services::types::thread_fields::ThreadContext;
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
 
 pub struct NtThread {
@@ -155,4 +157,55 @@ impl NtThread {
             err => Err(err),
         }
     }
+
+    ///
+    /// # Belongs To
+    ///
+    /// Whether the thread is one of `process`'s. Ids are reused, so check this on threads looked up by id.
+    ///
+    pub fn belongs_to(&self, process: &NtProcess) -> bool {
+        unsafe { PsGetThreadProcess(self.nt_thread) == process.nt_process }
+    }
+
+    pub fn suspend(&self) -> Result<u32, NtStatus> {
+        let mut previous = 0u32;
+        match unsafe { PsSuspendThread(self.nt_thread, &mut previous) } {
+            NtStatus::Success => Ok(previous),
+            err => Err(err),
+        }
+    }
+
+    ///
+    /// # Get Context
+    ///
+    /// Gets the user mode registers through `PsGetContextThread`.
+    ///
+    /// ## Remarks
+    /// - Waits for the thread to run a special kernel APC. Must be called at `PASSIVE_LEVEL`, from the async worker.
+    ///
+    /// ## Return
+    /// * [`ThreadContext`] - With [`ThreadContext::CONTEXT_ALL`].
+    /// * [`NtStatus`] - `PsGetContextThread` failed.
+    pub fn get_context(&self) -> Result<ThreadContext, NtStatus> {
+        let mut context = ThreadContext::new(ThreadContext::CONTEXT_ALL);
+        // kernel mode: our buffer is kernel memory, no probing. the context we get is still the user mode one.
+        match unsafe {
+            PsGetContextThread(
+                self.nt_thread,
+                &mut context as *mut _ as _,
+                ProcessorMode::KernelMode,
+            )
+        } {
+            NtStatus::Success => Ok(context),
+            err => Err(err),
+        }
+    }
+
+    pub fn resume(&self) -> Result<u32, NtStatus> {
+        let mut previous = 0u32;
+        match unsafe { PsResumeThread(self.nt_thread, &mut previous) } {
+            NtStatus::Success => Ok(previous),
+            err => Err(err),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::nt::callback::NtCallback;
 
+use crate::nt::mm::mdl::MemoryDescriptor;
 use crate::nt::mm::rmd::RawMemoryDescriptor;
 use crate::nt::process::NtProcess;
 use crate::nt::thread::NtThread;
@@ -10,6 +11,9 @@ use alloc::boxed::Box;
 use alloc::vec;
 use alloc::vec::Vec;
 use hxposed_core::hxposed::*;
+use hxposed_core::hxposed::output::OutputBufferHeader;
+use hxposed_core::hxposed::permissions::PluginPermissions;
+use hxposed_core::hxposed::requests::memory::Va;
 use spin::mutex::SpinMutex;
 
 pub static CALLER_PROCESSES: SpinMutex<Vec<NtProcess>> = SpinMutex::new(Vec::new());
@@ -21,6 +25,23 @@ pub struct ObjectTracker {
     pub tokens: Vec<NtToken>,
     pub processes: Vec<NtProcess>,
     pub rmds: Vec<RawMemoryDescriptor>,
+    pub output: SpinMutex<Option<OutputBuffer>>,
+    // every output buffer we allocated, replaced ones too. unmapped and freed when the process goes.
+    pub output_memory: Vec<MemoryDescriptor>,
+    pub permissions: PluginPermissions,
+}
+
+///
+/// # Output Buffer
+///
+/// The registered output buffer of a process. See [`OutputBufferHeader`].
+///
+#[derive(Copy, Clone, Debug)]
+pub struct OutputBuffer {
+    pub system_va: Va,
+    pub user_va: u64,
+    // ours. the one in the buffer is only a mirror, caller can scribble on it all it wants.
+    pub header: OutputBufferHeader,
 }
 
 impl Drop for ObjectTracker {
@@ -33,7 +54,7 @@ impl Drop for ObjectTracker {
 }
 
 impl ObjectTracker {
-    pub fn alloc_new() -> *mut Self {
+    pub fn alloc_new(permissions: PluginPermissions) -> *mut Self {
         let mut me = DangerPtr {
             ptr: Box::into_raw(Self::alloc()),
         };
@@ -43,6 +64,9 @@ impl ObjectTracker {
         me.tokens = Vec::new();
         me.processes = vec![NtProcess::current()];
         me.rmds = Vec::new();
+        me.output = SpinMutex::new(None);
+        me.output_memory = Vec::new();
+        me.permissions = permissions;
 
         me.ptr
     }
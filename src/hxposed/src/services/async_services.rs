@@ -0,0 +1,143 @@
+use crate::nt::event::NtEvent;
+use crate::nt::mm::mdl::MemoryDescriptor;
+use crate::nt::process::NtProcess;
+use crate::nt::thread::NtThread;
+use crate::services::dispatch;
+use crate::win::{KeGetCurrentThread, NtStatus, PVOID};
+use alloc::collections::VecDeque;
+use core::sync::atomic::{AtomicU64, Ordering};
+use hxposed_core::hxposed::AsyncCookie;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::requests::async_call::AsyncInfo;
+use hxposed_core::hxposed::responses::async_call::{AsyncCompletion, AsyncResponse};
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+use spin::Once;
+use spin::mutex::SpinMutex;
+
+///
+/// # Async Work
+///
+/// A slow call waiting for the worker.
+///
+struct AsyncWork {
+    cookie: AsyncCookie,
+    process: NtProcess,
+    request: HxRequest,
+    event: NtEvent,
+    /// Keeps the completion locked, and mapped at `completion_va`.
+    _completion: MemoryDescriptor,
+    completion_va: usize,
+}
+
+static QUEUE: SpinMutex<VecDeque<AsyncWork>> = SpinMutex::new(VecDeque::new());
+static WAKE: Once<NtEvent> = Once::new();
+static NEXT_COOKIE: AtomicU64 = AtomicU64::new(1);
+/// `_ETHREAD` of the worker. Pointers aren't `Sync`.
+static WORKER: Once<usize> = Once::new();
+
+///
+/// # Init
+///
+/// Starts the worker thread that executes slow calls.
+///
+pub(crate) fn init() -> Result<(), NtStatus> {
+    WAKE.call_once(NtEvent::new);
+    NtThread::create(worker, None)
+}
+
+///
+/// # Queue
+///
+/// Queues a slow call to the worker. The caller is notified through the [`AsyncInfo`] in `extended_arg4`.
+///
+/// ## Return
+/// * [`AsyncResponse`] - Cookie of the queued call.
+/// * [`NotFoundReason::Event`] - Event handle is invalid.
+/// * [`HxResponse::invalid_params`] - Completion buffer is inaccessible (1), or worker isn't running (0).
+pub(crate) fn queue(request: &HxRequest) -> HxResponse {
+    let wake = match WAKE.get() {
+        Some(x) => x,
+        None => return HxResponse::invalid_params(0),
+    };
+
+    let info = AsyncInfo::from_raw(request.extended_arg4);
+
+    let event = match NtEvent::from_handle(info.event as _) {
+        Ok(x) => x,
+        Err(_) => return HxResponse::not_found_what(NotFoundReason::Event),
+    };
+
+    let mut completion = match MemoryDescriptor::lock_pages(
+        info.completion as _,
+        size_of::<AsyncCompletion>() as _,
+    ) {
+        Some(x) => x,
+        None => return HxResponse::invalid_params(1),
+    };
+
+    // mapped now, so the worker has nothing left to fail once the call ran.
+    let completion_va = match completion.get_system_address_safe() {
+        Ok(x) => x as usize,
+        Err(_) => return HxResponse::invalid_params(1),
+    };
+
+    let cookie = NEXT_COOKIE.fetch_add(1, Ordering::Relaxed);
+
+    let mut request = request.clone();
+    request.call.set_is_slow(false);
+
+    QUEUE.lock().push_back(AsyncWork {
+        cookie,
+        // worker needs the process to stay alive.
+        process: NtProcess::current().clone(),
+        request,
+        event,
+        _completion: completion,
+        completion_va,
+    });
+    wake.signal();
+
+    AsyncResponse { cookie }.into_raw()
+}
+
+///
+/// # On Worker
+///
+/// Whether the current thread is the worker. Handlers that can block check this,
+/// so they don't run inline in the syscall handler through a plain send or a batch.
+///
+pub(crate) fn on_worker() -> bool {
+    WORKER.get() == Some(&(unsafe { KeGetCurrentThread() } as usize))
+}
+
+extern "C" fn worker(_arg: PVOID) {
+    let wake = WAKE.get().unwrap();
+    WORKER.call_once(|| unsafe { KeGetCurrentThread() } as usize);
+
+    loop {
+        wake.wait(false, 1000);
+
+        // don't hold the lock while executing. handlers can take long, that's why they are here.
+        // so pop into a local first. a guard in the `while let` would live for the whole body.
+        loop {
+            let work = QUEUE.lock().pop_front();
+            let Some(work) = work else { break };
+
+            let response = {
+                // handlers work on the tracker of the current process. so become the caller.
+                let _ctx = work.process.begin_context();
+                dispatch(&work.request)
+            };
+
+            let ptr = work.completion_va as *mut AsyncCompletion;
+            unsafe {
+                (&raw mut (*ptr).cookie).write_volatile(work.cookie);
+                (&raw mut (*ptr).response).write_volatile(response);
+                (&raw mut (*ptr).completed).write_volatile(1);
+            }
+
+            work.event.signal();
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::nt::mm::user::is_user_range;
+use crate::nt::process::NtProcess;
+use crate::services::dispatch;
+use hxposed_core::hxposed::error::NotAllowedReason;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::requests::batch::BatchRequest;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::responses::batch::BatchResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+///
+/// # Batch
+///
+/// Executes every request in the caller's buffer, writing responses to the parallel buffer.
+///
+/// ## Remarks
+/// - Requests in a batch aren't logged individually. That's half the point.
+/// - A nested batch or a slow call gets [`HxResponse::invalid_params`], the rest of the batch goes on.
+/// - Permissions are checked per request. Ones the caller lacks get [`NotAllowedReason::MissingPermission`].
+///
+/// ## Return
+/// * [`BatchResponse`] - Number of requests executed.
+/// * [`HxResponse::invalid_params`] - Count is too big (2), or either buffer is inaccessible or not in user memory (0 or 1).
+pub(crate) fn batch(request: BatchRequest) -> HxResponse {
+    if request.count > BatchRequest::MAX_COUNT {
+        return HxResponse::invalid_params(2);
+    }
+
+    // anyone can batch. don't let them have us read or write kernel memory.
+    if !is_user_range::<HxRequest>(request.requests, request.count as _) {
+        return HxResponse::invalid_params(0);
+    }
+    if !is_user_range::<HxResponse>(request.responses, request.count as _) {
+        return HxResponse::invalid_params(1);
+    }
+
+    let permissions = NtProcess::current()
+        .get_object_tracker_unchecked()
+        .permissions;
+
+    let requests = request.requests as *const HxRequest;
+    let responses = request.responses as *mut HxResponse;
+
+    for i in 0..request.count as usize {
+        let entry = match microseh::try_seh(|| unsafe { requests.add(i).read_unaligned() }) {
+            Ok(x) => x,
+            Err(_) => return HxResponse::invalid_params(0),
+        };
+
+        // no nesting, and no slow calls. they would outlive the batch.
+        let result = match entry.call.func() {
+            _ if entry.call.is_slow() => HxResponse::invalid_params(0),
+            ServiceFunction::Batch => HxResponse::invalid_params(0),
+            x => match permissions.require(x.permissions()) {
+                Err(reason) => HxResponse::not_allowed(reason),
+                Ok(_) => dispatch(&entry),
+            },
+        };
+
+        if microseh::try_seh(|| unsafe { responses.add(i).write_unaligned(result) }).is_err() {
+            return HxResponse::invalid_params(1);
+        }
+    }
+
+    BatchResponse {
+        completed: request.count,
+    }
+    .into_raw()
+}
@@ -1,9 +1,16 @@
-use crate::nt::object::NtHandle;
+use crate::nt::object::{NtHandle, NtObject};
+use crate::win::NtStatus;
 use crate::nt::process::NtProcess;
-use hxposed_core::hxposed::error::NotFoundReason;
-use hxposed_core::hxposed::requests::handle::{GetHandleObjectRequest, SwapHandleObjectRequest, UpgradeHandleRequest};
+use crate::services::async_services;
+use crate::services::output_services::write_output;
+use alloc::vec::Vec;
+use bit_field::BitField;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::OutputSlice;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::requests::handle::{CloseRemoteHandleRequest, CreateHandleRequest, DowngradeHandleRequest, EnumerateHandlesRequest, GetHandleObjectRequest, SwapHandleObjectRequest, UpgradeHandleRequest};
 use hxposed_core::hxposed::responses::empty::EmptyResponse;
-use hxposed_core::hxposed::responses::handle::GetHandleObjectResponse;
+use hxposed_core::hxposed::responses::handle::{CreateHandleResponse, EnumerateHandlesResponse, GetHandleObjectResponse, HandleAttributes, HandleEntry};
 use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
 use crate::nt::ObjectBody;
 
@@ -27,6 +34,15 @@ pub(crate) fn upgrade_handle(request: UpgradeHandleRequest) -> HxResponse {
     EmptyResponse::default()
 }
 
+///
+/// # Swap Handle Object
+///
+/// Points a handle in the table of `process` to another object. After checking that it is one.
+///
+/// ## Return
+/// * [`EmptyResponse`] - Swapped.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`] or [`NotFoundReason::Handle`].
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::TypeMismatch`]. Not an object, or a different type and not `force`.
 pub(crate) fn swap_handle_obj(request: SwapHandleObjectRequest) -> HxResponse {
     // this needs to become a macro
     let process = NtProcess::current();
@@ -43,7 +59,20 @@ pub(crate) fn swap_handle_obj(request: SwapHandleObjectRequest) -> HxResponse {
         Some(entry) => entry,
     };
 
-    NtHandle::set_object_ptr(handle_entry, ObjectBody(request.object as _));
+    let object = ObjectBody(request.object as _);
+    let object_type = match NtObject::get_object_type(object) {
+        Some(x) => x,
+        None => return HxResponse::not_allowed(NotAllowedReason::TypeMismatch),
+    };
+
+    // _OBJECT_TYPE pointers are unique per type. no need to decode TypeIndex with the header cookie.
+    if !request.force
+        && NtObject::get_object_type(NtHandle::get_object_ptr(handle_entry)) != Some(object_type)
+    {
+        return HxResponse::not_allowed(NotAllowedReason::TypeMismatch);
+    }
+
+    NtHandle::set_object_ptr(handle_entry, object);
 
     EmptyResponse::default()
 }
@@ -67,4 +96,182 @@ pub(crate) fn get_handle_obj(request: GetHandleObjectRequest) -> HxResponse {
         object: NtHandle::get_object_ptr(handle_entry).0 as _,
         granted_access: NtHandle::get_granted_access(handle_entry),
     }.into_raw()
-}
\ No newline at end of file
+}
+
+///
+/// # Enumerate Handles
+///
+/// Walks the handle table of `process` into one blob. Entries first, then the type names and names they point to.
+///
+/// ## Remarks
+/// - Asks a name for every object, which can block. So only served on the async worker.
+///
+/// ## Return
+/// * [`EnumerateHandlesResponse`] - Where the blob is.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Not open.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Doesn't fit in the output buffer.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::AsyncOnly`]. Not sent async.
+pub(crate) fn enumerate_handles(request: EnumerateHandlesRequest) -> HxResponse {
+    if !async_services::on_worker() {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+        Some(x) => x,
+    };
+
+    let handles = NtHandle::walk(process.get_handle_table());
+    let entries_size = handles.len() * size_of::<HandleEntry>();
+
+    let mut strings = Vec::<u8>::new();
+    let mut push_string = |string: &[u16]| {
+        let slice = OutputSlice::new()
+            .with_offset((entries_size + strings.len()) as _)
+            .with_length((string.len() * 2) as _);
+        strings.extend(string.iter().flat_map(|x| x.to_le_bytes()));
+        slice
+    };
+
+    let entries = handles
+        .iter()
+        // walk referenced the objects. the handles may be gone by now, the objects are not.
+        .map(|info| HandleEntry {
+            handle: info.handle,
+            object: info.object.object_addr.0 as _,
+            type_name: push_string(info.object.get_type_name().as_slice()),
+            name: match info.object.get_name() {
+                Some(name) => push_string(name.as_slice()),
+                None => OutputSlice::new(),
+            },
+            granted_access: info.granted_access,
+            attributes: HandleAttributes::from_bits(info.attributes),
+            reserved: [0; 3],
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + strings.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&strings);
+
+    match write_output(&blob) {
+        Ok(handles) => EnumerateHandlesResponse {
+            handles,
+            count: entries.len() as _,
+        }
+        .into_raw(),
+        Err(err) => err,
+    }
+}
+
+///
+/// # Create Handle
+///
+/// Opens a handle to an object the plugin has open, in the plugin's own handle table.
+///
+/// ## Return
+/// * [`CreateHandleResponse`] - The handle.
+/// * [`HxResponse::invalid_params`] - Not a process, thread or token.
+/// * [`HxResponse::not_found_what`] - Plugin does not have the object open.
+/// * [`HxResponse::nt_error`] - `ObOpenObjectByPointer` failed.
+pub(crate) fn create_handle(request: CreateHandleRequest) -> HxResponse {
+    let process = NtProcess::current();
+    let tracker = process.get_object_tracker_unchecked();
+
+    let object = match request.object {
+        ObjectType::Process(x) => match tracker.get_open_process(x) {
+            Some(x) => x.nt_process as _,
+            None => return HxResponse::not_found_what(NotFoundReason::Process),
+        },
+        ObjectType::Thread(x) => match tracker.get_open_thread(x) {
+            Some(x) => x.nt_thread as _,
+            None => return HxResponse::not_found_what(NotFoundReason::Thread),
+        },
+        ObjectType::Token(x) => match tracker.get_open_token(x) {
+            Some(x) => x.nt_token as _,
+            None => return HxResponse::not_found_what(NotFoundReason::Token),
+        },
+        _ => return HxResponse::invalid_params(0),
+    };
+
+    match NtObject::create_handle(object, request.access_rights) {
+        Ok(handle) => CreateHandleResponse {
+            handle: handle as _,
+        }
+        .into_raw(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
+
+///
+/// # Downgrade Handle
+///
+/// Removes access rights from a handle in the table of `process`. The reverse of [`upgrade_handle`].
+///
+/// ## Return
+/// * [`EmptyResponse`] - Rights not in `access_rights` are gone.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`] or [`NotFoundReason::Handle`].
+pub(crate) fn downgrade_handle(request: DowngradeHandleRequest) -> HxResponse {
+    let process = NtProcess::current();
+    let process = match process
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+        Some(x) => x,
+    };
+    let handle_entry = match NtHandle::get_handle_entry(request.handle, process.get_handle_table())
+    {
+        None => return HxResponse::not_found_what(NotFoundReason::Handle),
+        Some(entry) => entry,
+    };
+
+    NtHandle::downgrade_handle(handle_entry, request.access_rights);
+
+    EmptyResponse::default()
+}
+
+///
+/// # Close Remote Handle
+///
+/// Closes a handle in the table of `process`, as if the process closed it itself.
+///
+/// ## Return
+/// * [`EmptyResponse`] - Closed.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`] or [`NotFoundReason::Handle`]. Free entries count as not found.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::HandleProtected`]. Handle has `OBJ_PROTECT_CLOSE`.
+/// * [`HxResponse::nt_error`] - `ObCloseHandle` failed.
+pub(crate) fn close_remote_handle(request: CloseRemoteHandleRequest) -> HxResponse {
+    let process = NtProcess::current();
+    let process = match process
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+        Some(x) => x,
+    };
+    let handle_entry = match NtHandle::get_handle_entry(request.handle, process.get_handle_table())
+    {
+        // lookups hand out free entries too.
+        Some(entry) if unsafe { *entry } != 0 => entry,
+        _ => return HxResponse::not_found_what(NotFoundReason::Handle),
+    };
+
+    // ObCloseHandle only refuses these for user mode. kernel mode bugchecks instead.
+    // not under the entry lock, nothing exported closes with it held. the owner flipping it right now is on them.
+    if NtHandle::get_attributes(handle_entry).get_bit(0) {
+        return HxResponse::not_allowed(NotAllowedReason::HandleProtected);
+    }
+
+    match NtHandle::close(process, request.handle) {
+        NtStatus::Success => EmptyResponse::default(),
+        NtStatus::InvalidHandle => HxResponse::not_found_what(NotFoundReason::Handle),
+        err => HxResponse::nt_error(err as _),
+    }
+}
@@ -2,6 +2,10 @@ use crate::nt::arch::cr3::Cr3Context;
 use crate::nt::arch::pt::{
     PageDirectoryEntry, PageDirectoryPointerEntry, PageMapLevel4, PageTableEntry, PagingEntry,
 };
+use crate::nt::context::ApcProcessContext;
+use crate::nt::mm::user::is_user_range;
+use crate::services::async_services;
+use crate::services::output_services::write_output;
 use crate::nt::mm::rmd::RawMemoryDescriptor;
 use crate::nt::process::NtProcess;
 use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
@@ -9,6 +13,10 @@ use hxposed_core::hxposed::requests::memory::*;
 use hxposed_core::hxposed::responses::empty::EmptyResponse;
 use hxposed_core::hxposed::responses::memory::*;
 use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+use hxposed_core::hxposed::ProcessObject;
+use hxposed_core::hxposed::output::OutputSlice;
+use alloc::vec;
+use alloc::vec::Vec;
 
 // I hate this so much
 pub fn get_set_page_attribute(request: PageAttributeRequest) -> HxResponse {
@@ -262,3 +270,178 @@ pub fn free_memory(request: FreeMemoryRequest) -> HxResponse {
         },
     }
 }
+
+pub fn read_virtual_memory(request: ReadVirtualMemoryRequest) -> HxResponse {
+    copy_virtual_memory(
+        request.addr_space,
+        request.address,
+        request.buffer,
+        request.size,
+        false,
+    )
+}
+
+pub fn write_virtual_memory(request: WriteVirtualMemoryRequest) -> HxResponse {
+    copy_virtual_memory(
+        request.addr_space,
+        request.address,
+        request.buffer,
+        request.size,
+        true,
+    )
+}
+
+///
+/// # Copy Virtual Memory
+///
+/// Copies between the caller's `buffer` and `address` in `addr_space`, a page at a time.
+///
+/// We can't be attached to both processes at once, so every page goes through a kernel buffer.
+/// Stops at the first page that faults on either side. What made it before that stays copied.
+fn copy_virtual_memory(
+    addr_space: ProcessObject,
+    address: u64,
+    buffer: u64,
+    size: u32,
+    write: bool,
+) -> HxResponse {
+    // both sides are user memory. don't let them have us read or write kernel memory.
+    if !is_user_range::<u8>(address, size as _) {
+        return HxResponse::invalid_params(1);
+    }
+    if !is_user_range::<u8>(buffer, size as _) {
+        return HxResponse::invalid_params(2);
+    }
+
+    let current = NtProcess::current();
+    let target = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(addr_space)
+    {
+        Some(x) => x.nt_process,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let mut bounce = vec![0u8; 0x1000];
+    let mut copied = 0u32;
+
+    while copied < size {
+        let remote = address + copied as u64;
+        let local = buffer + copied as u64;
+        let chunk = (0x1000 - (remote & 0xFFF) as u32).min(size - copied) as usize;
+        let bounce = &mut bounce[..chunk];
+
+        let (from, to) = match write {
+            true => (local, remote),
+            false => (remote, local),
+        };
+
+        // read side
+        let read = {
+            let _ctx = (!write).then(|| ApcProcessContext::begin(target));
+            microseh::try_seh(|| unsafe {
+                core::ptr::copy_nonoverlapping(from as *const u8, bounce.as_mut_ptr(), chunk)
+            })
+        };
+        if read.is_err() {
+            break;
+        }
+
+        // write side
+        let written = {
+            let _ctx = write.then(|| ApcProcessContext::begin(target));
+            microseh::try_seh(|| unsafe {
+                core::ptr::copy_nonoverlapping(bounce.as_ptr(), to as *mut u8, chunk)
+            })
+        };
+        if written.is_err() {
+            break;
+        }
+
+        copied += chunk as u32;
+    }
+
+    VirtualMemoryResponse {
+        bytes_copied: copied,
+    }
+    .into_raw()
+}
+
+///
+/// # Enumerate Memory Regions
+///
+/// Walks the VAD tree of `addr_space` into one blob. Entries first, then the file names they point to.
+///
+/// ## Remarks
+/// - Holds the address space lock of another process while copying names. So only served on the async worker.
+///
+/// ## Return
+/// * [`MemoryRegionsResponse`] - Where the blob is. `truncated` if the walk gave up on part of the tree.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Not open.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Doesn't fit in the output buffer.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::AsyncOnly`]. Not sent async.
+pub fn enumerate_memory_regions(request: EnumerateMemoryRegionsRequest) -> HxResponse {
+    if !async_services::on_worker() {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.addr_space)
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // held until the names are copied out. the VADs and files are only ours until then.
+    let _lock = process.lock_address_space();
+    let (vads, truncated) = process.get_vads();
+    let entries_size = vads.len() * size_of::<MemoryRegionEntry>();
+
+    let mut names = Vec::<u8>::new();
+    let entries = vads
+        .iter()
+        .map(|vad| {
+            let flags = vad.get_flags();
+            let file_name = match vad.get_file_name() {
+                None => OutputSlice::new(),
+                Some(name) => {
+                    let name = name.as_slice();
+                    let slice = OutputSlice::new()
+                        .with_offset((entries_size + names.len()) as _)
+                        .with_length((name.len() * 2) as _);
+                    names.extend(name.iter().flat_map(|x| x.to_le_bytes()));
+                    slice
+                }
+            };
+
+            MemoryRegionEntry {
+                start_vpn: vad.get_start_vpn(),
+                end_vpn: vad.get_end_vpn(),
+                commit_charge: vad.get_commit_charge(),
+                file_name,
+                protection: flags.protection(),
+                vad_type: flags.vad_type(),
+                private: flags.private_memory() as _,
+                reserved: [0; 5],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + names.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&names);
+
+    match write_output(&blob) {
+        Ok(regions) => MemoryRegionsResponse {
+            regions,
+            count: entries.len() as _,
+            truncated,
+        }
+        .into_raw(),
+        Err(err) => err,
+    }
+}
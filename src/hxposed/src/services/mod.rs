@@ -1,25 +1,24 @@
+use hxposed_core::hx_services;
 use hxposed_core::hxposed::call::HxCall;
 use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::func::ServiceFunction;
 use hxposed_core::hxposed::requests::{HxRequest, SyscallRequest};
-use hxposed_core::hxposed::requests::handle::*;
-use hxposed_core::hxposed::requests::io::*;
-use hxposed_core::hxposed::requests::memory::*;
-use hxposed_core::hxposed::requests::notify::*;
-use hxposed_core::hxposed::requests::process::*;
-use hxposed_core::hxposed::requests::security::*;
-use hxposed_core::hxposed::requests::thread::*;
+use hxposed_core::hxposed::requests::status::StatusRequest;
 use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
 use hxposed_core::hxposed::responses::status::StatusResponse;
-use hxposed_core::hxposed::status::HypervisorStatus;
-use crate::hyper_row;
+use hxposed_core::hxposed::status::{Capabilities, HypervisorStatus, PROTOCOL_VERSION};
 use crate::nt::arch::hxfs::{HxFs, Registers};
 use crate::nt::process::NtProcess;
 use crate::nt::thread::NtThread;
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
+use crate::win::{NT_PS_RESUME_THREAD, NT_PS_SUSPEND_THREAD};
 
+pub mod async_services;
+pub mod batch_services;
 pub mod callback_services;
 pub mod io_services;
 pub mod memory_services;
+pub mod output_services;
 pub mod process_services;
 pub mod security_services;
 pub mod thread_services;
@@ -32,63 +31,93 @@ fn invalid_handler(_req: &HxRequest) -> HxResponse {
     HxResponse::not_found_what(NotFoundReason::ServiceFunction)
 }
 
+fn get_state(_request: StatusRequest) -> HxResponse {
+    StatusResponse {
+        state: HypervisorStatus::SystemVirtualized,
+        version: PROTOCOL_VERSION,
+        capabilities: capabilities(),
+    }
+    .into_raw()
+}
+
 const DISPATCH_TABLE_MAX: usize = 8;
-#[allow(unused)]
-static DISPATCH_TABLE: [[SyscallHandler; 16]; DISPATCH_TABLE_MAX] = [
-    hyper_row!(|_| {
-        StatusResponse {
-            state: HypervisorStatus::SystemVirtualized,
-            version: 1,
+
+macro_rules! dispatch_table {
+    ($(
+        $name:ident = $id:literal $(=> $ctor:ident($req:ty) -> $resp:ty, $handler:path, $($perm:ident)|+)?;
+    )*) => {
+        static DISPATCH_TABLE: [[SyscallHandler; 16]; DISPATCH_TABLE_MAX] = {
+            let mut table = [[INV; 16]; DISPATCH_TABLE_MAX];
+            $($(
+                table[$id >> 4][$id & 0xF] = |x| $handler(<$req as SyscallRequest>::from_raw(x));
+            )?)*
+            table
+        };
+    };
+}
+
+hx_services!(dispatch_table);
+
+///
+/// # Capabilities
+///
+/// Builds the capability bitmap reported by `GetState` out of [`DISPATCH_TABLE`].
+///
+/// Every slot that isn't [`INV`], and is [`available`], counts as implemented.
+///
+fn capabilities() -> Capabilities {
+    let mut capabilities = Capabilities::default();
+
+    for (category, row) in DISPATCH_TABLE.iter().enumerate() {
+        for (func, handler) in row.iter().enumerate() {
+            if !core::ptr::fn_addr_eq(*handler, INV) && available(category, func) {
+                capabilities.set(category, func);
+            }
         }
-        .into_raw()
-    }),
-    hyper_row!(
-        |x| { process_services::open_process(OpenProcessRequest::from_raw(x)) },
-        |x| { process_services::close_process(CloseProcessRequest::from_raw(x)) },
-        |x| { process_services::get_process_field_sync(GetProcessFieldRequest::from_raw(x)) },
-        |x| { process_services::set_process_field_sync(SetProcessFieldRequest::from_raw(x)) }
-    ),
-    hyper_row!(
-        |x| {
-            callback_services::register_callback_receiver(RegisterNotifyHandlerRequest::from_raw(x))
+    }
+
+    capabilities
+}
+
+///
+/// # Available
+///
+/// Whether what the service needs was located on this build. Ones that aren't are treated as if they weren't registered.
+///
+fn available(category: usize, func: usize) -> bool {
+    match ServiceFunction::from_bits((category << 4 | func) as u16) {
+        ServiceFunction::SuspendThread
+        | ServiceFunction::ResumeThread
+        | ServiceFunction::SuspendProcess
+        | ServiceFunction::ResumeProcess => unsafe {
+            NT_PS_SUSPEND_THREAD != 0 && NT_PS_RESUME_THREAD != 0
         },
-        |x| {
-            callback_services::unregister_callback_receiver(
-                UnregisterNotifyHandlerRequest::from_raw(x),
-            )
-        }
-    ),
-    hyper_row!(
-        |x| { memory_services::allocate_memory(AllocateMemoryRequest::from_raw(x)) },
-        |x| { memory_services::free_memory(FreeMemoryRequest::from_raw(x)) },
-        |x| { memory_services::get_set_page_attribute(PageAttributeRequest::from_raw(x)) },
-        |x| { memory_services::map_va_to_pa(MapRmdRequest::from_raw(x)) },
-        |x| { memory_services::translate_address(TranslateAddressRequest::from_raw(x)) },
-        |x| { memory_services::describe_memory(DescribeMemoryRequest::from_raw(x)) }
-    ),
-    hyper_row!(
-        |x| { thread_services::open_thread_sync(OpenThreadRequest::from_raw(x)) },
-        |x| { thread_services::close_thread_sync(CloseThreadRequest::from_raw(x)) },
-        |x| { thread_services::get_thread_field_sync(GetThreadFieldRequest::from_raw(x)) },
-        |x| { thread_services::set_thread_field_sync(SetThreadFieldRequest::from_raw(x)) }
-    ),
-    hyper_row!(
-        |x| { security_services::open_token_sync(OpenTokenRequest::from_raw(x)) },
-        |x| { security_services::close_token_sync(CloseTokenRequest::from_raw(x)) },
-        |x| { security_services::get_token_field_sync(GetTokenFieldRequest::from_raw(x)) },
-        |x| { security_services::set_token_field_sync(SetTokenFieldRequest::from_raw(x)) }
-    ),
-    hyper_row!(
-        |x| { io_services::rw_msr(MsrIoRequest::from_raw(x)) },
-        |x| { io_services::exec_privileged(PrivilegedInstructionRequest::from_raw(x)) }
-    ),
-    hyper_row!(
-        |x| { handle_services::upgrade_handle(UpgradeHandleRequest::from_raw(x)) },
-        |x| { handle_services::get_handle_obj(GetHandleObjectRequest::from_raw(x)) },
-        |x| { handle_services::swap_handle_obj(SwapHandleObjectRequest::from_raw(x)) }
-
-    ),
-];
+        _ => true,
+    }
+}
+
+///
+/// # Dispatch
+///
+/// Finds the handler of the request in [`DISPATCH_TABLE`] and runs it.
+///
+/// ## Remarks
+/// - Permissions are not checked here. [`syscall_handler`] and the batch do that, in context of the caller.
+///
+pub(crate) fn dispatch(request: &HxRequest) -> HxResponse {
+    let function = request.call.func().into_bits() as usize;
+    const CATEGORY_MASK: usize = 0xF0;
+    const FUNCTION_MASK: usize = 0x0F;
+
+    let category = (function & CATEGORY_MASK) >> 4;
+    let func = function & FUNCTION_MASK;
+
+    if core::intrinsics::unlikely(category >= DISPATCH_TABLE_MAX || !available(category, func)) {
+        return HxResponse::not_found_what(NotFoundReason::ServiceFunction);
+    }
+
+    DISPATCH_TABLE[category][func](request)
+}
 
 #[unsafe(no_mangle)]
 pub(crate) fn syscall_handler(registers: &mut Registers) {
@@ -109,12 +138,16 @@ pub(crate) fn syscall_handler(registers: &mut Registers) {
         ..Default::default()
     };
 
-    HxLogger::serial_log(LogType::Trace, LogEvent::SystemCall(
-        request.call.into_bits(),
-        request.arg1,
-        request.arg2,
-        request.arg3,
-    ));
+    let ignore_result = request.call.ignore_result();
+
+    if !ignore_result {
+        HxLogger::serial_log(LogType::Trace, LogEvent::SystemCall(
+            request.call.into_bits(),
+            request.arg1,
+            request.arg2,
+            request.arg3,
+        ));
+    }
 
     if request.call.extended_args_present() {
         request.extended_arg1 = registers.xmm0;
@@ -123,21 +156,21 @@ pub(crate) fn syscall_handler(registers: &mut Registers) {
         request.extended_arg4 = registers.xmm3;
     }
 
-    let function = info.func().into_bits() as usize;
-    const CATEGORY_MASK: usize = 0xF0;
-    const FUNCTION_MASK: usize = 0x0F;
+    let permissions = NtProcess::current()
+        .get_object_tracker_unchecked()
+        .permissions;
 
-    let category = (function & CATEGORY_MASK) >> 4;
-    let func = function & FUNCTION_MASK;
+    // slow calls are checked here too. the worker doesn't know who asked.
+    let result = match permissions.require(request.call.func().permissions()) {
+        Err(reason) => HxResponse::not_allowed(reason),
+        Ok(_) if request.call.is_slow() => async_services::queue(&request),
+        Ok(_) => dispatch(&request),
+    };
 
-    if core::intrinsics::unlikely(category >= DISPATCH_TABLE_MAX) {
-        registers.write_response(HxResponse::not_found_what(
-            NotFoundReason::ServiceFunction,
-        ));
+    // fire and forget. caller doesn't look at the registers, so leave them as they are.
+    if ignore_result {
         return;
     }
-    
-    let result = DISPATCH_TABLE[category][func](&request);
 
     HxLogger::serial_log(LogType::Trace, LogEvent::CallResult(result.arg1, result.arg2, result.arg3));
 
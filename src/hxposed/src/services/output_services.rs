@@ -0,0 +1,149 @@
+use crate::nt::mm::mdl::{MemoryDescriptor, OwnType};
+use crate::nt::process::NtProcess;
+use crate::objects::OutputBuffer;
+use crate::win::{ExAllocatePool2, ExFreePool, PagePriority, PoolFlags, ProcessorMode};
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::{OutputBufferHeader, OutputSlice};
+use hxposed_core::hxposed::requests::memory::Va;
+use hxposed_core::hxposed::requests::output::RegisterOutputBufferRequest;
+use hxposed_core::hxposed::responses::output::RegisterOutputBufferResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+///
+/// # Register Output Buffer
+///
+/// Allocates the output buffer of the caller, and maps it wherever Mm finds room.
+///
+/// ## Remarks
+/// - If the current buffer is big enough, it's kept and emptied instead.
+/// - Replaced buffers stay mapped until the process exits. The caller may still be reading one.
+///
+/// ## Return
+/// * [`RegisterOutputBufferResponse`] - Where the buffer is mapped.
+/// * [`HxResponse::invalid_params`] - `size` is more than [`OutputBufferHeader::MAX_SIZE`].
+/// * [`HxResponse::nt_error`] - Out of pool, or mapping failed.
+pub(crate) fn register_output_buffer(request: RegisterOutputBufferRequest) -> HxResponse {
+    if request.size > OutputBufferHeader::MAX_SIZE {
+        return HxResponse::invalid_params(0);
+    }
+
+    let process = NtProcess::current();
+    let tracker = process.get_object_tracker_unchecked();
+    let mut output = tracker.output.lock();
+
+    // the one we have is big enough. just empty it.
+    if let Some(x) = output.as_mut().filter(|x| request.size <= x.header.size) {
+        x.header = OutputBufferHeader::new(x.header.size);
+        unsafe {
+            (x.system_va.get_addr() as *mut OutputBufferHeader).write_volatile(x.header);
+        }
+
+        return RegisterOutputBufferResponse {
+            address: x.user_va,
+            size: x.header.size,
+        }
+        .into_raw();
+    }
+
+    let size = request.size.max(OutputBufferHeader::SIZE).next_multiple_of(0x1000);
+    let pool = unsafe { ExAllocatePool2(PoolFlags::NonPaged, size as _, 0x2009) };
+    if pool.is_null() {
+        // STATUS_INSUFFICIENT_RESOURCES
+        return HxResponse::nt_error(0xC000009A);
+    }
+
+    let mut memory = match MemoryDescriptor::new_describe_nonpaged(pool, size) {
+        Some(x) => x,
+        None => {
+            unsafe { ExFreePool(pool) };
+            return HxResponse::nt_error(0xC000009A);
+        }
+    };
+    // from here on, dropping the descriptor frees the pool too.
+    memory.owns = OwnType::NonPaged(pool as _);
+
+    // we are in the caller. it gets mapped into its address space.
+    let address = match memory.map(
+        None,
+        ProcessorMode::UserMode,
+        PagePriority::NormalPagePriority as _,
+    ) {
+        Ok(x) => x as u64,
+        Err(err) => return HxResponse::nt_error(err as _),
+    };
+
+    let buffer = OutputBuffer {
+        system_va: Va::from(pool as u64),
+        user_va: address,
+        header: OutputBufferHeader::new(size),
+    };
+
+    unsafe {
+        (pool as *mut OutputBufferHeader).write_volatile(buffer.header);
+    }
+
+    tracker.output_memory.push(memory);
+    *output = Some(buffer);
+
+    RegisterOutputBufferResponse { address, size }.into_raw()
+}
+
+///
+/// # Output Remaining
+///
+/// Bytes left in the output buffer of the caller. For results that can be cut to fit.
+///
+/// ## Return
+/// * [`u32`] - Bytes left.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has not registered one.
+pub(crate) fn output_remaining() -> Result<u32, HxResponse> {
+    let process = NtProcess::current();
+    let tracker = process.get_object_tracker_unchecked();
+    match tracker.output.lock().as_ref() {
+        Some(x) => Ok(x.header.remaining()),
+        None => Err(HxResponse::not_found_what(NotFoundReason::OutputBuffer)),
+    }
+}
+
+///
+/// # Write Output
+///
+/// Writes a variable-length result to the output buffer of the caller.
+///
+/// ## Remarks
+/// - Bounds come from our copy of the header. Never from the buffer itself.
+///
+/// ## Return
+/// * [`OutputSlice`] - Where the result was written.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has not registered one.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Result does not fit.
+pub(crate) fn write_output<T: Copy>(data: &[T]) -> Result<OutputSlice, HxResponse> {
+    let process = NtProcess::current();
+    let tracker = process.get_object_tracker_unchecked();
+    let mut output = tracker.output.lock();
+    let output = match output.as_mut() {
+        Some(x) => x,
+        None => return Err(HxResponse::not_found_what(NotFoundReason::OutputBuffer)),
+    };
+
+    let length = size_of_val(data);
+    let slice = match u32::try_from(length)
+        .ok()
+        .and_then(|x| output.header.reserve(x))
+    {
+        Some(x) => x,
+        None => return Err(HxResponse::not_allowed(NotAllowedReason::BufferTooSmall)),
+    };
+
+    let base = output.system_va.get_addr();
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            data.as_ptr() as *const u8,
+            (base + slice.offset() as u64) as *mut u8,
+            length,
+        );
+        (base as *mut OutputBufferHeader).write_volatile(output.header);
+    }
+
+    Ok(slice)
+}
@@ -1,11 +1,16 @@
-use crate::nt::process::NtProcess;
+use crate::nt::process::{NtProcess, ProcessParameter};
+use crate::services::async_services;
+use crate::services::output_services::{output_remaining, write_output};
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
-use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
 use hxposed_core::hxposed::requests::process::*;
 use hxposed_core::hxposed::responses::empty::EmptyResponse;
 use hxposed_core::hxposed::responses::process::*;
+use hxposed_core::hxposed::responses::thread::SuspendCountResponse;
 use hxposed_core::hxposed::responses::{HxResponse, OpenObjectResponse, SyscallResponse};
 use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::output::OutputSlice;
+use alloc::vec::Vec;
 
 ///
 /// # Set Process Field (Sync)
@@ -20,17 +25,16 @@ use hxposed_core::hxposed::ObjectType;
 ///
 /// ## Return
 /// * [`HxResponse::nt_error`] - An error occurred writing to the user buffer.
-/// * [`HxResponse::not_allowed_perms`] - The plugin lacks the required permissions.
 /// * [`HxResponse::invalid_params`] - Invalid buffer.
 /// * [`GetProcessFieldResponse::NtPath`] - Number of bytes for the name. Also, depending on if the caller allocated the buffer, name is written to buffer.
 pub(crate) fn set_process_field_sync(request: SetProcessFieldRequest) -> HxResponse {
-    let process = NtProcess::current();
-    let mut process = match process
+    let current = NtProcess::current();
+    let mut process = match current
         .get_object_tracker_unchecked()
         .get_open_process(request.process as _)
     {
         Some(process) => process,
-        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
     };
 
     match request.field {
@@ -46,6 +50,10 @@ pub(crate) fn set_process_field_sync(request: SetProcessFieldRequest) -> HxRespo
             process.set_mitigations(flags);
             EmptyResponse::default()
         }
+        ProcessField::MitigationFlags3(flags) => {
+            process.set_mitigations3(flags);
+            EmptyResponse::default()
+        }
         ProcessField::DirectoryTableBase(base) => {
             process.set_directory_table_base(base);
             EmptyResponse::default()
@@ -55,7 +63,7 @@ pub(crate) fn set_process_field_sync(request: SetProcessFieldRequest) -> HxRespo
             EmptyResponse::default()
         }
         ProcessField::Token(token) => {
-            let token = match process.get_object_tracker_unchecked().get_open_token(token) {
+            let token = match current.get_object_tracker_unchecked().get_open_token(token) {
                 Some(x) => x,
                 None => return HxResponse::not_found_what(NotFoundReason::Token),
             };
@@ -81,8 +89,8 @@ pub(crate) fn set_process_field_sync(request: SetProcessFieldRequest) -> HxRespo
 ///
 /// ## Return
 /// * [`HxResponse::nt_error`] - An error occurred writing to the user buffer.
-/// * [`HxResponse::not_allowed_perms`] - The plugin lacks the required permissions.
-/// * [`GetProcessFieldResponse::NtPath`] - Number of bytes for the name. Also, depending on if the caller allocated the buffer, name is written to buffer.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has no output buffer.
+/// * [`GetProcessFieldResponse`] - `NtPath` and `Threads` are written to the output buffer of the caller.
 ///
 pub(crate) fn get_process_field_sync(request: GetProcessFieldRequest) -> HxResponse {
     let process = NtProcess::current();
@@ -91,46 +99,84 @@ pub(crate) fn get_process_field_sync(request: GetProcessFieldRequest) -> HxRespo
         .get_open_process(request.process as _)
     {
         Some(process) => process,
-        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
     };
 
+    let mut truncated = false;
     let field = match request.field {
-        ProcessField::NtPath(ptr) => {
+        ProcessField::NtPath(_) => {
             let field = process.get_nt_path();
-            if ptr != 0 {
-                let _ = microseh::try_seh(|| unsafe {
-                    core::ptr::copy_nonoverlapping(field.as_ptr(), ptr as _, field.len())
-                });
+            match write_output(field.as_slice()) {
+                Ok(slice) => ProcessField::NtPath(slice),
+                Err(e) => return e,
             }
-
-            ProcessField::NtPath(field.len() as _)
         }
         ProcessField::Protection(_) => ProcessField::Protection(process.get_protection()),
         ProcessField::Signers(_) => ProcessField::Signers(process.get_signers()),
         ProcessField::MitigationFlags(_) => {
             ProcessField::MitigationFlags(process.get_mitigations())
         }
-        ProcessField::Token(_) => ProcessField::Token(process.get_token() as _),
-        ProcessField::Threads(ptr) => {
-            let field = process.get_threads();
-            if ptr != 0 {
-                let _ = microseh::try_seh(|| unsafe {
-                    core::ptr::copy_nonoverlapping(field.as_ptr(), ptr as _, field.len())
-                });
-            }
-
-            ProcessField::Threads(field.len() as _)
+        ProcessField::MitigationFlags3(_) => {
+            ProcessField::MitigationFlags3(process.get_mitigations3())
         }
+        ProcessField::Token(_) => ProcessField::Token(process.get_token() as _),
+        ProcessField::Threads(_) => match write_output(&process.get_threads()) {
+            Ok(slice) => ProcessField::Threads(slice),
+            Err(e) => return e,
+        },
         ProcessField::DirectoryTableBase(_) => {
             ProcessField::DirectoryTableBase(process.get_directory_table_base().into())
         }
         ProcessField::UserDirectoryTableBase(_) => {
-            ProcessField::DirectoryTableBase(process.get_user_directory_table_base().into())
+            ProcessField::UserDirectoryTableBase(process.get_user_directory_table_base().into())
+        }
+        ProcessField::CommandLine(_) => {
+            match write_parameter(process, ProcessParameter::CommandLine) {
+                Ok(slice) => ProcessField::CommandLine(slice),
+                Err(e) => return e,
+            }
+        }
+        ProcessField::Environment(_) => {
+            // cut to what's left, not failed. it can be bigger than the whole buffer.
+            let limit = match output_remaining() {
+                Ok(x) => x as usize / 2,
+                Err(e) => return e,
+            };
+            let block = match process.get_environment(limit) {
+                Some((block, cut)) => {
+                    truncated = cut;
+                    block
+                }
+                None => return HxResponse::not_allowed(NotAllowedReason::AccessViolation),
+            };
+            match write_output(&block) {
+                Ok(slice) => ProcessField::Environment(slice),
+                Err(e) => return e,
+            }
+        }
+        ProcessField::CurrentDirectory(_) => {
+            match write_parameter(process, ProcessParameter::CurrentDirectory) {
+                Ok(slice) => ProcessField::CurrentDirectory(slice),
+                Err(e) => return e,
+            }
+        }
+        ProcessField::WindowTitle(_) => {
+            match write_parameter(process, ProcessParameter::WindowTitle) {
+                Ok(slice) => ProcessField::WindowTitle(slice),
+                Err(e) => return e,
+            }
         }
         ProcessField::Unknown => ProcessField::Unknown,
     };
 
-    GetProcessFieldResponse { field }.into_raw()
+    GetProcessFieldResponse { field, truncated }.into_raw()
+}
+
+fn write_parameter(process: &NtProcess, parameter: ProcessParameter) -> Result<OutputSlice, HxResponse> {
+    match process.get_process_parameter(parameter) {
+        Some(x) => write_output(&x),
+        None => Err(HxResponse::not_allowed(NotAllowedReason::AccessViolation)),
+    }
 }
 
 ///
@@ -164,6 +210,130 @@ pub(crate) fn close_process(request: CloseProcessRequest) -> HxResponse {
     }
 }
 
+///
+/// # Kill Process
+///
+/// Terminates a process the plugin has open. Protected ones included.
+///
+/// ## Arguments
+/// * `request` - [`KillProcessRequest`].
+///
+/// ## Remarks
+/// - Process stays in the virtual handle table. Plugin closes it as usual.
+///
+/// ## Return
+/// * [`HxResponse::ok`] - Process is terminating.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Plugin does not have it open.
+/// * [`HxResponse::nt_error`] - `PsTerminateProcess` failed.
+pub(crate) fn kill_process(request: KillProcessRequest) -> HxResponse {
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    match process.kill(request.exit_code) {
+        Ok(_) => EmptyResponse::default(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
+
+///
+/// # Suspend Process
+///
+/// Suspends every thread of a process the plugin has open. Protected ones included.
+///
+/// ## Arguments
+/// * `request` - [`SuspendProcessRequest`].
+///
+/// ## Remarks
+/// - NT keeps suspend counts per thread. Each suspend needs its own resume.
+/// - Not the caller's own process. The calling thread would be suspended with it.
+///
+/// ## Return
+/// * [`SuspendCountResponse`] - Highest suspend count the threads had before.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Plugin does not have it open.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::CurrentProcess`].
+/// * [`HxResponse::nt_error`] - Suspending a thread failed. See [`NtProcess::suspend`].
+pub(crate) fn suspend_process(request: SuspendProcessRequest) -> HxResponse {
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    if process.nt_process == current.nt_process {
+        return HxResponse::not_allowed(NotAllowedReason::CurrentProcess);
+    }
+
+    match process.suspend() {
+        Ok(previous_count) => SuspendCountResponse { previous_count }.into_raw(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
+
+///
+/// # Resume Process
+///
+/// Undoes one [`suspend_process`].
+///
+/// ## Arguments
+/// * `request` - [`ResumeProcessRequest`].
+///
+/// ## Return
+/// * [`SuspendCountResponse`] - Highest suspend count the threads had before.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Plugin does not have it open.
+/// * [`HxResponse::nt_error`] - Resuming a thread failed. See [`NtProcess::resume`].
+pub(crate) fn resume_process(request: ResumeProcessRequest) -> HxResponse {
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    match process.resume() {
+        Ok(previous_count) => SuspendCountResponse { previous_count }.into_raw(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
+
+///
+/// # Enumerate Processes
+///
+/// Walks the active process list, writing a [`ProcessEntry`] for each process to the output buffer of the caller.
+///
+/// ## Return
+/// * [`EnumerateProcessesResponse`] - Where the entries are.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has no output buffer.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Too many processes.
+pub(crate) fn enumerate_processes(_request: EnumerateProcessesRequest) -> HxResponse {
+    let entries = NtProcess::get_active_processes()
+        .iter()
+        .map(|process| ProcessEntry {
+            id: process.id,
+            parent_id: process.get_parent_id(),
+            create_time: process.get_create_time(),
+            protection: process.get_protection(),
+            signers: process.get_signers(),
+            image_file_name: process.get_image_file_name(),
+        })
+        .collect::<Vec<_>>();
+
+    match write_output(&entries) {
+        Ok(processes) => EnumerateProcessesResponse { processes }.into_raw(),
+        Err(e) => e,
+    }
+}
+
 pub(crate) fn open_process(request: OpenProcessRequest) -> HxResponse {
     let current = NtProcess::current();
 
@@ -186,3 +356,69 @@ pub(crate) fn open_process(request: OpenProcessRequest) -> HxResponse {
     }
     .into_raw()
 }
+
+///
+/// # Enumerate Modules
+///
+/// Walks the loader lists of `process` into one blob. Entries first, then the paths they point to.
+///
+/// ## Remarks
+/// - Attaches to `process` and reads its user memory, which can page fault. So only served on the async worker.
+///
+/// ## Return
+/// * [`EnumerateModulesResponse`] - Where the blob is.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Process`]. Not open.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Doesn't fit in the output buffer.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::AsyncOnly`]. Not sent async.
+pub fn enumerate_modules(request: EnumerateModulesRequest) -> HxResponse {
+    if !async_services::on_worker() {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let current = NtProcess::current();
+    let process = match current
+        .get_object_tracker_unchecked()
+        .get_open_process(request.process)
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let modules = process.get_modules();
+    let entries_size = modules.len() * size_of::<ModuleEntry>();
+
+    let mut paths = Vec::<u8>::new();
+    let entries = modules
+        .iter()
+        .map(|module| {
+            let path = OutputSlice::new()
+                .with_offset((entries_size + paths.len()) as _)
+                .with_length((module.path.len() * 2) as _);
+            paths.extend(module.path.iter().flat_map(|x| x.to_le_bytes()));
+
+            ModuleEntry {
+                base: module.base,
+                entry_point: module.entry_point,
+                path,
+                size: module.size,
+                wow64: module.wow64 as _,
+                reserved: [0; 3],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + paths.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&paths);
+
+    match write_output(&blob) {
+        Ok(modules) => EnumerateModulesResponse {
+            modules,
+            count: entries.len() as _,
+        }
+        .into_raw(),
+        Err(err) => err,
+    }
+}
@@ -1,6 +1,7 @@
 use crate::nt;
 use crate::nt::process::NtProcess;
 use crate::nt::token::NtToken;
+use crate::services::output_services::write_output;
 use crate::utils::logger::{HxLogger, LogEvent, LogType};
 use hxposed_core::hxposed::error::NotFoundReason;
 use hxposed_core::hxposed::requests::security::*;
@@ -46,14 +47,12 @@ pub(crate) fn get_token_field_sync(request: GetTokenFieldRequest) -> HxResponse
     match request.field {
         TokenField::Unknown => return HxResponse::invalid_params(0),
         TokenField::SourceName(_) => GetTokenFieldResponse::SourceName(token.get_source_name()),
-        TokenField::AccountName(ptr) => {
+        TokenField::AccountName(_) => {
             let field = token.get_account_name();
-            if ptr != 0 {
-                let _ = microseh::try_seh(|| unsafe {
-                    core::ptr::copy_nonoverlapping(field.as_ptr(), ptr as _, field.len())
-                });
+            match write_output(field.as_slice()) {
+                Ok(slice) => GetTokenFieldResponse::AccountName(slice),
+                Err(e) => return e,
             }
-            GetTokenFieldResponse::AccountName(field.len() as _)
         }
         TokenField::Type(_) => GetTokenFieldResponse::Type(token.get_type()),
         TokenField::IntegrityLevelIndex(_) => {
@@ -95,7 +94,7 @@ pub(crate) fn open_token_sync(request: OpenTokenRequest) -> HxResponse {
         object: ObjectType::Token(
             process
                 .get_object_tracker_unchecked()
-                .add_open_token(NtToken::from_ptr_owning(request.token as _)),
+                .add_open_token(NtToken::from_ptr_owning(token as _)),
         ),
     }
     .into_raw()
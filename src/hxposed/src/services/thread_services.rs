@@ -1,6 +1,8 @@
 use crate::nt::process::NtProcess;
 use crate::nt::thread::NtThread;
-use hxposed_core::hxposed::error::NotFoundReason;
+use crate::services::async_services;
+use crate::services::output_services::write_output;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
 use hxposed_core::hxposed::requests::thread::*;
 use hxposed_core::hxposed::responses::empty::EmptyResponse;
 use hxposed_core::hxposed::responses::thread::*;
@@ -24,6 +26,22 @@ pub(crate) fn get_thread_field_sync(request: GetThreadFieldRequest) -> HxRespons
         ThreadField::AdjustedClientToken(_) => {
             GetThreadFieldResponse::AdjustedClientToken(thread.get_adjusted_client_token() as _)
         }
+        ThreadField::Context(_) => {
+            // waits for an APC on the thread. that's for the worker to sit through, not the handler.
+            if !async_services::on_worker() {
+                return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+            }
+
+            let context = match thread.get_context() {
+                Ok(x) => x,
+                Err(err) => return HxResponse::nt_error(err as _),
+            };
+
+            match write_output(&[context]) {
+                Ok(slice) => GetThreadFieldResponse::Context(slice),
+                Err(e) => return e,
+            }
+        }
     }
     .into_raw()
 }
@@ -101,3 +119,59 @@ pub(crate) fn close_thread_sync(request: CloseThreadRequest) -> HxResponse {
         }
     }
 }
+
+///
+/// # Suspend Thread
+///
+/// Increments the suspend count of a thread the plugin has open. Threads of protected processes included.
+///
+/// ## Arguments
+/// * `request` - [`SuspendThreadRequest`].
+///
+/// ## Return
+/// * [`SuspendCountResponse`] - Suspend count before this call.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Thread`]. Plugin does not have it open.
+/// * [`HxResponse::nt_error`] - `PsSuspendThread` failed.
+pub(crate) fn suspend_thread(request: SuspendThreadRequest) -> HxResponse {
+    let process = NtProcess::current();
+    let thread = match process
+        .get_object_tracker_unchecked()
+        .get_open_thread(request.thread)
+    {
+        Some(thread) => thread,
+        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    match thread.suspend() {
+        Ok(previous_count) => SuspendCountResponse { previous_count }.into_raw(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
+
+///
+/// # Resume Thread
+///
+/// Decrements the suspend count of a thread the plugin has open. Thread runs again once it reaches zero.
+///
+/// ## Arguments
+/// * `request` - [`ResumeThreadRequest`].
+///
+/// ## Return
+/// * [`SuspendCountResponse`] - Suspend count before this call.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::Thread`]. Plugin does not have it open.
+/// * [`HxResponse::nt_error`] - `PsResumeThread` failed.
+pub(crate) fn resume_thread(request: ResumeThreadRequest) -> HxResponse {
+    let process = NtProcess::current();
+    let thread = match process
+        .get_object_tracker_unchecked()
+        .get_open_thread(request.thread)
+    {
+        Some(thread) => thread,
+        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    match thread.resume() {
+        Ok(previous_count) => SuspendCountResponse { previous_count }.into_raw(),
+        Err(err) => HxResponse::nt_error(err as _),
+    }
+}
@@ -17,24 +17,6 @@ macro_rules! scoped_log {
     };
 }
 
-#[macro_export]
-macro_rules! hyper_row {
-    ($($func:expr),*) => {
-        {
-            const ARR: [SyscallHandler; 16] = {
-                let mut row = [INV; 16];
-                let mut i = 0;
-                $(
-                    row[i] = $func;
-                    i += 1;
-                )*
-                row
-            };
-            ARR
-        }
-    };
-}
-
 #[macro_export]
 macro_rules! as_pvoid {
     ($t:ident) => {
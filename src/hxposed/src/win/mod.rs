@@ -26,6 +26,7 @@ pub type PEPROCESS = *mut c_void;
 pub type PKEVENT = *mut c_void;
 pub type PACCESS_TOKEN = *mut c_void;
 pub type PETHREAD = *mut c_void;
+pub type PMMVAD = *mut c_void;
 pub type HANDLE = *mut c_void;
 pub type PVOID = *mut c_void;
 pub type PUCHAR = *mut c_char;
@@ -147,6 +148,12 @@ pub enum NtStatus {
     NotAllocated = 0xC00000A0,
     AccessViolation = 0xC0000005,
     BufferTooSmall = 0xc0000023,
+    NotImplemented = 0xC0000002,
+    SuspendCountExceeded = 0xC000004A,
+    ThreadIsTerminating = 0xC000004B,
+    ProcessIsTerminating = 0xC000010A,
+    InfoLengthMismatch = 0xC0000004,
+    InvalidHandle = 0xC0000008,
 }
 
 impl NtStatus {
@@ -243,11 +250,12 @@ pub enum CreateThreadNotifType {
     PsCreateThreadNotifyNonSystem,
 }
 
-pub(crate) type PsTerminateProcessType = unsafe extern "C" fn(PEPROCESS, NtStatus) -> NtStatus;
+pub(crate) type PsTerminateProcessType = unsafe extern "C" fn(PEPROCESS, u32) -> NtStatus;
 pub(crate) type PsTerminateThreadType = unsafe extern "C" fn(PETHREAD, NtStatus, i8) -> NtStatus;
 pub(crate) type ExpLookupHandleTableEntryType =
     unsafe extern "C" fn(PHANDLE_TABLE, _EXHANDLE) -> *mut u64;
 pub(crate) type ExCreateHandleType = unsafe extern "C" fn(PHANDLE_TABLE, PVOID) -> *mut u64;
+pub(crate) type PsSuspendResumeThreadType = unsafe extern "C" fn(PETHREAD, *mut u32) -> NtStatus;
 
 #[unsafe(no_mangle)]
 pub(crate) static mut NT_KI_SYSTEM_CALL64: u64 = 0;
@@ -265,6 +273,9 @@ pub(crate) static mut NT_PS_TERMINATE_THREAD: u64 = 0;
 pub(crate) static mut NT_EXP_LOOKUP_HANDLE_TABLE_ENTRY: u64 = 0;
 #[unsafe(no_mangle)]
 pub(crate) static mut NT_EX_CREATE_HANDLE: u64 = 0;
+// not exported. 0 until they're located for this build, and the services using them are turned off.
+pub(crate) static mut NT_PS_SUSPEND_THREAD: u64 = 0;
+pub(crate) static mut NT_PS_RESUME_THREAD: u64 = 0;
 
 pub unsafe extern "C" fn ExpLookupHandleTableEntry(
     Table: PHANDLE_TABLE,
@@ -291,7 +302,8 @@ pub unsafe extern "C" fn ExCreateHandle(Table: PHANDLE_TABLE, ObjectHeader: PVOI
     result
 }
 
-pub unsafe extern "C" fn PsTerminateProcess(Process: PEPROCESS, ExitCode: NtStatus) -> NtStatus {
+// exit code is any NTSTATUS the caller likes. not just the ones we know.
+pub unsafe extern "C" fn PsTerminateProcess(Process: PEPROCESS, ExitCode: u32) -> NtStatus {
     let func: PsTerminateProcessType = mem::transmute(NT_PS_TERMINATE_PROCESS);
     func(Process, ExitCode)
 }
@@ -305,6 +317,24 @@ pub unsafe extern "C" fn PspTerminateThread(
     func(Thread, ExitCode, SomethingElse)
 }
 
+pub unsafe extern "C" fn PsSuspendThread(Thread: PETHREAD, PreviousSuspendCount: *mut u32) -> NtStatus {
+    if NT_PS_SUSPEND_THREAD == 0 {
+        return NtStatus::NotImplemented;
+    }
+
+    let func: PsSuspendResumeThreadType = mem::transmute(NT_PS_SUSPEND_THREAD);
+    func(Thread, PreviousSuspendCount)
+}
+
+pub unsafe extern "C" fn PsResumeThread(Thread: PETHREAD, PreviousSuspendCount: *mut u32) -> NtStatus {
+    if NT_PS_RESUME_THREAD == 0 {
+        return NtStatus::NotImplemented;
+    }
+
+    let func: PsSuspendResumeThreadType = mem::transmute(NT_PS_RESUME_THREAD);
+    func(Thread, PreviousSuspendCount)
+}
+
 pub(crate) const NT_CURRENT_PROCESS: HANDLE = -1 as _;
 
 #[unsafe(naked)]
@@ -334,6 +364,12 @@ unsafe extern "C" {
 
     pub fn PsLookupProcessByProcessId(Id: HANDLE, Process: *mut PEPROCESS) -> NtStatus;
     pub fn PsLookupThreadByThreadId(Id: HANDLE, Process: *mut PETHREAD) -> NtStatus;
+    pub fn ZwQuerySystemInformation(
+        SystemInformationClass: u32,
+        SystemInformation: PVOID,
+        SystemInformationLength: u32,
+        ReturnLength: *mut u32,
+    ) -> NtStatus;
     pub fn PsReferencePrimaryToken(Process: PEPROCESS) -> PACCESS_TOKEN;
     pub fn PsReferenceImpersonationToken(
         Thread: PETHREAD,
@@ -347,7 +383,41 @@ unsafe extern "C" {
         Routine: PVOID,
     ) -> NtStatus;
     pub fn PsGetProcessId(Process: PEPROCESS) -> HANDLE;
+    pub fn PsGetProcessWow64Process(Process: PEPROCESS) -> PVOID;
+    pub fn PsGetContextThread(
+        Thread: PETHREAD,
+        ThreadContext: PVOID,
+        PreviousMode: ProcessorMode,
+    ) -> NtStatus;
+    pub fn ObGetObjectType(Object: PVOID) -> PVOID;
+    pub fn ObfReferenceObject(Object: PVOID) -> isize;
+    pub fn ObfDereferenceObject(Object: PVOID) -> isize;
+    pub fn ObReferenceObjectSafe(Object: PVOID) -> Boolean;
+    pub fn ObCloseHandle(Handle: HANDLE, PreviousMode: ProcessorMode) -> NtStatus;
+    pub fn ExEnumHandleTable(
+        HandleTable: PHANDLE_TABLE,
+        EnumHandleProcedure: PVOID,
+        EnumParameter: PVOID,
+        Handle: *mut HANDLE,
+    ) -> Boolean;
+    pub fn ExfUnblockPushLock(PushLock: *mut u64, WaitBlock: PVOID);
+    pub fn ObOpenObjectByPointer(
+        Object: PVOID,
+        HandleAttributes: u32,
+        PassedAccessState: PVOID,
+        DesiredAccess: u32,
+        ObjectType: PVOID,
+        AccessMode: ProcessorMode,
+        Handle: *mut HANDLE,
+    ) -> NtStatus;
+    pub fn ObQueryNameString(
+        Object: PVOID,
+        ObjectNameInfo: *mut UNICODE_STRING,
+        Length: u32,
+        ReturnLength: *mut u32,
+    ) -> NtStatus;
     pub fn PsGetThreadId(Thread: PETHREAD) -> HANDLE;
+    pub fn PsGetThreadProcess(Thread: PETHREAD) -> PEPROCESS;
     pub fn PsCreateSystemThread(
         ThreadHandle: *mut HANDLE,
         DesiredAccess: ThreadAccessRights,
@@ -364,6 +434,8 @@ unsafe extern "C" {
     pub fn ExReleasePushLockSharedEx(Lock: *mut u64, Flags: u32);
     pub fn ExAcquirePushLockExclusiveEx(Lock: *mut u64, Flags: u32);
     pub fn ExAcquirePushLockSharedEx(Lock: *mut u64, Flags: u32);
+    pub fn KeEnterCriticalRegion();
+    pub fn KeLeaveCriticalRegion();
 
     pub fn IoGetCurrentProcess() -> PEPROCESS;
     pub fn IoFreeMdl(Mdl: *mut MDL);
@@ -31,6 +31,11 @@ impl UnicodeString {
         self.buffer.as_ptr()
     }
 
+    // without the terminator, if there's one.
+    pub fn as_slice(&self) -> &[u16] {
+        self.buffer.strip_suffix(&[0]).unwrap_or(&self.buffer)
+    }
+
     pub fn get_raw_bytes(&self) -> &[u8] {
         unsafe { core::slice::from_raw_parts::<u8>(self.as_ptr() as _, self.len() * 2) }
     }
@@ -1,4 +1,5 @@
 use crate::hxposed::error::{NotAllowedReason, NotFoundReason};
+use crate::hxposed::func::ServiceFunction;
 use crate::hxposed::responses::HxResponse;
 use core::fmt;
 
@@ -12,6 +13,7 @@ pub enum HxError {
     NtError(u32),
     TimedOut,
     HvNotLoaded,
+    NotSupported(ServiceFunction),
     Unknown
 }
 
@@ -28,6 +30,7 @@ impl fmt::Debug for HxError {
             Self::TimedOut => write!(f, "TimedOut"),
             Self::NtError(val) => write!(f, "NtError: {:#x}", val),
             Self::HvNotLoaded => write!(f, "HvNotLoaded"),
+            Self::NotSupported(function) => f.debug_tuple("NotSupported").field(function).finish(),
             Self::Unknown => write!(f, "UnknownError"),
         }
     }
@@ -43,6 +46,7 @@ impl fmt::Display for HxError {
             Self::TimedOut => write!(f, "Operation took too long"),
             Self::NtError(val) => write!(f, "Internal NT returned error: {:#x}", val),
             Self::HvNotLoaded => write!(f, "Hypervisor is not loaded"),
+            Self::NotSupported(function) => write!(f, "Hypervisor does not implement {:?}", function),
             HxError::Unknown => write!(f, "Unknown error"),
         }
     }
@@ -52,14 +56,15 @@ impl core::error::Error for HxError {}
 
 impl HxError {
     pub fn from_response(response: &HxResponse) -> HxError {
-        match response.result.error_code {
+        match response.result.error_code() {
             0 => HxError::Success,
-            1 => HxError::NotAllowed(NotAllowedReason::from_bits(response.result.error_reason as _)),
-            2 => HxError::NotFound(NotFoundReason::from_bits(response.result.error_reason as _)),
-            3 => HxError::InvalidParameters(response.result.error_reason as _),
-            4 => HxError::NtError(response.result.error_reason as _),
+            1 => HxError::NotAllowed(NotAllowedReason::from_bits(response.result.error_reason() as _)),
+            2 => HxError::NotFound(NotFoundReason::from_bits(response.result.error_reason() as _)),
+            3 => HxError::InvalidParameters(response.result.error_reason() as _),
+            4 => HxError::NtError(response.result.error_reason() as _),
             5 => HxError::TimedOut,
             6 => HxError::HvNotLoaded, // HvNotLoaded is a pseudo error. its returned by vmcall mechanism when RCX is not 2009.
+            7 => HxError::NotSupported(ServiceFunction::from_bits(response.result.error_reason() as _)),
             _ => Self::Unknown,
         }
     }
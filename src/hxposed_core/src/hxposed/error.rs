@@ -1,3 +1,5 @@
+use crate::hxposed::permissions::PluginPermissions;
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[repr(u32)]
 pub enum NotAllowedReason {
@@ -6,6 +8,17 @@ pub enum NotAllowedReason {
     PageNotPresent = 3,
     MappingsExist = 4,
     AccessViolation = 5,
+    BufferTooSmall = 6,
+    /// Caller lacks the permission. Travels as its bit index, in bits 8-15 of the reason.
+    MissingPermission(PluginPermissions) = 7,
+    /// Object is not of the type it should be. Or not an object at all.
+    TypeMismatch = 8,
+    /// Would stop the caller, e.g. suspending its own process.
+    CurrentProcess = 9,
+    /// Can block, or waits on another thread. Only served on the async worker, send it with `send_async`.
+    AsyncOnly = 10,
+    /// Handle has `OBJ_PROTECT_CLOSE`. Its owner has to lift that first.
+    HandleProtected = 11,
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -21,6 +34,7 @@ pub enum NotFoundReason {
     Event = 9,
     Field = 10,
     Handle = 11,
+    OutputBuffer = 12,
 }
 
 impl NotFoundReason {
@@ -39,6 +53,7 @@ impl NotFoundReason {
             9 => Self::Event,
             10 => Self::Field,
             11 => Self::Handle,
+            12 => Self::OutputBuffer,
             _ => Self::Unknown
         }
     }
@@ -46,15 +61,38 @@ impl NotFoundReason {
 
 impl NotAllowedReason {
     pub const fn into_bits(self) -> u32 {
-        self as _
+        match self {
+            Self::Unknown => 0,
+            Self::LockHeld => 2,
+            Self::PageNotPresent => 3,
+            Self::MappingsExist => 4,
+            Self::AccessViolation => 5,
+            Self::BufferTooSmall => 6,
+            Self::MissingPermission(x) => 7 | (x.bits().trailing_zeros() & 0xFF) << 8,
+            Self::TypeMismatch => 8,
+            Self::CurrentProcess => 9,
+            Self::AsyncOnly => 10,
+            Self::HandleProtected => 11,
+        }
     }
 
     pub const fn from_bits(value: u32) -> Self {
-        match value {
+        match value & 0xFF {
             2 => Self::LockHeld,
             3 => Self::PageNotPresent,
             4 => Self::MappingsExist,
             5 => Self::AccessViolation,
+            6 => Self::BufferTooSmall,
+            7 => match (value >> 8) & 0xFF {
+                x if x < u64::BITS => {
+                    Self::MissingPermission(PluginPermissions::from_bits_retain(1 << x))
+                }
+                _ => Self::MissingPermission(PluginPermissions::NONE),
+            },
+            8 => Self::TypeMismatch,
+            9 => Self::CurrentProcess,
+            10 => Self::AsyncOnly,
+            11 => Self::HandleProtected,
             _ => Self::Unknown,
         }
     }
@@ -1,52 +1,246 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub enum ServiceFunction {
-    // Separated by CATEGORY - FUNCTION
-    GetState = 0b_0000_0000,
-
-    OpenProcess = 0b_0001_0000,
-    CloseProcess = 0b_0001_0001,
-    GetProcessField = 0b_0001_0010,
-    SetProcessField = 0b_0001_0011,
-
-    RegisterNotifyEvent = 0b_0010_0000,
-    UnregisterNotifyEvent = 0b_0010_0001,
-
-    AllocateMemory = 0b_0011_0000,
-    FreeMemory = 0b_0011_0001,
-    GetSetPageAttribute = 0b_0011_0010,
-    MapRawMemoryDescriptor = 0b_0011_0011,
-    TranslateAddress = 0b_0011_0100,
-    DescribePhysicalMemory = 0b_0011_0101,
-
-    OpenThread = 0b_0100_0000,
-    CloseThread = 0b_0100_0001,
-    GetThreadField = 0b_0100_0010,
-    SetThreadField = 0b_0100_0011,
-
-    OpenToken = 0b_0101_0000,
-    CloseToken = 0b_0101_0001,
-    GetTokenField = 0b_0101_0011,
-    SetTokenField = 0b_0101_0100,
-
-    MsrIo = 0b_0110_0000,
-    ExecutePrivilegedInstruction = 0b_0110_0001,
-    InterProcessorInterrupt = 0b_0110_0010,
-
-    UpgradeHandle = 0b_0111_0000,
-    GetHandleObject = 0b_0111_0001,
-    SwapHandleObject = 0b_0111_0010,
+use crate::error::HxError;
+use crate::hxposed::error::NotFoundReason;
+use crate::hxposed::permissions::PluginPermissions;
 
+///
+/// # HxPosed Services
+///
+/// The registry of every service function. Ids, [`HxCall`](crate::hxposed::call::HxCall) constructors,
+/// request and response types, and handlers are declared here, and nowhere else.
+///
+/// Passes the list to the macro named `$callback`, which generates whatever it needs out of it.
+/// [`ServiceFunction`] and the `HxCall` constructors are generated this way, so are the dispatch tables of the driver and the simulator.
+///
+/// ## Entries
+/// `Name = id => constructor(Request) -> Response, handler, PERMISSION | PERMISSION;`
+///
+/// * `id` - `CATEGORY << 4 | FUNCTION`.
+/// * `constructor` - Name of the `HxCall` constructor.
+/// * `handler` - Path of the handler, relative to the module the dispatch table is generated in.
+/// * `PERMISSION` - [`PluginPermissions`](crate::hxposed::permissions::PluginPermissions) the caller must have. All of them.
+///
+/// Entries without the part after `id` are reserved. They get an id, and nothing else.
+///
+/// ## Remarks
+/// - Handlers take the request and return an [`HxResponse`](crate::hxposed::responses::HxResponse).
+///
+#[macro_export]
+macro_rules! hx_services {
+    ($callback:ident) => {
+        $callback! {
+            GetState = 0x00 => get_status(
+                $crate::hxposed::requests::status::StatusRequest
+            ) -> $crate::hxposed::responses::status::StatusResponse, get_state, NONE;
+            Batch = 0x01 => batch(
+                $crate::hxposed::requests::batch::BatchRequest
+            ) -> $crate::hxposed::responses::batch::BatchResponse, batch_services::batch, NONE;
+            RegisterOutputBuffer = 0x02 => register_output_buffer(
+                $crate::hxposed::requests::output::RegisterOutputBufferRequest
+            ) -> $crate::hxposed::responses::output::RegisterOutputBufferResponse, output_services::register_output_buffer, NONE;
+
+            OpenProcess = 0x10 => open_process(
+                $crate::hxposed::requests::process::OpenProcessRequest
+            ) -> $crate::hxposed::responses::OpenObjectResponse, process_services::open_process, PROCESS_EXECUTIVE;
+            CloseProcess = 0x11 => close_process(
+                $crate::hxposed::requests::process::CloseProcessRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, process_services::close_process, PROCESS_EXECUTIVE;
+            GetProcessField = 0x12 => get_process_field(
+                $crate::hxposed::requests::process::GetProcessFieldRequest
+            ) -> $crate::hxposed::responses::process::GetProcessFieldResponse, process_services::get_process_field_sync, PROCESS_EXECUTIVE;
+            SetProcessField = 0x13 => set_process_field(
+                $crate::hxposed::requests::process::SetProcessFieldRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, process_services::set_process_field_sync, PROCESS_SECURITY;
+            KillProcess = 0x14 => kill_process(
+                $crate::hxposed::requests::process::KillProcessRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, process_services::kill_process, PROCESS_SECURITY;
+            EnumerateProcesses = 0x15 => enumerate_processes(
+                $crate::hxposed::requests::process::EnumerateProcessesRequest
+            ) -> $crate::hxposed::responses::process::EnumerateProcessesResponse, process_services::enumerate_processes, PROCESS_EXECUTIVE;
+            EnumerateModules = 0x16 => enumerate_modules(
+                $crate::hxposed::requests::process::EnumerateModulesRequest
+            ) -> $crate::hxposed::responses::process::EnumerateModulesResponse, process_services::enumerate_modules, PROCESS_EXECUTIVE | MEMORY_VIRTUAL;
+            SuspendProcess = 0x17 => suspend_process(
+                $crate::hxposed::requests::process::SuspendProcessRequest
+            ) -> $crate::hxposed::responses::thread::SuspendCountResponse, process_services::suspend_process, PROCESS_EXECUTIVE;
+            ResumeProcess = 0x18 => resume_process(
+                $crate::hxposed::requests::process::ResumeProcessRequest
+            ) -> $crate::hxposed::responses::thread::SuspendCountResponse, process_services::resume_process, PROCESS_EXECUTIVE;
+
+            RegisterNotifyEvent = 0x20 => register_notify_event(
+                $crate::hxposed::requests::notify::RegisterNotifyHandlerRequest
+            ) -> $crate::hxposed::responses::notify::RegisterNotifyHandlerResponse, callback_services::register_callback_receiver, NOTIFY_EVENTS;
+            UnregisterNotifyEvent = 0x21 => unregister_notify_event(
+                $crate::hxposed::requests::notify::UnregisterNotifyHandlerRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, callback_services::unregister_callback_receiver, NOTIFY_EVENTS;
+
+            AllocateMemory = 0x30 => mem_alloc(
+                $crate::hxposed::requests::memory::AllocateMemoryRequest
+            ) -> $crate::hxposed::responses::memory::AllocateMemoryResponse, memory_services::allocate_memory, MEMORY_ALLOCATION;
+            FreeMemory = 0x31 => free_mem(
+                $crate::hxposed::requests::memory::FreeMemoryRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, memory_services::free_memory, MEMORY_ALLOCATION;
+            GetSetPageAttribute = 0x32 => set_page_attr(
+                $crate::hxposed::requests::memory::PageAttributeRequest
+            ) -> $crate::hxposed::responses::memory::PageAttributeResponse, memory_services::get_set_page_attribute, MEMORY_VIRTUAL;
+            MapRawMemoryDescriptor = 0x33 => rmd_map(
+                $crate::hxposed::requests::memory::MapRmdRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, memory_services::map_va_to_pa, MEMORY_VIRTUAL;
+            TranslateAddress = 0x34 => translate_address(
+                $crate::hxposed::requests::memory::TranslateAddressRequest
+            ) -> $crate::hxposed::responses::memory::TranslateAddressResponse, memory_services::translate_address, MEMORY_PHYSICAL;
+            DescribePhysicalMemory = 0x35 => describe_physical(
+                $crate::hxposed::requests::memory::DescribeMemoryRequest
+            ) -> $crate::hxposed::responses::memory::DescribeMemoryResponse, memory_services::describe_memory, MEMORY_PHYSICAL;
+            ReadVirtualMemory = 0x36 => read_virtual(
+                $crate::hxposed::requests::memory::ReadVirtualMemoryRequest
+            ) -> $crate::hxposed::responses::memory::VirtualMemoryResponse, memory_services::read_virtual_memory, MEMORY_VIRTUAL;
+            WriteVirtualMemory = 0x37 => write_virtual(
+                $crate::hxposed::requests::memory::WriteVirtualMemoryRequest
+            ) -> $crate::hxposed::responses::memory::VirtualMemoryResponse, memory_services::write_virtual_memory, MEMORY_VIRTUAL;
+            EnumerateMemoryRegions = 0x38 => memory_regions(
+                $crate::hxposed::requests::memory::EnumerateMemoryRegionsRequest
+            ) -> $crate::hxposed::responses::memory::MemoryRegionsResponse, memory_services::enumerate_memory_regions, MEMORY_VIRTUAL;
+
+            OpenThread = 0x40 => open_thread(
+                $crate::hxposed::requests::thread::OpenThreadRequest
+            ) -> $crate::hxposed::responses::OpenObjectResponse, thread_services::open_thread_sync, THREAD_EXECUTIVE;
+            CloseThread = 0x41 => close_thread(
+                $crate::hxposed::requests::thread::CloseThreadRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, thread_services::close_thread_sync, THREAD_EXECUTIVE;
+            GetThreadField = 0x42 => get_thread_field(
+                $crate::hxposed::requests::thread::GetThreadFieldRequest
+            ) -> $crate::hxposed::responses::thread::GetThreadFieldResponse, thread_services::get_thread_field_sync, THREAD_EXECUTIVE;
+            SetThreadField = 0x43 => set_thread_field(
+                $crate::hxposed::requests::thread::SetThreadFieldRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, thread_services::set_thread_field_sync, THREAD_SECURITY;
+            SuspendThread = 0x44 => suspend_thread(
+                $crate::hxposed::requests::thread::SuspendThreadRequest
+            ) -> $crate::hxposed::responses::thread::SuspendCountResponse, thread_services::suspend_thread, THREAD_EXECUTIVE;
+            ResumeThread = 0x45 => resume_thread(
+                $crate::hxposed::requests::thread::ResumeThreadRequest
+            ) -> $crate::hxposed::responses::thread::SuspendCountResponse, thread_services::resume_thread, THREAD_EXECUTIVE;
+
+            OpenToken = 0x50 => open_token(
+                $crate::hxposed::requests::security::OpenTokenRequest
+            ) -> $crate::hxposed::responses::OpenObjectResponse, security_services::open_token_sync, SECURITY_MANAGE;
+            CloseToken = 0x51 => close_token(
+                $crate::hxposed::requests::security::CloseTokenRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, security_services::close_token_sync, SECURITY_MANAGE;
+            GetTokenField = 0x53 => get_token_field(
+                $crate::hxposed::requests::security::GetTokenFieldRequest
+            ) -> $crate::hxposed::responses::security::GetTokenFieldResponse, security_services::get_token_field_sync, SECURITY_MANAGE;
+            SetTokenField = 0x54 => set_token_field(
+                $crate::hxposed::requests::security::SetTokenFieldRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, security_services::set_token_field_sync, SECURITY_MANAGE;
+
+            MsrIo = 0x60 => msr_io(
+                $crate::hxposed::requests::io::MsrIoRequest
+            ) -> $crate::hxposed::responses::io::MsrIoResponse, io_services::rw_msr, PRIVILEGED_IO;
+            ExecutePrivilegedInstruction = 0x61 => exec_priv(
+                $crate::hxposed::requests::io::PrivilegedInstructionRequest
+            ) -> $crate::hxposed::responses::io::PrivilegedInstructionResponse, io_services::exec_privileged, PRIVILEGED_IO;
+            InterProcessorInterrupt = 0x62;
+
+            UpgradeHandle = 0x70 => upgrade_handle(
+                $crate::hxposed::requests::handle::UpgradeHandleRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, handle_services::upgrade_handle, HANDLE_MANAGE;
+            GetHandleObject = 0x71 => get_handle_obj(
+                $crate::hxposed::requests::handle::GetHandleObjectRequest
+            ) -> $crate::hxposed::responses::handle::GetHandleObjectResponse, handle_services::get_handle_obj, HANDLE_MANAGE;
+            SwapHandleObject = 0x72 => swap_handle_obj(
+                $crate::hxposed::requests::handle::SwapHandleObjectRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, handle_services::swap_handle_obj, HANDLE_MANAGE;
+            EnumerateHandles = 0x73 => enumerate_handles(
+                $crate::hxposed::requests::handle::EnumerateHandlesRequest
+            ) -> $crate::hxposed::responses::handle::EnumerateHandlesResponse, handle_services::enumerate_handles, PROCESS_EXECUTIVE | HANDLE_MANAGE;
+            CreateHandle = 0x74 => create_handle(
+                $crate::hxposed::requests::handle::CreateHandleRequest
+            ) -> $crate::hxposed::responses::handle::CreateHandleResponse, handle_services::create_handle, HANDLE_MANAGE;
+            CloseRemoteHandle = 0x75 => close_remote_handle(
+                $crate::hxposed::requests::handle::CloseRemoteHandleRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, handle_services::close_remote_handle, HANDLE_MANAGE;
+            DowngradeHandle = 0x76 => downgrade_handle(
+                $crate::hxposed::requests::handle::DowngradeHandleRequest
+            ) -> $crate::hxposed::responses::empty::EmptyResponse, handle_services::downgrade_handle, HANDLE_MANAGE;
+        }
+    };
 }
 
-impl ServiceFunction {
-    pub const fn into_bits(self) -> u16 {
-        self as u16
-    }
+macro_rules! service_function {
+    ($(
+        $name:ident = $id:literal $(=> $ctor:ident($req:ty) -> $resp:ty, $handler:path, $($perm:ident)|+)?;
+    )*) => {
+        ///
+        /// # Service Function
+        ///
+        /// Id of a service. Separated by `CATEGORY - FUNCTION`. Generated from [`hx_services`](crate::hx_services).
+        ///
+        #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+        #[repr(u16)]
+        pub enum ServiceFunction {
+            $($name = $id,)*
+            /// Not a service function. What unknown ids decode to.
+            Unknown = 0xFFFF,
+        }
 
-    pub const fn from_bits(bits: u8) -> Self {
-        // get rekt
-        unsafe {
-            core::mem::transmute(bits)
+        impl ServiceFunction {
+            pub const fn into_bits(self) -> u16 {
+                self as u16
+            }
+
+            ///
+            /// # From Bits
+            ///
+            /// Unknown ids become [`Self::Unknown`]. Use [`TryFrom`] to tell them apart.
+            ///
+            pub const fn from_bits(bits: u16) -> Self {
+                match bits {
+                    $($id => Self::$name,)*
+                    _ => Self::Unknown,
+                }
+            }
+
+            ///
+            /// # Permissions
+            ///
+            /// Permissions the caller must have to call this. Reserved and unknown functions require none, they fail anyway.
+            ///
+            pub const fn permissions(self) -> PluginPermissions {
+                match self {
+                    $($(Self::$name => PluginPermissions::NONE$(.union(PluginPermissions::$perm))+,)?)*
+                    _ => PluginPermissions::NONE,
+                }
+            }
+        }
+
+        // the registry must agree with SyscallRequest::Response.
+        #[allow(dead_code)]
+        const _: () = {
+            fn same<T: crate::hxposed::requests::SyscallRequest<Response = R>, R>() {}
+            fn check() {
+                $($(
+                    same::<$req, $resp>();
+                )?)*
+            }
+        };
+    };
+}
+
+hx_services!(service_function);
+
+impl TryFrom<u16> for ServiceFunction {
+    type Error = HxError;
+
+    ///
+    /// # Try From
+    ///
+    /// ## Return
+    /// * [`ServiceFunction`] - Function with the id `value`.
+    /// * [`HxError::NotFound`] - [`NotFoundReason::ServiceFunction`]. No function has that id.
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match Self::from_bits(value) {
+            Self::Unknown => Err(HxError::NotFound(NotFoundReason::ServiceFunction)),
+            x => Ok(x),
         }
     }
-}
\ No newline at end of file
+}
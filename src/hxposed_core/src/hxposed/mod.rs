@@ -1,9 +1,13 @@
 pub mod call;
 pub mod error;
 pub mod func;
+pub mod output;
+pub mod permissions;
+pub mod raw;
 pub mod requests;
 pub mod responses;
 pub mod status;
+pub mod transport;
 pub mod utils;
 
 pub type HxObject = u64;
@@ -15,38 +19,18 @@ pub type RmdObject = HxObject;
 pub type CallbackObject = HxObject;
 pub type AsyncCookie = HxObject;
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
-pub enum ObjectType {
-    Process(ProcessObject),
-    Thread(ThreadObject),
-    Token(TokenObject),
-    Rmd(RmdObject),
-    Registry(u64),
-    Unknown
-}
+use hxposed_macros::RawEnum;
 
-impl ObjectType {
-    pub fn into_raw(self) -> (u64, u64) {
-        match self {
-            ObjectType::Process(p) => (1, p),
-            ObjectType::Thread(t) => (2, t),
-            ObjectType::Token(t) => (3, t),
-            ObjectType::Rmd(m) => (4, m),
-            ObjectType::Registry(r) => (5, r),
-            ObjectType::Unknown => (0, 0),
-        }
-    }
-
-    pub fn from_raw(object: u64, value: u64) -> ObjectType {
-        match object {
-            1 => ObjectType::Process(value),
-            2 => ObjectType::Thread(value),
-            3 => ObjectType::Token(value),
-            4 => ObjectType::Rmd(value),
-            5 => ObjectType::Registry(value),
-            _ => ObjectType::Unknown,
-        }
-    }
+#[derive(Clone, Copy, Eq, PartialEq, Debug, RawEnum)]
+#[repr(u64)]
+pub enum ObjectType {
+    Process(ProcessObject) = 1,
+    Thread(ThreadObject) = 2,
+    Token(TokenObject) = 3,
+    Rmd(RmdObject) = 4,
+    Registry(u64) = 5,
+    #[raw(unknown)]
+    Unknown = 0,
 }
 
 impl Into<u64> for ObjectType {
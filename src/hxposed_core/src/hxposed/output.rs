@@ -0,0 +1,99 @@
+use bitfield_struct::bitfield;
+
+///
+/// # Output Buffer Header
+///
+/// Sits at the start of the output buffer. Variable-length results (paths, thread lists and such) are written after it,
+/// and returned as [`OutputSlice`]s.
+///
+/// The hypervisor allocates the buffer and maps it into the process, wherever there's room, on `RegisterOutputBuffer`.
+/// One per process.
+///
+/// ## Remarks
+/// - Results are written at `cursor`. When one does not fit before the end, the call fails with `BufferTooSmall`.
+///   Registering again empties the buffer.
+/// - The hypervisor keeps its own copy of this, and only mirrors it here. Changing it does nothing.
+///
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct OutputBufferHeader {
+    /// Size of the whole buffer, header included.
+    pub size: u32,
+    /// Offset the next result is written at.
+    pub cursor: u32,
+}
+
+impl OutputBufferHeader {
+    /// Size of the header. Results never start before this.
+    pub const SIZE: u32 = size_of::<Self>() as _;
+    /// Largest buffer the hypervisor allocates. 16 MiB.
+    pub const MAX_SIZE: u32 = 0x100_0000;
+
+    pub const fn new(size: u32) -> Self {
+        Self {
+            size,
+            cursor: Self::SIZE,
+        }
+    }
+
+    ///
+    /// # Reserve
+    ///
+    /// Finds room for a result of `length` bytes, and moves the cursor past it.
+    ///
+    /// ## Remarks
+    /// - Never wraps around. Results before the cursor may not have been read yet.
+    ///
+    /// ## Return
+    /// * [`OutputSlice`] - Where to write the result.
+    /// * [`None`] - Result does not fit in what's left of the buffer.
+    pub fn reserve(&mut self, length: u32) -> Option<OutputSlice> {
+        if self.cursor < Self::SIZE || length > self.size.saturating_sub(self.cursor) {
+            return None;
+        }
+
+        let slice = OutputSlice::new()
+            .with_offset(self.cursor)
+            .with_length(length);
+
+        // keep results 8 byte aligned.
+        self.cursor = self.cursor.saturating_add(length.next_multiple_of(8)).min(self.size);
+
+        Some(slice)
+    }
+
+    ///
+    /// # Remaining
+    ///
+    /// Bytes left for results.
+    ///
+    pub const fn remaining(&self) -> u32 {
+        self.size.saturating_sub(self.cursor)
+    }
+}
+
+///
+/// # Output Slice
+///
+/// A result in the output buffer. See [`OutputBufferHeader`].
+///
+#[bitfield(u64)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct OutputSlice {
+    /// Offset from the start of the buffer, in bytes.
+    pub offset: u32,
+    /// Length in bytes.
+    pub length: u32,
+}
+
+impl OutputSlice {
+    ///
+    /// # Is Within
+    ///
+    /// Checks that the slice lies in a buffer of `size` bytes, and not in its header.
+    ///
+    pub fn is_within(&self, size: u32) -> bool {
+        self.offset() >= OutputBufferHeader::SIZE
+            && (self.offset() as u64 + self.length() as u64) <= size as u64
+    }
+}
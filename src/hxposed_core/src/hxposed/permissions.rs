@@ -0,0 +1,64 @@
+use crate::hxposed::error::NotAllowedReason;
+use bitflag::bitflag;
+
+///
+/// # Plugin Permissions
+///
+/// What a caller is allowed to do. Every service function requires some of these, see [`hx_services`](crate::hx_services).
+///
+/// Granted per verified caller by HxGuard, out of `CallerPermissions` under the `CallerVerification` key.
+/// Entries there are parallel to `VerifiedCallers`. When caller verification is off, everyone gets everything.
+///
+/// ## Remarks
+/// - `*_EXECUTIVE` permissions open and query objects. `*_SECURITY` ones change them.
+///
+#[bitflag(u64)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+#[allow(non_camel_case_types)] // they are constants to everyone using them.
+pub enum PluginPermissions {
+    #[default]
+    NONE = 0,
+    /// Open processes and query them.
+    PROCESS_EXECUTIVE = 1 << 0,
+    /// Change protection, signers, mitigations and tokens of processes. Kill them.
+    PROCESS_SECURITY = 1 << 1,
+    /// Open threads and query them.
+    THREAD_EXECUTIVE = 1 << 2,
+    /// Change impersonation of threads.
+    THREAD_SECURITY = 1 << 3,
+    /// Open tokens, query and change them.
+    SECURITY_MANAGE = 1 << 4,
+    /// Map memory into address spaces, walk their regions, change page attributes, read and write memory of other processes.
+    MEMORY_VIRTUAL = 1 << 5,
+    /// Translate addresses and describe physical memory.
+    MEMORY_PHYSICAL = 1 << 6,
+    /// Allocate and free memory.
+    MEMORY_ALLOCATION = 1 << 7,
+    /// Receive notifications of objects being created and deleted.
+    NOTIFY_EVENTS = 1 << 8,
+    /// Read and write MSRs, execute privileged instructions.
+    PRIVILEGED_IO = 1 << 9,
+    /// Walk handle tables, upgrade handles and swap the objects behind them.
+    HANDLE_MANAGE = 1 << 10,
+}
+
+impl PluginPermissions {
+    ///
+    /// # Require
+    ///
+    /// Checks that `self` has everything in `required`.
+    ///
+    /// ## Return
+    /// * Nothing - All there.
+    /// * [`NotAllowedReason::MissingPermission`] - Naming the first permission missing.
+    pub const fn require(self, required: Self) -> Result<(), NotAllowedReason> {
+        let missing = required.difference(self);
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        Err(NotAllowedReason::MissingPermission(Self::from_bits_retain(
+            1 << missing.bits().trailing_zeros(),
+        )))
+    }
+}
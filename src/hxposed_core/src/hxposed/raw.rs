@@ -0,0 +1,149 @@
+use crate::hxposed::output::OutputSlice;
+use crate::hxposed::requests::io::MsrOperation;
+use crate::hxposed::requests::memory::{MapOperation, MemoryType, PageAttributeOperation, Va};
+use crate::services::types::process_fields::{
+    MitigationOptions, MitigationOptions3, ProcessProtection, ProcessSignatureLevels,
+};
+use crate::services::types::security_fields::{ImpersonationLevel, TokenPrivilege, TokenType};
+
+///
+/// # Raw Value
+///
+/// A value that fits in a single argument of [`HxRequest`](crate::hxposed::requests::HxRequest) or [`HxResponse`](crate::hxposed::responses::HxResponse).
+///
+/// Fields of types derived with `SyscallRequest`, `SyscallResponse` and `RawEnum` must implement this.
+///
+pub trait RawValue: Sized {
+    fn into_raw_value(self) -> u64;
+    fn from_raw_value(raw: u64) -> Self;
+}
+
+///
+/// # Raw Extended Value
+///
+/// A value that fits in an extended argument of [`HxRequest`](crate::hxposed::requests::HxRequest) or [`HxResponse`](crate::hxposed::responses::HxResponse).
+///
+/// Every [`RawValue`] is one. So are `u128` and `[u8; 16]`, which use the whole register.
+///
+pub trait RawExtendedValue: Sized {
+    fn into_raw_extended(self) -> u128;
+    fn from_raw_extended(raw: u128) -> Self;
+}
+
+impl<T: RawValue> RawExtendedValue for T {
+    fn into_raw_extended(self) -> u128 {
+        self.into_raw_value() as _
+    }
+
+    fn from_raw_extended(raw: u128) -> Self {
+        T::from_raw_value(raw as _)
+    }
+}
+
+impl RawExtendedValue for u128 {
+    fn into_raw_extended(self) -> u128 {
+        self
+    }
+
+    fn from_raw_extended(raw: u128) -> Self {
+        raw
+    }
+}
+
+impl RawExtendedValue for [u8; 16] {
+    fn into_raw_extended(self) -> u128 {
+        u128::from_le_bytes(self)
+    }
+
+    fn from_raw_extended(raw: u128) -> Self {
+        raw.to_le_bytes()
+    }
+}
+
+macro_rules! raw_value_int {
+    ($($ty:ty),*) => {
+        $(
+            impl RawValue for $ty {
+                fn into_raw_value(self) -> u64 {
+                    self as _
+                }
+
+                fn from_raw_value(raw: u64) -> Self {
+                    raw as _
+                }
+            }
+        )*
+    };
+}
+
+// for the types with into_bits/from_bits.
+macro_rules! raw_value_bits {
+    ($($ty:ty),*) => {
+        $(
+            impl RawValue for $ty {
+                fn into_raw_value(self) -> u64 {
+                    self.into_bits() as _
+                }
+
+                fn from_raw_value(raw: u64) -> Self {
+                    Self::from_bits(raw as _)
+                }
+            }
+        )*
+    };
+}
+
+raw_value_int!(u8, u16, u32, u64);
+
+raw_value_bits!(
+    ProcessProtection,
+    ProcessSignatureLevels,
+    MitigationOptions,
+    MitigationOptions3,
+    TokenType,
+    ImpersonationLevel,
+    MapOperation,
+    PageAttributeOperation,
+    MsrOperation,
+    OutputSlice
+);
+
+impl RawValue for bool {
+    fn into_raw_value(self) -> u64 {
+        self as _
+    }
+
+    fn from_raw_value(raw: u64) -> Self {
+        raw == 1
+    }
+}
+
+impl RawValue for TokenPrivilege {
+    fn into_raw_value(self) -> u64 {
+        self.bits()
+    }
+
+    fn from_raw_value(raw: u64) -> Self {
+        TokenPrivilege::from_bits_truncate(raw)
+    }
+}
+
+impl RawValue for MemoryType {
+    fn into_raw_value(self) -> u64 {
+        self.into()
+    }
+
+    fn from_raw_value(raw: u64) -> Self {
+        raw.into()
+    }
+}
+
+impl RawValue for Va {
+    fn into_raw_value(self) -> u64 {
+        self.into()
+    }
+
+    fn from_raw_value(raw: u64) -> Self {
+        raw.into()
+    }
+}
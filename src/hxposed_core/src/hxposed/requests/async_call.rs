@@ -0,0 +1,42 @@
+use crate::hxposed::requests::HxRequest;
+
+///
+/// # Async Info
+///
+/// Tells the hypervisor where to report completion of a slow call (see [`HxCall::is_slow`](crate::hxposed::call::HxCall::is_slow)).
+///
+/// Travels in `extended_arg4` of the request. So slow calls can use 3 extended arguments at most.
+///
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
+pub struct AsyncInfo {
+    /// Event signaled on completion.
+    pub event: u64,
+    /// Pointer to an [`AsyncCompletion`](crate::hxposed::responses::async_call::AsyncCompletion).
+    pub completion: u64,
+}
+
+impl AsyncInfo {
+    pub fn into_raw(self) -> u128 {
+        self.event as u128 | (self.completion as u128) << 64
+    }
+
+    pub fn from_raw(raw: u128) -> Self {
+        Self {
+            event: raw as u64,
+            completion: (raw >> 64) as u64,
+        }
+    }
+
+    ///
+    /// # Attach
+    ///
+    /// Turns `request` into a slow call reporting to this.
+    ///
+    pub fn attach(self, request: &mut HxRequest) {
+        request.call = request
+            .call
+            .with_is_slow(true)
+            .with_extended_args_present(true);
+        request.extended_arg4 = self.into_raw();
+    }
+}
@@ -0,0 +1,30 @@
+use crate::hxposed::responses::batch::BatchResponse;
+use hxposed_macros::SyscallRequest;
+
+///
+/// # Batch Request
+///
+/// Executes `count` requests at `requests`, writing their responses to `responses`. In one kernel entry.
+///
+/// ## Remarks
+/// - Requests are executed in order. A failing request does not stop the ones after it. Check each response.
+/// - Nested batches are refused with [`HxError::InvalidParameters`](crate::error::HxError::InvalidParameters).
+/// - See [`HxBatch`](crate::services::batch::HxBatch) for a typed wrapper.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = batch, response = BatchResponse)]
+pub struct BatchRequest {
+    /// Pointer to an array of [`HxRequest`](crate::hxposed::requests::HxRequest).
+    #[raw(arg1)]
+    pub requests: u64,
+    /// Pointer to an array of [`HxResponse`](crate::hxposed::responses::HxResponse). Same length as `requests`.
+    #[raw(arg2)]
+    pub responses: u64,
+    #[raw(arg3)]
+    pub count: u64,
+}
+
+impl BatchRequest {
+    /// Most requests a single batch can hold.
+    pub const MAX_COUNT: u64 = 256;
+}
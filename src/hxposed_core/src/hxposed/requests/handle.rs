@@ -1,89 +1,112 @@
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
-use crate::hxposed::call::HxCall;
 use crate::hxposed::responses::empty::EmptyResponse;
-use crate::hxposed::{Handle, ProcessObject};
-use crate::hxposed::responses::handle::GetHandleObjectResponse;
+use crate::hxposed::{Handle, ObjectType, ProcessObject};
+use crate::hxposed::responses::handle::{CreateHandleResponse, EnumerateHandlesResponse, GetHandleObjectResponse};
+use hxposed_macros::SyscallRequest;
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = upgrade_handle, response = EmptyResponse)]
 pub struct UpgradeHandleRequest {
+    #[raw(arg1)]
     pub handle: Handle,
+    #[raw(arg2)]
     pub process: ProcessObject,
+    #[raw(arg3)]
     pub access_rights: u32,
 }
 
-#[derive(Clone, Default, Debug)]
+///
+/// # Swap Handle Object Request
+///
+/// Points `handle` in the handle table of `process` to `object`.
+///
+/// ## Remarks
+/// - `object` must be an object body the driver can read the header of.
+/// - And of the same type as the object the handle points to now, unless `force` is set.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = swap_handle_obj, response = EmptyResponse)]
 pub struct SwapHandleObjectRequest {
+    #[raw(arg1)]
     pub handle: Handle,
+    #[raw(arg2)]
     pub process: ProcessObject,
+    #[raw(arg3)]
     pub object: u64,
+    #[raw(extended_arg1)]
+    pub force: bool,
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = get_handle_obj, response = GetHandleObjectResponse)]
 pub struct GetHandleObjectRequest {
+    #[raw(arg1)]
     pub handle: Handle,
+    #[raw(arg2)]
     pub process: ProcessObject,
 }
 
-impl SyscallRequest for GetHandleObjectRequest {
-    type Response = GetHandleObjectResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::upgrade_handle(),
-            arg1: self.handle,
-            arg2: self.process,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            handle: request.arg1,
-            process: request.arg2,
-        }
-    }
+///
+/// # Enumerate Handles Request
+///
+/// Walks the handle table (`_EPROCESS.ObjectTable`) of `process`.
+///
+/// ## Remarks
+/// - Result is one blob in the output buffer. See [`EnumerateHandlesResponse`].
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = enumerate_handles, response = EnumerateHandlesResponse)]
+pub struct EnumerateHandlesRequest {
+    #[raw(arg1)]
+    pub process: ProcessObject,
 }
 
-impl SyscallRequest for UpgradeHandleRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::upgrade_handle(),
-            arg1: self.handle,
-            arg2: self.process,
-            arg3: self.access_rights as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            handle: request.arg1,
-            process: request.arg2,
-            access_rights: request.arg3 as _,
-        }
-    }
+///
+/// # Create Handle Request
+///
+/// Opens a handle to `object` in the handle table of the caller.
+///
+/// ## Remarks
+/// - `object` is one the caller has open. Only [`ObjectType::Process`], [`ObjectType::Thread`] and [`ObjectType::Token`].
+/// - Access checks are skipped. `access_rights` is granted as is.
+///
+#[derive(Clone, Debug, SyscallRequest)]
+#[syscall(call = create_handle, response = CreateHandleResponse)]
+pub struct CreateHandleRequest {
+    #[raw(arg1, arg2)]
+    pub object: ObjectType,
+    #[raw(arg3)]
+    pub access_rights: u32,
 }
 
-impl SyscallRequest for SwapHandleObjectRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::swap_handle_obj(),
-            arg1: self.handle,
-            arg2: self.process,
-            arg3: self.object,
-            ..Default::default()
-        }
-    }
+///
+/// # Close Remote Handle Request
+///
+/// Closes `handle` in the handle table of `process`, as its owner would. Not if it has `OBJ_PROTECT_CLOSE`.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = close_remote_handle, response = EmptyResponse)]
+pub struct CloseRemoteHandleRequest {
+    #[raw(arg1)]
+    pub handle: Handle,
+    #[raw(arg2)]
+    pub process: ProcessObject,
+}
 
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            handle: request.arg1,
-            process: request.arg2,
-            object: request.arg3,
-        }
-    }
+///
+/// # Downgrade Handle Request
+///
+/// Takes rights away from `handle` in the handle table of `process`. Rights not in `access_rights` are removed.
+///
+/// ## Remarks
+/// - Never adds rights. See [`UpgradeHandleRequest`] for that.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = downgrade_handle, response = EmptyResponse)]
+pub struct DowngradeHandleRequest {
+    #[raw(arg1)]
+    pub handle: Handle,
+    #[raw(arg2)]
+    pub process: ProcessObject,
+    #[raw(arg3)]
+    pub access_rights: u32,
 }
@@ -1,47 +1,109 @@
-use crate::hxposed::call::HxCall;
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::empty::EmptyResponse;
 use crate::hxposed::responses::memory::*;
 use crate::hxposed::{ProcessObject, RmdObject};
 use bit_field::BitField;
 use core::ops::{BitAnd, Shl};
+use hxposed_macros::{RawEnum, SyscallRequest};
 
-#[derive(Debug)]
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = mem_alloc, response = AllocateMemoryResponse)]
 pub struct AllocateMemoryRequest {
+    #[raw(arg1)]
     pub size: u32,
+    #[raw(arg2)]
     pub memory_type: MemoryType,
 }
 
-#[derive(Debug)]
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = describe_physical, response = DescribeMemoryResponse)]
 pub struct DescribeMemoryRequest {
+    #[raw(arg2)]
     pub size: u32,
+    #[raw(arg1)]
     pub pa: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = translate_address, response = TranslateAddressResponse)]
 pub struct TranslateAddressRequest {
+    #[raw(arg1)]
     pub addr_space: ProcessObject,
+    #[raw(arg2)]
     pub virtual_addr: u64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = free_mem, response = EmptyResponse)]
 pub struct FreeMemoryRequest {
+    #[raw(arg1)]
     pub obj: RmdObject,
 }
 
-#[derive(Debug)]
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = rmd_map, response = EmptyResponse)]
 pub struct MapRmdRequest {
+    #[raw(arg2)]
     pub addr_space: ProcessObject,
+    #[raw(arg1)]
     pub object: RmdObject,
+    #[raw(arg3)]
     pub map_addr: u64,
+    #[raw(extended_arg1)]
     pub operation: MapOperation,
 }
 
-#[derive(Debug)]
+///
+/// # Read Virtual Memory Request
+///
+/// Copies `size` bytes at `address` in `addr_space` to `buffer` in the caller.
+///
+/// ## Remarks
+/// - Stops at the first page that can't be read. See [`VirtualMemoryResponse`].
+///
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = read_virtual, response = VirtualMemoryResponse)]
+pub struct ReadVirtualMemoryRequest {
+    #[raw(arg1)]
+    pub addr_space: ProcessObject,
+    #[raw(arg2)]
+    pub address: u64,
+    #[raw(arg3)]
+    pub buffer: u64,
+    #[raw(extended_arg1)]
+    pub size: u32,
+}
+
+///
+/// # Write Virtual Memory Request
+///
+/// Copies `size` bytes from `buffer` in the caller to `address` in `addr_space`.
+///
+/// ## Remarks
+/// - Stops at the first page that can't be written. See [`VirtualMemoryResponse`].
+///
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = write_virtual, response = VirtualMemoryResponse)]
+pub struct WriteVirtualMemoryRequest {
+    #[raw(arg1)]
+    pub addr_space: ProcessObject,
+    #[raw(arg2)]
+    pub address: u64,
+    #[raw(arg3)]
+    pub buffer: u64,
+    #[raw(extended_arg1)]
+    pub size: u32,
+}
+
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = set_page_attr, response = PageAttributeResponse)]
 pub struct PageAttributeRequest {
+    #[raw(arg1)]
     pub addr_space: ProcessObject,
+    #[raw(extended_arg1, extended_arg2)]
     pub paging_type: PagingType,
+    #[raw(arg3)]
     pub type_bits: u64,
+    #[raw(arg2)]
     pub operation: PageAttributeOperation,
 }
 
@@ -69,11 +131,27 @@ impl From<u64> for MemoryType {
         match value {
             0 => MemoryType::NonPagedPool,
             1 => MemoryType::ContiguousPhysical,
+            2 => MemoryType::NonOwned,
             _ =>MemoryType::Unknown,
         }
     }
 }
 
+///
+/// # Enumerate Memory Regions Request
+///
+/// Walks the VAD tree of `addr_space`.
+///
+/// ## Remarks
+/// - Result is one blob in the output buffer. See [`MemoryRegionsResponse`].
+///
+#[derive(Clone, Debug, SyscallRequest)]
+#[syscall(call = memory_regions, response = MemoryRegionsResponse)]
+pub struct EnumerateMemoryRegionsRequest {
+    #[raw(arg1)]
+    pub addr_space: ProcessObject,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MapOperation {
     Map,
@@ -120,168 +198,16 @@ impl PageAttributeOperation {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, RawEnum)]
+#[repr(u64)]
 pub enum PagingType {
-    Pml5(Va),
-    Pml4(Va),
-    Pdp(Va),
-    Pd(Va),
-    Pt(Va),
-    Unknown
-}
-
-impl PagingType {
-    pub fn from_raw_enum(object: u64, value: u64) -> Self {
-        match object {
-            0 => Self::Pml5(Va::from(value)),
-            1 => Self::Pml4(Va::from(value)),
-            2 => Self::Pdp(Va::from(value)),
-            3 => Self::Pd(Va::from(value)),
-            4 => Self::Pt(Va::from(value)),
-            _ => PagingType::Unknown,
-        }
-    }
-
-    pub fn into_raw_enum(self) -> (u64, u64) {
-        match self {
-            PagingType::Pml5(x) => (0, x.into()),
-            PagingType::Pml4(x) => (1, x.into()),
-            PagingType::Pdp(x) => (2, x.into()),
-            PagingType::Pd(x) => (3, x.into()),
-            PagingType::Pt(x) => (4, x.into()),
-            PagingType::Unknown => (u64::MAX, u64::MAX),
-        }
-    }
-}
-
-impl SyscallRequest for FreeMemoryRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::free_mem(),
-            arg1: self.obj,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self { obj: request.arg1 }
-    }
-}
-
-impl SyscallRequest for DescribeMemoryRequest {
-    type Response = DescribeMemoryResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::describe_physical(),
-            arg1: self.pa,
-            arg2: self.size as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            pa: request.arg1,
-            size: request.arg2 as _,
-        }
-    }
-}
-
-impl SyscallRequest for AllocateMemoryRequest {
-    type Response = AllocateMemoryResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::mem_alloc(),
-            arg1: self.size as _,
-            arg2: self.memory_type.into(),
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            size: request.arg1 as _,
-            memory_type: request.arg2.into(),
-        }
-    }
-}
-
-impl SyscallRequest for TranslateAddressRequest {
-    type Response = TranslateAddressResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::translate_address(),
-            arg1: self.addr_space,
-            arg2: self.virtual_addr,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            addr_space: request.arg1,
-            virtual_addr: request.arg2,
-        }
-    }
-}
-
-impl SyscallRequest for MapRmdRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::rmd_map(),
-            arg1: self.object,
-            arg2: self.addr_space,
-            arg3: self.map_addr,
-            extended_arg1: self.operation.clone().into_bits() as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            object: request.arg1,
-            addr_space: request.arg2,
-            map_addr: request.arg3,
-            operation: MapOperation::from_bits(request.extended_arg1 as _),
-        }
-    }
-}
-
-impl SyscallRequest for PageAttributeRequest {
-    type Response = PageAttributeResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let args = self.paging_type.clone().into_raw_enum();
-        HxRequest {
-            call: HxCall::set_page_attr(),
-            arg1: self.addr_space,
-            arg2: self.operation.into_bits(),
-            arg3: self.type_bits,
-            extended_arg1: args.0 as _,
-            extended_arg2: args.1 as _,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            addr_space: request.arg1,
-            operation: PageAttributeOperation::from_bits(request.arg2),
-            type_bits: request.arg3,
-            paging_type: PagingType::from_raw_enum(
-                request.extended_arg1 as _,
-                request.extended_arg2 as _,
-            ),
-        }
-    }
+    Pml5(Va) = 0,
+    Pml4(Va) = 1,
+    Pdp(Va) = 2,
+    Pd(Va) = 3,
+    Pt(Va) = 4,
+    #[raw(unknown)]
+    Unknown = u64::MAX,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -3,13 +3,16 @@
 use crate::error::HxError;
 use crate::hxposed::call::HxCall;
 use crate::hxposed::responses::SyscallResponse;
-use crate::intern::instructions::vmcall;
+use crate::hxposed::transport::transport;
 use alloc::boxed::Box;
 use core::any::Any;
 use core::pin::Pin;
 
+pub mod async_call;
+pub mod batch;
 pub mod memory;
 pub mod notify;
+pub mod output;
 pub mod process;
 pub mod security;
 pub mod status;
@@ -18,6 +21,7 @@ pub mod io;
 pub mod handle;
 
 #[derive(Clone, Default, Debug)]
+#[repr(C)]
 pub struct HxRequest {
     pub call: HxCall,
     pub arg1: u64,
@@ -38,6 +42,7 @@ pub trait SyscallRequest {
 
 pub trait Syscall<T: SyscallRequest> {
     fn send(self) -> Result<T::Response, HxError>;
+    fn send_forget(self);
 }
 
 impl<T> Syscall<T> for T
@@ -45,11 +50,25 @@ where
     T: SyscallRequest,
 {
     fn send(self) -> Result<T::Response, HxError> {
-        let response = vmcall(&mut self.into_raw());
-        if response.result.error_code != 0 {
+        let response = transport().send(&mut self.into_raw());
+        if response.result.error_code() != 0 {
             Err(HxError::from_response(&response))
         } else {
             Ok(T::Response::from_raw(response))
         }
     }
+
+    ///
+    /// # Send Forget
+    ///
+    /// Sends the request with [`HxCall::ignore_result`] set. The hypervisor doesn't write a response back.
+    ///
+    /// ## Remarks
+    /// - Errors, even [`HxError::HvNotLoaded`], are not reported. Use it where the result would be thrown away anyway, like in `Drop`.
+    fn send_forget(self) {
+        let mut raw = self.into_raw();
+        raw.call.set_ignore_result(true);
+
+        let _ = transport().send(&mut raw);
+    }
 }
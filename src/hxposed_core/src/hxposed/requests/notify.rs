@@ -3,6 +3,7 @@ use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::empty::EmptyResponse;
 use crate::hxposed::responses::notify::{RegisterNotifyHandlerResponse};
 use crate::hxposed::{CallbackObject, ObjectType};
+use hxposed_macros::SyscallRequest;
 
 pub struct RegisterNotifyHandlerRequest {
     pub target_object: ObjectType,
@@ -10,33 +11,18 @@ pub struct RegisterNotifyHandlerRequest {
     pub memory: u64
 }
 
+#[derive(SyscallRequest)]
+#[syscall(call = unregister_notify_event, response = EmptyResponse)]
 pub struct UnregisterNotifyHandlerRequest {
+    #[raw(arg1)]
     pub callback: CallbackObject,
 }
 
-impl SyscallRequest for UnregisterNotifyHandlerRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::unregister_notify_event(),
-            arg1: self.callback,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            callback: request.arg1,
-        }
-    }
-}
-
 impl SyscallRequest for RegisterNotifyHandlerRequest {
     type Response = RegisterNotifyHandlerResponse;
 
     fn into_raw(self) -> HxRequest {
-        let args = self.target_object.into_raw();
+        let args = self.target_object.into_raw_enum();
         HxRequest {
             call: HxCall::register_notify_event(),
             arg1: args.0,
@@ -48,7 +34,7 @@ impl SyscallRequest for RegisterNotifyHandlerRequest {
 
     fn from_raw(request: &HxRequest) -> Self {
         Self {
-            target_object: ObjectType::from_raw(request.arg1, 0),
+            target_object: ObjectType::from_raw_enum(request.arg1, 0),
             memory: request.arg3,
             event_handle: request.arg2
         }
@@ -0,0 +1,19 @@
+use crate::hxposed::responses::output::RegisterOutputBufferResponse;
+use hxposed_macros::SyscallRequest;
+
+///
+/// # Register Output Buffer Request
+///
+/// Makes the hypervisor allocate an output buffer of `size` bytes, and map it into the calling process.
+///
+/// ## Remarks
+/// - The buffer is emptied. If `size` fits in the current one, that one is kept. Otherwise, it's replaced.
+/// - Replaced buffers stay mapped until the process exits. They are just not written to anymore.
+/// - See [`HxOutputBuffer`](crate::services::output::HxOutputBuffer) for the typed wrapper.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = register_output_buffer, response = RegisterOutputBufferResponse)]
+pub struct RegisterOutputBufferRequest {
+    #[raw(arg1)]
+    pub size: u32,
+}
@@ -1,168 +1,130 @@
 #![allow(dead_code)]
 
-use crate::hxposed::call::HxCall;
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::empty::{EmptyResponse};
 use crate::hxposed::responses::process::*;
+use crate::hxposed::responses::thread::SuspendCountResponse;
 use crate::hxposed::ProcessObject;
+use crate::hxposed::output::OutputSlice;
 use crate::hxposed::responses::OpenObjectResponse;
 use crate::services::types::process_fields::*;
+use hxposed_macros::{RawEnum, SyscallRequest};
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = open_process, response = OpenObjectResponse)]
 pub struct OpenProcessRequest {
+    #[raw(arg1)]
     pub process_id: u64,
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = close_process, response = EmptyResponse)]
 pub struct CloseProcessRequest {
+    #[raw(arg1)]
     pub process: ProcessObject,
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = kill_process, response = EmptyResponse)]
 pub struct KillProcessRequest {
+    #[raw(arg1)]
     pub process: ProcessObject,
+    /// NT status the process exits with.
+    #[raw(arg2)]
     pub exit_code: u32,
 }
 
-#[derive(Debug, Clone)]
-pub struct GetProcessFieldRequest {
+///
+/// # Suspend Process Request
+///
+/// Suspends every thread of `process`. Threads created after this are not.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = suspend_process, response = SuspendCountResponse)]
+pub struct SuspendProcessRequest {
+    #[raw(arg1)]
     pub process: ProcessObject,
-    pub field: ProcessField,
 }
 
-#[derive(Debug)]
-pub struct SetProcessFieldRequest {
+///
+/// # Resume Process Request
+///
+/// Undoes one [`SuspendProcessRequest`].
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = resume_process, response = SuspendCountResponse)]
+pub struct ResumeProcessRequest {
+    #[raw(arg1)]
     pub process: ProcessObject,
-    pub field: ProcessField,
-}
-
-impl SyscallRequest for OpenProcessRequest {
-    type Response = OpenObjectResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::open_process(),
-            arg1: self.process_id as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            process_id: request.arg1 as _,
-        }
-    }
 }
 
-impl SyscallRequest for CloseProcessRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::close_process(),
-            arg1: self.process as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            process: request.arg1 as _,
-        }
-    }
+///
+/// # Enumerate Processes Request
+///
+/// Walks the active process list. Entries are written to the output buffer, see [`ProcessEntry`](crate::hxposed::responses::process::ProcessEntry).
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = enumerate_processes, response = EnumerateProcessesResponse)]
+pub struct EnumerateProcessesRequest;
+
+///
+/// # Enumerate Modules Request
+///
+/// Walks `PEB->Ldr->InLoadOrderModuleList` of `process`. And the one in the 32-bit PEB, if it's WoW64.
+///
+/// ## Remarks
+/// - Result is one blob in the output buffer. See [`EnumerateModulesResponse`].
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = enumerate_modules, response = EnumerateModulesResponse)]
+pub struct EnumerateModulesRequest {
+    #[raw(arg1)]
+    pub process: ProcessObject,
 }
 
-impl SyscallRequest for GetProcessFieldRequest {
-    type Response = GetProcessFieldResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let objs = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::get_process_field(),
-            arg1: self.process as _,
-            arg2: objs.0,
-            arg3: objs.1,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            process: request.arg1 as _,
-            field: ProcessField::from_raw_enum(request.arg2, request.arg3)
-        }
-    }
+#[derive(Debug, Clone, SyscallRequest)]
+#[syscall(call = get_process_field, response = GetProcessFieldResponse)]
+pub struct GetProcessFieldRequest {
+    #[raw(arg1)]
+    pub process: ProcessObject,
+    #[raw(arg2, arg3)]
+    pub field: ProcessField,
 }
 
-impl SyscallRequest for SetProcessFieldRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let objs = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::set_process_field(),
-            arg1: self.process as _,
-            arg2: objs.0,
-            arg3: objs.1,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            process: request.arg1 as _,
-            field: ProcessField::from_raw_enum(request.arg2, request.arg3)
-        }
-    }
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = set_process_field, response = EmptyResponse)]
+pub struct SetProcessFieldRequest {
+    #[raw(arg1)]
+    pub process: ProcessObject,
+    #[raw(arg2, arg3)]
+    pub field: ProcessField,
 }
 
-
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug, RawEnum)]
+#[repr(u64)]
 pub enum ProcessField {
-    NtPath(u64),
-    Protection(ProcessProtection),
-    Signers(ProcessSignatureLevels),
-    MitigationFlags(MitigationOptions),
-    Token(u64),
-    Threads(u64),
-    DirectoryTableBase(u64),
-    UserDirectoryTableBase(u64),
-    Unknown
-}
-
-impl ProcessField {
-    pub fn into_raw_enum(self) -> (u64, u64) {
-        match self {
-            ProcessField::NtPath(x) => (1, x),
-            ProcessField::Protection(x) => (2, x.into_bits() as _),
-            ProcessField::Signers(x) => (3, x.into_bits() as _),
-            ProcessField::MitigationFlags(x) => (4, x.into_bits() as _),
-            ProcessField::Token(x) => (5, x),
-            ProcessField::Threads(x) => (6, x),
-            ProcessField::DirectoryTableBase(x) => (7, x),
-            ProcessField::UserDirectoryTableBase(x) => (8, x),
-            ProcessField::Unknown => (0, 0),
-        }
-    }
-
-    pub fn from_raw_enum(object: u64, value: u64) -> Self {
-        match object {
-            1 => ProcessField::NtPath(value),
-            2 => ProcessField::Protection(ProcessProtection::from_bits(value as _)),
-            3 => ProcessField::Signers(ProcessSignatureLevels::from_bits(value as _)),
-            4 => ProcessField::MitigationFlags(MitigationOptions::from_bits(value as _)),
-            5 => ProcessField::Token(value),
-            6 => ProcessField::Threads(value),
-            7 => ProcessField::DirectoryTableBase(value),
-            8 => ProcessField::UserDirectoryTableBase(value),
-            _ => ProcessField::Unknown
-        }
-    }
+    /// UTF-16, not terminated.
+    NtPath(OutputSlice) = 1,
+    Protection(ProcessProtection) = 2,
+    Signers(ProcessSignatureLevels) = 3,
+    MitigationFlags(MitigationOptions) = 4,
+    Token(u64) = 5,
+    /// Array of `u32` thread ids.
+    Threads(OutputSlice) = 6,
+    DirectoryTableBase(u64) = 7,
+    UserDirectoryTableBase(u64) = 8,
+    /// UTF-16, not terminated. Get only.
+    CommandLine(OutputSlice) = 9,
+    /// UTF-16 environment block. `NAME=VALUE` strings, each null terminated. Get only.
+    Environment(OutputSlice) = 10,
+    /// UTF-16, not terminated. Get only.
+    CurrentDirectory(OutputSlice) = 11,
+    /// UTF-16, not terminated. Get only.
+    WindowTitle(OutputSlice) = 12,
+    MitigationFlags3(MitigationOptions3) = 13,
+    #[raw(unknown)]
+    Unknown = 0,
 }
 
-
 //TODO: move this
 #[derive(Clone, Default, Eq, PartialEq, Hash, Debug)]
 pub enum ObjectOpenType {
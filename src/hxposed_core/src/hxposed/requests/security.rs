@@ -1,161 +1,59 @@
 #![allow(dead_code)]
 
 use crate::hxposed::TokenObject;
-use crate::hxposed::call::HxCall;
 use crate::hxposed::requests::process::ObjectOpenType;
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::empty::{EmptyResponse};
 use crate::hxposed::responses::OpenObjectResponse;
 use crate::hxposed::responses::security::*;
 use crate::services::types::security_fields::{ImpersonationLevel, TokenPrivilege, TokenType};
+use crate::hxposed::output::OutputSlice;
+use hxposed_macros::{RawEnum, SyscallRequest};
 
+#[derive(SyscallRequest)]
+#[syscall(call = open_token, response = OpenObjectResponse)]
 pub struct OpenTokenRequest {
+    #[raw(arg1)]
     pub token: TokenObject,
 }
 
+#[derive(SyscallRequest)]
+#[syscall(call = close_token, response = EmptyResponse)]
 pub struct CloseTokenRequest {
+    #[raw(arg1)]
     pub token: TokenObject,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, SyscallRequest)]
+#[syscall(call = get_token_field, response = GetTokenFieldResponse)]
 pub struct GetTokenFieldRequest {
+    #[raw(arg1)]
     pub token: TokenObject,
+    #[raw(arg2, arg3)]
     pub field: TokenField,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, SyscallRequest)]
+#[syscall(call = set_token_field, response = EmptyResponse)]
 pub struct SetTokenFieldRequest {
+    #[raw(arg1)]
     pub token: TokenObject,
+    #[raw(arg2, arg3)]
     pub field: TokenField,
 }
 
-impl SyscallRequest for SetTokenFieldRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let args = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::set_token_field(),
-            arg1: self.token,
-            arg2: args.0,
-            arg3: args.1,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            token: request.arg1,
-            field: TokenField::from_raw_enum(request.arg2, request.arg3),
-        }
-    }
-}
-
-impl SyscallRequest for GetTokenFieldRequest {
-    type Response = GetTokenFieldResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let args = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::get_token_field(),
-            arg1: self.token,
-            arg2: args.0,
-            arg3: args.1,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            token: request.arg1,
-            field: TokenField::from_raw_enum(request.arg2, request.arg3),
-        }
-    }
-}
-
-impl SyscallRequest for CloseTokenRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::close_token(),
-            arg1: self.token,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            token: request.arg1,
-        }
-    }
-}
-
-impl SyscallRequest for OpenTokenRequest {
-    type Response = OpenObjectResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::open_token(),
-            arg1: self.token,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            token: request.arg1,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, RawEnum)]
+#[repr(u64)]
 pub enum TokenField {
-    SourceName(u64), // actually a char[8] lol
-    AccountName(u64),
-    Type(TokenType),
-    IntegrityLevelIndex(u32),
-    MandatoryPolicy(u32),
-    ImpersonationLevel(ImpersonationLevel),
-    EnabledPrivileges(TokenPrivilege),
-    PresentPrivileges(TokenPrivilege),
-    EnabledByDefaultPrivileges(TokenPrivilege),
-    Unknown
-}
-
-impl TokenField {
-    pub fn into_raw_enum(self) -> (u64, u64) {
-        match self {
-            TokenField::SourceName(x) => (1, x),
-            TokenField::AccountName(x) => (2, x as _),
-            TokenField::Type(x) => (3, x.into_bits() as _),
-            TokenField::IntegrityLevelIndex(x) => (4, x as _),
-            TokenField::MandatoryPolicy(x) => (5, x as _),
-            TokenField::ImpersonationLevel(x) => (6, x.into_bits() as _),
-            TokenField::EnabledPrivileges(x) => (7, x.bits() as _),
-            TokenField::PresentPrivileges(x) => (8, x.bits() as _),
-            TokenField::EnabledByDefaultPrivileges(x) => (9, x.bits() as _),
-            TokenField::Unknown => (0, 0),
-        }
-    }
-
-    pub fn from_raw_enum(object: u64, value: u64) -> Self {
-        match object {
-            1 => TokenField::SourceName(value as _),
-            2 => TokenField::AccountName(value as _),
-            3 => TokenField::Type(TokenType::from_bits(value as _)),
-            4 => TokenField::IntegrityLevelIndex(value as _),
-            5 => TokenField::MandatoryPolicy(value as _),
-            6 => TokenField::ImpersonationLevel(ImpersonationLevel::from_bits(value as _)),
-            7 => TokenField::EnabledPrivileges(TokenPrivilege::from_bits_truncate(value as _)),
-            8 => TokenField::PresentPrivileges(TokenPrivilege::from_bits_truncate(value as _)),
-            9 => TokenField::EnabledByDefaultPrivileges(TokenPrivilege::from_bits_truncate(
-                value as _,
-            )),
-            _ => TokenField::Unknown
-        }
-    }
+    SourceName(u64) = 1, // actually a char[8] lol
+    /// UTF-16, not terminated.
+    AccountName(OutputSlice) = 2,
+    Type(TokenType) = 3,
+    IntegrityLevelIndex(u32) = 4,
+    MandatoryPolicy(u32) = 5,
+    ImpersonationLevel(ImpersonationLevel) = 6,
+    EnabledPrivileges(TokenPrivilege) = 7,
+    PresentPrivileges(TokenPrivilege) = 8,
+    EnabledByDefaultPrivileges(TokenPrivilege) = 9,
+    #[raw(unknown)]
+    Unknown = 0,
 }
@@ -1,22 +1,7 @@
-use crate::hxposed::call::HxCall;
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::status::StatusResponse;
+use hxposed_macros::SyscallRequest;
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = get_status, response = StatusResponse)]
 #[repr(C)]
 pub struct StatusRequest;
-
-impl SyscallRequest for StatusRequest {
-    type Response = StatusResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::get_status(),
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(_request: &HxRequest) -> Self {
-        Self {}
-    }
-}
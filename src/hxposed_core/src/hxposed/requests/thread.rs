@@ -1,137 +1,76 @@
-use crate::hxposed::call::HxCall;
 use crate::hxposed::requests::process::ObjectOpenType;
-use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::empty::{EmptyResponse};
 use crate::hxposed::responses::OpenObjectResponse;
 use crate::hxposed::responses::thread::*;
 use crate::hxposed::ThreadObject;
+use crate::hxposed::output::OutputSlice;
+use hxposed_macros::{RawEnum, SyscallRequest};
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = open_thread, response = OpenObjectResponse)]
 pub struct OpenThreadRequest {
+    #[raw(arg1)]
     pub tid: u64,
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = close_thread, response = EmptyResponse)]
 pub struct CloseThreadRequest {
+    #[raw(arg1)]
     pub thread: ThreadObject,
 }
 
-#[derive(Debug, Clone)]
-pub struct GetThreadFieldRequest {
+///
+/// # Suspend Thread Request
+///
+/// Increments the suspend count of `thread`. It stops running once it's above 0.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = suspend_thread, response = SuspendCountResponse)]
+pub struct SuspendThreadRequest {
+    #[raw(arg1)]
     pub thread: ThreadObject,
-    pub field: ThreadField,
 }
 
-#[derive(Debug)]
-pub struct SetThreadFieldRequest {
+///
+/// # Resume Thread Request
+///
+/// Decrements the suspend count of `thread`. It runs again once it's 0.
+///
+#[derive(Clone, Default, Debug, SyscallRequest)]
+#[syscall(call = resume_thread, response = SuspendCountResponse)]
+pub struct ResumeThreadRequest {
+    #[raw(arg1)]
     pub thread: ThreadObject,
-    pub field: ThreadField,
-}
-
-impl SyscallRequest for GetThreadFieldRequest {
-    type Response = GetThreadFieldResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let args = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::get_thread_field(),
-            arg1: self.thread as _,
-            arg2: args.0,
-            arg3: args.1,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            thread: request.arg1 as _,
-            field: ThreadField::from_raw_enum(request.arg2, request.arg3)
-        }
-    }
-}
-
-impl SyscallRequest for SetThreadFieldRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        let args = self.field.into_raw_enum();
-        HxRequest {
-            call: HxCall::set_thread_field(),
-            arg1: self.thread as _,
-            arg2: args.0,
-            arg3: args.1,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            thread: request.arg1 as _,
-            field: ThreadField::from_raw_enum(request.arg2, request.arg3)
-        }
-    }
 }
 
-impl SyscallRequest for CloseThreadRequest {
-    type Response = EmptyResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::close_thread(),
-            arg1: self.thread.clone() as _,
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            thread: request.arg1 as _,
-        }
-    }
+#[derive(Debug, Clone, SyscallRequest)]
+#[syscall(call = get_thread_field, response = GetThreadFieldResponse)]
+pub struct GetThreadFieldRequest {
+    #[raw(arg1)]
+    pub thread: ThreadObject,
+    #[raw(arg2, arg3)]
+    pub field: ThreadField,
 }
 
-impl SyscallRequest for OpenThreadRequest {
-    type Response = OpenObjectResponse;
-
-    fn into_raw(self) -> HxRequest {
-        HxRequest {
-            call: HxCall::open_thread(),
-            arg1: self.tid as _,
-
-            ..Default::default()
-        }
-    }
-
-    fn from_raw(request: &HxRequest) -> Self {
-        Self {
-            tid: request.arg1 as _,
-        }
-    }
+#[derive(Debug, SyscallRequest)]
+#[syscall(call = set_thread_field, response = EmptyResponse)]
+pub struct SetThreadFieldRequest {
+    #[raw(arg1)]
+    pub thread: ThreadObject,
+    #[raw(arg2, arg3)]
+    pub field: ThreadField,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RawEnum)]
+#[repr(u64)]
 pub enum ThreadField {
-    ActiveImpersonationInfo(bool),
-    AdjustedClientToken(u64),
-    Unknown
-}
-
-impl ThreadField {
-    pub fn into_raw_enum(self) -> (u64, u64) {
-        match self {
-            ThreadField::ActiveImpersonationInfo(x) => (1, x as _),
-            ThreadField::AdjustedClientToken(x) => (2, x as _),
-            ThreadField::Unknown => (0, 0),
-        }
-    }
-
-    pub fn from_raw_enum(object: u64, value: u64) -> Self {
-        match object {
-            1 => ThreadField::ActiveImpersonationInfo(value == 1),
-            2 => ThreadField::AdjustedClientToken(value as _),
-            _ => ThreadField::Unknown
-        }
-    }
+    ActiveImpersonationInfo(bool) = 1,
+    AdjustedClientToken(u64) = 2,
+    /// [`ThreadContext`](crate::services::types::thread_fields::ThreadContext) of user mode. Get only.
+    Context(OutputSlice) = 3,
+    #[raw(unknown)]
+    Unknown = 0,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -0,0 +1,31 @@
+use crate::hxposed::AsyncCookie;
+use crate::hxposed::responses::HxResponse;
+use hxposed_macros::SyscallResponse;
+
+///
+/// # Async Response
+///
+/// What a slow call returns right away. The actual response arrives in [`AsyncCompletion`].
+///
+#[derive(Clone, Default, Debug, SyscallResponse)]
+pub struct AsyncResponse {
+    #[raw(arg1)]
+    pub cookie: AsyncCookie,
+}
+
+///
+/// # Async Completion
+///
+/// Filled by the hypervisor when a slow call completes.
+///
+/// ## Remarks
+/// - `response` is written before `completed`. Once `completed` is non-zero, `response` is final.
+/// - Must stay alive until the call completes. The pages are locked by the hypervisor.
+///
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct AsyncCompletion {
+    pub cookie: AsyncCookie,
+    pub completed: u64,
+    pub response: HxResponse,
+}
@@ -0,0 +1,8 @@
+use hxposed_macros::SyscallResponse;
+
+#[derive(Clone, Default, Debug, SyscallResponse)]
+pub struct BatchResponse {
+    /// Number of responses written.
+    #[raw(arg1)]
+    pub completed: u64,
+}
@@ -1,26 +1,72 @@
-use crate::hxposed::call::HxResult;
-use crate::hxposed::responses::{HxResponse, SyscallResponse};
+use crate::hxposed::Handle;
+use crate::hxposed::output::OutputSlice;
+use bitfield_struct::bitfield;
+use hxposed_macros::SyscallResponse;
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct GetHandleObjectResponse {
+    #[raw(arg1)]
     pub object: u64,
+    #[raw(arg2)]
     pub granted_access: u32
 }
 
-impl SyscallResponse for GetHandleObjectResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            object: raw.arg1,
-            granted_access: raw.arg2 as _
-        }
-    }
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct CreateHandleResponse {
+    #[raw(arg1)]
+    pub handle: Handle,
+}
+
+///
+/// # Enumerate Handles Response
+///
+/// `handles` holds `count` [`HandleEntry`]s, followed by the UTF-16 names they point to.
+///
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct EnumerateHandlesResponse {
+    #[raw(arg1)]
+    pub handles: OutputSlice,
+    #[raw(arg2)]
+    pub count: u32,
+}
 
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.object,
-            arg2: self.granted_access as _,
-            ..Default::default()
-        }
-    }
-}
\ No newline at end of file
+///
+/// # Handle Entry
+///
+/// A `_HANDLE_TABLE_ENTRY`. See [`EnumerateHandlesResponse`].
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct HandleEntry {
+    /// Handle value, as the process uses it.
+    pub handle: u64,
+    /// Kernel address of the object body.
+    pub object: u64,
+    /// `_OBJECT_TYPE.Name`, relative to the start of the result.
+    pub type_name: OutputSlice,
+    /// Name of the object, relative to the start of the result. Empty if it has none.
+    pub name: OutputSlice,
+    /// `GrantedAccessBits`.
+    pub granted_access: u32,
+    pub attributes: HandleAttributes,
+    /// Zero. Keeps the padding written.
+    pub reserved: [u8; 3],
+}
+
+///
+/// # Handle Attributes
+///
+/// `Attributes` bits of a `_HANDLE_TABLE_ENTRY`. Same as `OBJ_PROTECT_CLOSE`, `OBJ_INHERIT` and `OBJ_AUDIT_OBJECT_CLOSE`.
+///
+#[bitfield(u8)]
+#[derive(Eq, PartialEq, Hash)]
+pub struct HandleAttributes {
+    /// `CloseHandle` fails on it.
+    pub protect_from_close: bool,
+    /// Child processes get a copy.
+    pub inherit: bool,
+    /// Closing it generates an audit.
+    pub audit_on_close: bool,
+    #[bits(5)]
+    __: u8,
+}
@@ -1,88 +1,142 @@
-use crate::hxposed::call::HxResult;
-use crate::hxposed::responses::{HxResponse, SyscallResponse};
+use crate::hxposed::output::OutputSlice;
 use crate::hxposed::RmdObject;
+use hxposed_macros::SyscallResponse;
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct PageAttributeResponse {
+    #[raw(arg1)]
     pub type_bits: u64,
 }
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct TranslateAddressResponse {
+    #[raw(arg1)]
     pub physical_addr: u64
 }
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct AllocateMemoryResponse {
+    #[raw(arg1)]
     pub rmd: RmdObject
 }
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct DescribeMemoryResponse {
+    #[raw(arg1)]
     pub rmd: RmdObject
 }
 
-impl SyscallResponse for DescribeMemoryResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            rmd: raw.arg1
-        }
-    }
-
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.rmd,
-            ..Default::default()
-        }
-    }
+#[derive(Clone, SyscallResponse)]
+pub struct VirtualMemoryResponse {
+    /// Bytes copied. Less than asked for when a page faulted.
+    #[raw(arg1)]
+    pub bytes_copied: u32,
 }
 
-impl SyscallResponse for TranslateAddressResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            physical_addr: raw.arg1
-        }
-    }
+///
+/// # Memory Regions Response
+///
+/// `regions` holds `count` [`MemoryRegionEntry`]s, followed by the UTF-16 file names they point to.
+///
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct MemoryRegionsResponse {
+    #[raw(arg1)]
+    pub regions: OutputSlice,
+    #[raw(arg2)]
+    pub count: u32,
+    /// The VAD tree was too deep, or too big, to walk all of it. Regions are missing.
+    #[raw(arg3)]
+    pub truncated: bool,
+}
 
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.physical_addr,
-                ..Default::default()
-        }
-    }
+///
+/// # Memory Region Entry
+///
+/// A VAD. See [`MemoryRegionsResponse`].
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct MemoryRegionEntry {
+    /// First page of the region.
+    pub start_vpn: u64,
+    /// Last page of the region. Inclusive, like NT has it.
+    pub end_vpn: u64,
+    /// Committed pages.
+    pub commit_charge: u64,
+    /// Backing file name, relative to the start of the result. Empty if there is none.
+    pub file_name: OutputSlice,
+    /// `MM_*` protection. See [`MemoryRegionEntry::page_protection`].
+    pub protection: u8,
+    /// [`VadType`], as bits.
+    pub vad_type: u8,
+    /// 1 if private memory. 0 if mapped.
+    pub private: u8,
+    /// Zero. Keeps the padding written.
+    pub reserved: [u8; 5],
 }
 
-impl SyscallResponse for AllocateMemoryResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            rmd: raw.arg1
-        }
-    }
+impl MemoryRegionEntry {
+    ///
+    /// # Page Protection
+    ///
+    /// Converts [`Self::protection`] to `PAGE_*` constants, like `VirtualQuery` returns them.
+    ///
+    pub const fn page_protection(&self) -> u32 {
+        const BASE: [u32; 8] = [
+            0x01, // PAGE_NOACCESS
+            0x02, // PAGE_READONLY
+            0x10, // PAGE_EXECUTE
+            0x20, // PAGE_EXECUTE_READ
+            0x04, // PAGE_READWRITE
+            0x08, // PAGE_WRITECOPY
+            0x40, // PAGE_EXECUTE_READWRITE
+            0x80, // PAGE_EXECUTE_WRITECOPY
+        ];
 
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.rmd,
-            ..Default::default()
+        let base = BASE[(self.protection & 7) as usize];
+        match self.protection & 0x18 {
+            0x08 => base | 0x200, // PAGE_NOCACHE
+            0x10 => base | 0x100, // PAGE_GUARD
+            0x18 => base | 0x400, // PAGE_WRITECOMBINE
+            _ => base,
         }
     }
 }
 
-impl SyscallResponse for PageAttributeResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            type_bits: raw.arg1
-        }
+///
+/// # Vad Type
+///
+/// `_MI_VAD_TYPE`.
+///
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[repr(u8)]
+pub enum VadType {
+    #[default]
+    None = 0,
+    DevicePhysicalMemory = 1,
+    ImageMap = 2,
+    Awe = 3,
+    WriteWatch = 4,
+    LargePages = 5,
+    RotatePhysical = 6,
+    LargePageSection = 7,
+}
+
+impl VadType {
+    pub const fn into_bits(self) -> u8 {
+        self as _
     }
 
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.type_bits,
-            arg2: 0,
-            arg3: 0
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 7 {
+            1 => Self::DevicePhysicalMemory,
+            2 => Self::ImageMap,
+            3 => Self::Awe,
+            4 => Self::WriteWatch,
+            5 => Self::LargePages,
+            6 => Self::RotatePhysical,
+            7 => Self::LargePageSection,
+            _ => Self::None,
         }
     }
-}
\ No newline at end of file
+}
@@ -4,10 +4,14 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use crate::error::HxError;
 use crate::hxposed::ObjectType;
+use hxposed_macros::SyscallResponse;
 
+pub mod async_call;
+pub mod batch;
 pub mod empty;
 pub mod memory;
 pub mod notify;
+pub mod output;
 pub mod process;
 pub mod security;
 pub mod status;
@@ -16,11 +20,18 @@ pub mod io;
 pub mod handle;
 
 #[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+#[repr(C)]
 pub struct HxResponse {
     pub result: HxResult,
     pub arg1: u64,
     pub arg2: u64,
     pub arg3: u64,
+
+    // only valid with result.extended_results_present.
+    pub extended_arg1: u128,
+    pub extended_arg2: u128,
+    pub extended_arg3: u128,
+    pub extended_arg4: u128,
 }
 
 impl HxResponse {
@@ -60,26 +71,8 @@ pub trait SyscallResponse: Sized + Send + Sync + Unpin {
     fn into_raw(self) -> HxResponse;
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, SyscallResponse)]
 pub struct OpenObjectResponse {
+    #[raw(arg1, arg2)]
     pub object: ObjectType,
 }
-
-impl SyscallResponse for OpenObjectResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            object: ObjectType::from_raw(raw.arg1, raw.arg2),
-        }
-    }
-
-    fn into_raw(self) -> HxResponse {
-        let object = ObjectType::into_raw(self.object);
-
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: object.0,
-            arg2: object.1,
-            ..Default::default()
-        }
-    }
-}
\ No newline at end of file
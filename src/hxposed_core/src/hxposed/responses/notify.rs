@@ -1,12 +1,12 @@
-use crate::hxposed::call::HxResult;
 use crate::hxposed::requests::notify::ObjectState;
-use crate::hxposed::responses::{HxResponse, SyscallResponse};
 use crate::hxposed::CallbackObject;
+use hxposed_macros::SyscallResponse;
 
 pub const CALLBACK_RESPONSE_RESERVED_OFFSET: u64 = 0;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, SyscallResponse)]
 pub struct RegisterNotifyHandlerResponse {
+    #[raw(arg1)]
     pub callback: CallbackObject,
 }
 
@@ -19,18 +19,3 @@ pub struct CallbackInformation {
     pub object_value: u64,
     pub object_state: ObjectState,
 }
-
-impl SyscallResponse for RegisterNotifyHandlerResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self { callback: raw.arg1 }
-    }
-
-    fn into_raw(self) -> HxResponse {
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: self.callback,
-
-            ..Default::default()
-        }
-    }
-}
@@ -0,0 +1,16 @@
+use hxposed_macros::SyscallResponse;
+
+///
+/// # Register Output Buffer Response
+///
+/// Where the hypervisor mapped the output buffer. See [`OutputBufferHeader`](crate::hxposed::output::OutputBufferHeader).
+///
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct RegisterOutputBufferResponse {
+    /// Address of the buffer in the caller. Picked by the hypervisor.
+    #[raw(arg1)]
+    pub address: u64,
+    /// Size of the buffer, header included. Rounded up to page size.
+    #[raw(arg2)]
+    pub size: u32,
+}
@@ -1,26 +1,101 @@
-use crate::hxposed::call::HxResult;
+use crate::hxposed::output::OutputSlice;
 use crate::hxposed::requests::process::ProcessField;
-use crate::hxposed::responses::{HxResponse, SyscallResponse};
+use crate::services::types::process_fields::{ProcessProtection, ProcessSignatureLevels};
+use alloc::string::String;
+use hxposed_macros::SyscallResponse;
 
-#[derive(Clone)]
+#[derive(Clone, SyscallResponse)]
 pub struct GetProcessFieldResponse {
+    #[raw(arg1, arg2)]
     pub field: ProcessField,
+    /// The result was cut to fit what was left of the output buffer. Only [`ProcessField::Environment`] is.
+    #[raw(arg3)]
+    pub truncated: bool,
 }
 
-impl SyscallResponse for GetProcessFieldResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        Self {
-            field: ProcessField::from_raw_enum(raw.arg1, raw.arg2),
-        }
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct EnumerateProcessesResponse {
+    /// Array of [`ProcessEntry`].
+    #[raw(arg1)]
+    pub processes: OutputSlice,
+}
+
+///
+/// # Process Entry
+///
+/// A process in the active process list. See [`EnumerateProcessesResponse`].
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct ProcessEntry {
+    pub id: u32,
+    /// `InheritedFromUniqueProcessId`. Parent may be long gone, and its id reused.
+    pub parent_id: u32,
+    /// `CreateTime`. 100ns intervals since 1601.
+    pub create_time: u64,
+    pub protection: ProcessProtection,
+    pub signers: ProcessSignatureLevels,
+    /// `ImageFileName`. ASCII, null terminated unless all 15 bytes are used.
+    pub image_file_name: [u8; 15],
+}
+
+impl ProcessEntry {
+    ///
+    /// # Name
+    ///
+    /// Gets the image name. Truncated to 15 characters by NT, not us.
+    ///
+    pub fn name(&self) -> String {
+        let length = self
+            .image_file_name
+            .iter()
+            .position(|x| *x == 0)
+            .unwrap_or(self.image_file_name.len());
+
+        String::from_utf8_lossy(&self.image_file_name[..length]).into_owned()
     }
 
-    fn into_raw(self) -> HxResponse {
-        let args = self.field.clone().into_raw_enum();
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: args.0,
-            arg2: args.1,
-            arg3: 0,
-        }
+    ///
+    /// # Is Parent Of
+    ///
+    /// Checks if this is the parent of `child`. Ids get reused, so the parent must also be older than the child.
+    ///
+    pub fn is_parent_of(&self, child: &ProcessEntry) -> bool {
+        self.id == child.parent_id && self.id != child.id && self.create_time <= child.create_time
     }
 }
+
+///
+/// # Enumerate Modules Response
+///
+/// `modules` holds `count` [`ModuleEntry`]s, followed by the UTF-16 paths they point to.
+///
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct EnumerateModulesResponse {
+    #[raw(arg1)]
+    pub modules: OutputSlice,
+    #[raw(arg2)]
+    pub count: u32,
+}
+
+///
+/// # Module Entry
+///
+/// A `LDR_DATA_TABLE_ENTRY`. See [`EnumerateModulesResponse`].
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub struct ModuleEntry {
+    /// `DllBase`.
+    pub base: u64,
+    /// `EntryPoint`. 0 for the ones without, like `ntdll.dll`.
+    pub entry_point: u64,
+    /// `FullDllName`, relative to the start of the result.
+    pub path: OutputSlice,
+    /// `SizeOfImage`.
+    pub size: u32,
+    /// 1 if from the 32-bit PEB.
+    pub wow64: u8,
+    /// Zero. Keeps the padding written.
+    pub reserved: [u8; 3],
+}
@@ -1,27 +1,52 @@
 use crate::hxposed::call::HxResult;
 use crate::hxposed::responses::{HxResponse, SyscallResponse};
-use crate::hxposed::status::HypervisorStatus;
+use crate::hxposed::status::{Capabilities, HypervisorStatus};
 
 #[derive(Clone, Default, Debug)]
 #[repr(C)]
 pub struct StatusResponse {
     pub state: HypervisorStatus,
     pub version: u32,
+    pub capabilities: Capabilities,
 }
 
 impl SyscallResponse for StatusResponse {
     fn from_raw(raw: HxResponse) -> Self {
+        let version = (raw.arg1 >> 32) as u32;
+
+        // version 1 drivers put the version in arg2 and know nothing about capabilities.
+        if version == 0 {
+            return Self {
+                state: HypervisorStatus::from(raw.arg1 as u32),
+                version: raw.arg2 as _,
+                capabilities: Capabilities::all(),
+            };
+        }
+
+        // whole bitmap in xmm0. drivers without extended results split it over arg2 and arg3.
+        let capabilities = match raw.result.extended_results_present() {
+            true => raw.extended_arg1,
+            false => raw.arg2 as u128 | (raw.arg3 as u128) << 64,
+        };
+
         Self {
             state: HypervisorStatus::from(raw.arg1 as u32),
-            version: raw.arg2 as _,
+            version,
+            capabilities: Capabilities::from_bits(capabilities),
         }
     }
 
     fn into_raw(self) -> HxResponse {
+        let state: u32 = self.state.into();
+        let capabilities = self.capabilities.into_bits();
+
         HxResponse {
-            result: HxResult::ok(),
-            arg1: self.state as _,
-            arg2: self.version as _,
+            result: HxResult::ok().with_extended_results_present(true),
+            arg1: state as u64 | (self.version as u64) << 32,
+            // still split for callers that don't read xmm0.
+            arg2: capabilities as u64,
+            arg3: (capabilities >> 64) as u64,
+            extended_arg1: capabilities,
             ..Default::default()
         }
     }
@@ -1,42 +1,17 @@
-use crate::hxposed::call::HxResult;
-use crate::hxposed::responses::{HxResponse, SyscallResponse};
+use crate::hxposed::output::OutputSlice;
+use hxposed_macros::{RawEnum, SyscallResponse};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RawEnum, SyscallResponse)]
 #[repr(u16)]
 pub enum GetThreadFieldResponse {
     ActiveImpersonationInfo(bool) = 1,
     AdjustedClientToken(u64) = 2,
+    Context(OutputSlice) = 3,
 }
 
-impl GetThreadFieldResponse {
-    pub fn into_raw_enum(self) -> (u64, u64) {
-        match self {
-            GetThreadFieldResponse::ActiveImpersonationInfo(info) => (0, info as u64),
-            GetThreadFieldResponse::AdjustedClientToken(token) => (1, token),
-        }
-    }
-
-    pub fn from_raw_enum(object: u64, value: u64) -> Self {
-        match object {
-            0 => GetThreadFieldResponse::ActiveImpersonationInfo(value == 1),
-            1 => GetThreadFieldResponse::AdjustedClientToken(value),
-            _ => panic!("Invalid object id: {}", object),
-        }
-    }
+#[derive(Clone, Debug, SyscallResponse)]
+pub struct SuspendCountResponse {
+    /// Suspend count before the request. 0 means it was running.
+    #[raw(arg1)]
+    pub previous_count: u32,
 }
-
-impl SyscallResponse for GetThreadFieldResponse {
-    fn from_raw(raw: HxResponse) -> Self {
-        GetThreadFieldResponse::from_raw_enum(raw.arg1, raw.arg2)
-    }
-
-    fn into_raw(self) -> HxResponse {
-        let args = self.into_raw_enum();
-        HxResponse {
-            result: HxResult::ok(),
-            arg1: args.0,
-            arg2: args.1,
-            arg3: 0,
-        }
-    }
-}
\ No newline at end of file
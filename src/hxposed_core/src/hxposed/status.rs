@@ -1,3 +1,4 @@
+use crate::hxposed::func::ServiceFunction;
 use core::fmt::{Display, Formatter};
 
 #[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
@@ -37,3 +38,77 @@ impl Display for HypervisorStatus {
         }
     }
 }
+
+///
+/// # Protocol Version
+///
+/// Version of the call protocol spoken by this build. Reported by `GetState`.
+///
+/// Drivers before capability reporting are version 1.
+/// Version 3 returns variable-length results through the output buffer. See [`OutputBufferHeader`](crate::hxposed::output::OutputBufferHeader).
+///
+pub const PROTOCOL_VERSION: u32 = 3;
+
+///
+/// # Capabilities
+///
+/// Bitmap of the [`ServiceFunction`]s a driver implements.
+///
+/// Bit `n` is set if the service function with id `n` is implemented. Since ids are `category << 4 | function`,
+/// each category gets its own 16-bit lane. See [`Self::category`].
+///
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct Capabilities(u128);
+
+impl Capabilities {
+    ///
+    /// # All
+    ///
+    /// Claims everything. Used for drivers that predate capability reporting.
+    ///
+    pub const fn all() -> Self {
+        Self(u128::MAX)
+    }
+
+    pub const fn from_bits(bits: u128) -> Self {
+        Self(bits)
+    }
+
+    pub const fn into_bits(self) -> u128 {
+        self.0
+    }
+
+    ///
+    /// # Set
+    ///
+    /// Marks the function at `category`, `function` as implemented.
+    ///
+    /// ## Panic
+    /// - If `category` is more than 7 or `function` is more than 15.
+    pub const fn set(&mut self, category: usize, function: usize) {
+        assert!(category < 8 && function < 16);
+        self.0 |= 1 << (category << 4 | function);
+    }
+
+    ///
+    /// # Supports
+    ///
+    /// ## Return
+    /// * [`bool`] - True if `function` is implemented.
+    pub const fn supports(&self, function: ServiceFunction) -> bool {
+        match 1u128.checked_shl(function.into_bits() as _) {
+            Some(bit) => self.0 & bit != 0,
+            // ServiceFunction::Unknown
+            None => false,
+        }
+    }
+
+    ///
+    /// # Category
+    ///
+    /// ## Return
+    /// * [`u16`] - Bitmap of implemented functions in `category`. Bit `n` is function `n`.
+    pub const fn category(&self, category: u8) -> u16 {
+        (self.0 >> ((category as u32 & 7) << 4)) as u16
+    }
+}
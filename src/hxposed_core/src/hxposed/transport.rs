@@ -0,0 +1,88 @@
+use crate::hxposed::requests::HxRequest;
+use crate::hxposed::responses::HxResponse;
+use crate::intern::instructions::vmcall;
+use alloc::boxed::Box;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+///
+/// # Transport
+///
+/// Carries a raw [`HxRequest`] to whatever is servicing HxPosed calls and hands back its [`HxResponse`].
+///
+/// Every [`Syscall::send`](crate::hxposed::requests::Syscall::send) goes through the currently registered transport.
+/// By default, that's [`SyscallTransport`], which issues the real `syscall` the hypervisor traps on.
+///
+/// Register something else with [`set_transport`] to run the services without the hypervisor loaded, e.g. for testing.
+///
+pub trait Transport: Send + Sync {
+    ///
+    /// # Send
+    ///
+    /// Sends the request and returns the response.
+    ///
+    /// ## Arguments
+    /// * `request` - Raw request to send. Implementations are allowed to modify it.
+    ///
+    /// ## Return
+    /// * [`HxResponse`] - Raw response. If the backend isn't there, result must be [`HxError::HvNotLoaded`](crate::error::HxError::HvNotLoaded).
+    ///
+    fn send(&self, request: &mut HxRequest) -> HxResponse;
+}
+
+///
+/// # Syscall Transport
+///
+/// The default transport. Sends the request to the hypervisor.
+///
+#[derive(Default, Clone, Copy, Debug)]
+pub struct SyscallTransport;
+
+impl Transport for SyscallTransport {
+    fn send(&self, request: &mut HxRequest) -> HxResponse {
+        vmcall(request)
+    }
+}
+
+static DEFAULT_TRANSPORT: SyscallTransport = SyscallTransport;
+static TRANSPORT: AtomicPtr<&'static dyn Transport> = AtomicPtr::new(null_mut());
+
+///
+/// # Set Transport
+///
+/// Replaces the transport used by all requests sent after this call.
+///
+/// ## Arguments
+/// * `transport` - New transport. Must live forever, since requests in flight on other threads may still be using the old one.
+///
+/// ## Remarks
+/// - The small allocation made for the previous transport is leaked for the same reason. Don't call this in a loop.
+///
+pub fn set_transport(transport: &'static dyn Transport) {
+    let new = Box::into_raw(Box::new(transport));
+    TRANSPORT.store(new, Ordering::Release);
+}
+
+///
+/// # Reset Transport
+///
+/// Goes back to [`SyscallTransport`].
+///
+pub fn reset_transport() {
+    TRANSPORT.store(null_mut(), Ordering::Release);
+}
+
+///
+/// # Transport
+///
+/// Gets the transport requests are currently sent through.
+///
+pub fn transport() -> &'static dyn Transport {
+    let current = TRANSPORT.load(Ordering::Acquire);
+    if current.is_null() {
+        &DEFAULT_TRANSPORT
+    } else {
+        // SAFETY: only set_transport stores non-null pointers, and those are never freed.
+        unsafe { *current }
+    }
+}
@@ -0,0 +1,334 @@
+#![allow(non_snake_case)]
+
+//!
+//! # Host
+//!
+//! Stand-ins for the `kernel32` functions the user-mode services use, for when we are not running on Windows.
+//!
+//! Handles returned here are only meaningful to the functions in this module.
+//! Backends that service HxPosed calls off Windows (e.g. `hxposed_sim`) use them to signal events the same way the driver would.
+//!
+
+extern crate std;
+
+use super::win::{OsVersionInfo, SystemInfo};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::vec::Vec;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+/// Value returned by [`WaitForSingleObject`] when the object is signaled.
+pub const WAIT_OBJECT_0: u32 = 0;
+/// Value returned by [`WaitForSingleObject`] when the wait timed out.
+pub const WAIT_TIMEOUT: u32 = 0x102;
+/// Value returned by [`WaitForSingleObject`] when the handle is invalid.
+pub const WAIT_FAILED: u32 = u32::MAX;
+/// Flag of [`RegisterWaitForSingleObject`]. The callback runs once, then the wait is done.
+pub const WT_EXECUTEONLYONCE: u32 = 0x8;
+
+struct HostEvent {
+    manual_reset: bool,
+    signaled: Mutex<bool>,
+    cond: Condvar,
+}
+
+struct HostWait {
+    event: u64,
+    callback: unsafe extern "C" fn(*mut u8, u8),
+    context: usize,
+}
+
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(0x1000);
+static NEXT_THREAD_ID: AtomicU32 = AtomicU32::new(0x2000);
+static EVENTS: Mutex<BTreeMap<u64, Arc<HostEvent>>> = Mutex::new(BTreeMap::new());
+static WAITS: Mutex<BTreeMap<u64, HostWait>> = Mutex::new(BTreeMap::new());
+// held while wait callbacks run. UnregisterWaitEx takes it to wait them out.
+static CALLBACKS: Mutex<()> = Mutex::new(());
+
+std::thread_local! {
+    static THREAD_ID: u32 = NEXT_THREAD_ID.fetch_add(4, Ordering::Relaxed);
+}
+
+fn get_event(handle: u64) -> Option<Arc<HostEvent>> {
+    EVENTS.lock().unwrap().get(&handle).cloned()
+}
+
+fn run_waits(event: u64) {
+    let _running = CALLBACKS.lock().unwrap();
+
+    let ready = {
+        let mut waits = WAITS.lock().unwrap();
+        let handles = waits
+            .iter()
+            .filter(|(_, wait)| wait.event == event)
+            .map(|(handle, _)| *handle)
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .filter_map(|x| waits.remove(&x))
+            .collect::<Vec<_>>()
+    };
+
+    for wait in ready {
+        unsafe { (wait.callback)(wait.context as _, 0) };
+    }
+}
+
+///
+/// # Get Current Process Id
+///
+/// Id of the host process.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn GetCurrentProcessId() -> u32 {
+    std::process::id()
+}
+
+///
+/// # Get Current Thread Id
+///
+/// Made up id of the calling thread. Stable for the thread's lifetime, unique within the process.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn GetCurrentThreadId() -> u32 {
+    THREAD_ID.with(|id| *id)
+}
+
+///
+/// # Create Event A
+///
+/// Creates an event, returns its handle. Security attributes and name are ignored.
+///
+/// ## Safety
+/// - Always safe. The pointers are never read. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn CreateEventA(
+    _security_attributes: *mut u8,
+    manual_reset: u32,
+    initial_state: u32,
+    _name: *mut u8,
+) -> u64 {
+    let handle = NEXT_HANDLE.fetch_add(4, Ordering::Relaxed);
+    EVENTS.lock().unwrap().insert(
+        handle,
+        Arc::new(HostEvent {
+            manual_reset: manual_reset != 0,
+            signaled: Mutex::new(initial_state != 0),
+            cond: Condvar::new(),
+        }),
+    );
+
+    handle
+}
+
+///
+/// # Close Handle
+///
+/// Closes an event handle. Waits already in progress keep the event alive until they return.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn CloseHandle(handle: u64) {
+    EVENTS.lock().unwrap().remove(&handle);
+}
+
+///
+/// # Set Event
+///
+/// Signals the event, and runs the callbacks waiting on it. Returns `0` if `handle` isn't one.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn SetEvent(handle: u64) -> u32 {
+    match get_event(handle) {
+        Some(event) => {
+            *event.signaled.lock().unwrap() = true;
+            event.cond.notify_all();
+            run_waits(handle);
+            1
+        }
+        None => 0,
+    }
+}
+
+///
+/// # Reset Event
+///
+/// Unsignals the event. Returns `0` if `handle` isn't one.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn ResetEvent(handle: u64) -> u32 {
+    match get_event(handle) {
+        Some(event) => {
+            *event.signaled.lock().unwrap() = false;
+            1
+        }
+        None => 0,
+    }
+}
+
+///
+/// # Wait For Single Object
+///
+/// Waits `time` milliseconds for the event. `u32::MAX` waits forever.
+///
+/// ## Safety
+/// - Always safe. `unsafe` only to match the `kernel32` import it stands in for.
+///
+pub unsafe fn WaitForSingleObject(handle: u64, time: u32) -> u32 {
+    let event = match get_event(handle) {
+        Some(event) => event,
+        None => return WAIT_FAILED,
+    };
+
+    let signaled = event.signaled.lock().unwrap();
+    let mut signaled = match time {
+        u32::MAX => event.cond.wait_while(signaled, |s| !*s).unwrap(),
+        ms => {
+            event
+                .cond
+                .wait_timeout_while(signaled, Duration::from_millis(ms as _), |s| !*s)
+                .unwrap()
+                .0
+        }
+    };
+
+    if !*signaled {
+        return WAIT_TIMEOUT;
+    }
+
+    if !event.manual_reset {
+        *signaled = false;
+    }
+
+    WAIT_OBJECT_0
+}
+
+///
+/// # Create Thread
+///
+/// Runs `start_address(parameter)` on a new host thread. Stack size and creation flags are ignored.
+///
+/// ## Safety
+/// - `start_address` must be safe to call with `parameter`, from another thread.
+/// - `thread_id` must be null, or valid for a `u32` write.
+///
+pub unsafe fn CreateThread(
+    _security_attributes: *mut u8,
+    _stack_size: usize,
+    start_address: unsafe extern "C" fn(*mut u64) -> u32,
+    parameter: *mut u64,
+    _creation_flags: u32,
+    thread_id: *mut u32,
+) -> u64 {
+    struct Parameter(*mut u64);
+    unsafe impl Send for Parameter {}
+
+    let parameter = Parameter(parameter);
+    let (tx, rx) = std::sync::mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        let parameter = parameter;
+        let _ = tx.send(unsafe { GetCurrentThreadId() });
+        unsafe { start_address(parameter.0) }
+    });
+
+    if !thread_id.is_null() {
+        unsafe { *thread_id = rx.recv().unwrap_or(0) };
+    }
+
+    // we don't support waiting on threads. just keep it alive.
+    Box::leak(Box::new(thread));
+
+    NEXT_HANDLE.fetch_add(4, Ordering::Relaxed)
+}
+
+///
+/// # Register Wait For Single Object
+///
+/// Calls `callback(context, 0)` once the event is signaled. Right away if it already is.
+///
+/// ## Remarks
+/// - Callbacks run on the thread that signals the event, not on a thread pool.
+/// - Only [`WT_EXECUTEONLYONCE`] waits without a timeout are supported. `milliseconds` and `flags` are ignored.
+///
+/// ## Safety
+/// - `new_wait_object` must be valid for a `u64` write.
+/// - `callback` must be safe to call with `context`, from any thread, until the wait is unregistered.
+///
+pub unsafe fn RegisterWaitForSingleObject(
+    new_wait_object: *mut u64,
+    object: u64,
+    callback: unsafe extern "C" fn(*mut u8, u8),
+    context: *mut u8,
+    _milliseconds: u32,
+    _flags: u32,
+) -> u32 {
+    let event = match get_event(object) {
+        Some(x) => x,
+        None => return 0,
+    };
+
+    let handle = NEXT_HANDLE.fetch_add(4, Ordering::Relaxed);
+    WAITS.lock().unwrap().insert(
+        handle,
+        HostWait {
+            event: object,
+            callback,
+            context: context as _,
+        },
+    );
+    unsafe { *new_wait_object = handle };
+
+    if *event.signaled.lock().unwrap() {
+        run_waits(object);
+    }
+
+    1
+}
+
+///
+/// # Unregister Wait Ex
+///
+/// Cancels the wait, and waits for its callback if it's running right now. `completion_event` is ignored.
+///
+/// ## Safety
+/// - Always safe. Must not be called from the wait's own callback, that deadlocks. Like the real thing.
+///
+pub unsafe fn UnregisterWaitEx(wait_handle: u64, _completion_event: u64) -> u32 {
+    // a wait that already fired is gone from the map. that's fine too.
+    WAITS.lock().unwrap().remove(&wait_handle);
+    drop(CALLBACKS.lock().unwrap());
+
+    1
+}
+
+/// An AMD64 machine with the cores of the host.
+pub(crate) unsafe fn GetNativeSystemInfo(info: *mut SystemInfo) {
+    let info = unsafe { &mut *info };
+    info.processor_architecture = 9; // PROCESSOR_ARCHITECTURE_AMD64
+    info.page_size = 0x1000;
+    info.allocation_granularity = 0x10000;
+    info.number_of_processors = std::thread::available_parallelism().map_or(1, |x| x.get() as _);
+}
+
+/// The build the driver has offsets for.
+pub(crate) unsafe fn RtlGetVersion(info: *mut OsVersionInfo) -> i32 {
+    let info = unsafe { &mut *info };
+    info.major_version = 10;
+    info.minor_version = 0;
+    info.build_number = 26100;
+    info.platform_id = 2; // VER_PLATFORM_WIN32_NT
+    info.product_type = 1; // VER_NT_WORKSTATION
+    0
+}
@@ -4,12 +4,12 @@ use crate::hxposed::call::HxResult;
 use crate::hxposed::requests::{HxRequest, SyscallRequest};
 use crate::hxposed::responses::{HxResponse, SyscallResponse};
 use core::arch::asm;
-use core::arch::x86_64::_mm_load_si128;
+use core::arch::x86_64::{__m128i, _mm_load_si128};
 
 #[allow(dead_code)]
 pub fn vmcall_typed<R: SyscallRequest>(req: R) -> Result<R::Response, HxError> {
     let raw_resp = vmcall(&mut req.into_raw());
-    if raw_resp.result.error_code != 0 {
+    if raw_resp.result.error_code() != 0 {
         Err(HxError::from_response(&raw_resp))
     } else {
         Ok(R::Response::from_raw(raw_resp))
@@ -20,6 +20,8 @@ pub(crate) fn vmcall(request: &mut HxRequest) -> HxResponse {
     let mut response = HxResponse::default();
     let mut result: u64;
     let mut leaf = 0x2009u64;
+    // hypervisor writes these back when it has extended results. so they are always clobbered.
+    let (xmm0, xmm1, xmm2, xmm3): (__m128i, __m128i, __m128i, __m128i);
 
     if request.call.extended_args_present() {
         unsafe {
@@ -29,10 +31,10 @@ pub(crate) fn vmcall(request: &mut HxRequest) -> HxResponse {
             inout("r9") request.arg2 => response.arg2,
             inout("r10") request.arg3 => response.arg3,
 
-            in("xmm0") _mm_load_si128(&request.extended_arg1 as *const _ as _),
-            in("xmm1") _mm_load_si128(&request.extended_arg2 as *const _ as _),
-            in("xmm2") _mm_load_si128(&request.extended_arg3 as *const _ as _),
-            in("xmm3") _mm_load_si128(&request.extended_arg4 as *const _ as _),
+            inout("xmm0") _mm_load_si128(&request.extended_arg1 as *const _ as _) => xmm0,
+            inout("xmm1") _mm_load_si128(&request.extended_arg2 as *const _ as _) => xmm1,
+            inout("xmm2") _mm_load_si128(&request.extended_arg3 as *const _ as _) => xmm2,
+            inout("xmm3") _mm_load_si128(&request.extended_arg4 as *const _ as _) => xmm3,
 
             inout("rsi") request.call.into_bits() => result,
             inout("rax") leaf);
@@ -44,6 +46,12 @@ pub(crate) fn vmcall(request: &mut HxRequest) -> HxResponse {
             inout("r8") request.arg1 => response.arg1,
             inout("r9") request.arg2 => response.arg2,
             inout("r10") request.arg3 => response.arg3,
+
+            out("xmm0") xmm0,
+            out("xmm1") xmm1,
+            out("xmm2") xmm2,
+            out("xmm3") xmm3,
+
             inout("rsi") request.call.into_bits() => result,
             inout("rax") leaf);
         }
@@ -55,5 +63,14 @@ pub(crate) fn vmcall(request: &mut HxRequest) -> HxResponse {
         response.result = HxResult::from_bits(result);
     }
 
+    if response.result.extended_results_present() {
+        unsafe {
+            response.extended_arg1 = core::mem::transmute::<__m128i, u128>(xmm0);
+            response.extended_arg2 = core::mem::transmute::<__m128i, u128>(xmm1);
+            response.extended_arg3 = core::mem::transmute::<__m128i, u128>(xmm2);
+            response.extended_arg4 = core::mem::transmute::<__m128i, u128>(xmm3);
+        }
+    }
+
     response
 }
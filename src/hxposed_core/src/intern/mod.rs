@@ -1,2 +1,4 @@
+#[cfg(all(feature = "usermode", not(windows)))]
+pub mod host;
 pub(crate) mod instructions;
 pub(crate) mod win;
\ No newline at end of file
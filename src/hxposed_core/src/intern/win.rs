@@ -1,6 +1,9 @@
 #![allow(non_snake_case)]
 
-#[cfg(feature = "usermode")]
+#[cfg(all(feature = "usermode", not(windows)))]
+pub(crate) use super::host::*;
+
+#[cfg(all(feature = "usermode", windows))]
 #[link(name = "kernel32")]
 unsafe extern "C" {
     pub(crate) fn WaitForSingleObject(handle: u64, time: u32) -> u32;
@@ -30,4 +33,63 @@ unsafe extern "C" {
         creation_flags: u32,
         thread_id: *mut u32,
     ) -> u64;
+
+    pub(crate) fn RegisterWaitForSingleObject(
+        new_wait_object: *mut u64,
+        object: u64,
+        callback: unsafe extern "C" fn(*mut u8, u8),
+        context: *mut u8,
+        milliseconds: u32,
+        flags: u32,
+    ) -> u32;
+
+    pub(crate) fn UnregisterWaitEx(wait_handle: u64, completion_event: u64) -> u32;
+}
+
+/// `SYSTEM_INFO`.
+#[cfg(feature = "usermode")]
+#[derive(Default)]
+#[repr(C)]
+pub(crate) struct SystemInfo {
+    pub processor_architecture: u16,
+    pub reserved: u16,
+    pub page_size: u32,
+    pub minimum_application_address: u64,
+    pub maximum_application_address: u64,
+    pub active_processor_mask: u64,
+    pub number_of_processors: u32,
+    pub processor_type: u32,
+    pub allocation_granularity: u32,
+    pub processor_level: u16,
+    pub processor_revision: u16,
+}
+
+/// `OSVERSIONINFOEXW`.
+#[cfg(feature = "usermode")]
+#[repr(C)]
+pub(crate) struct OsVersionInfo {
+    pub size: u32,
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub build_number: u32,
+    pub platform_id: u32,
+    pub csd_version: [u16; 128],
+    pub service_pack_major: u16,
+    pub service_pack_minor: u16,
+    pub suite_mask: u16,
+    pub product_type: u8,
+    pub reserved: u8,
+}
+
+#[cfg(all(feature = "usermode", windows))]
+#[link(name = "kernel32")]
+unsafe extern "C" {
+    pub(crate) fn GetNativeSystemInfo(info: *mut SystemInfo);
+}
+
+// GetVersionEx lies to processes without a manifest. this doesn't.
+#[cfg(all(feature = "usermode", windows))]
+#[link(name = "ntdll")]
+unsafe extern "C" {
+    pub(crate) fn RtlGetVersion(info: *mut OsVersionInfo) -> i32;
 }
@@ -4,4 +4,7 @@ extern crate alloc;
 pub mod error;
 pub mod hxposed;
 mod intern;
-pub mod services;
\ No newline at end of file
+pub mod services;
+
+#[cfg(all(feature = "usermode", not(windows)))]
+pub use intern::host;
\ No newline at end of file
@@ -0,0 +1,215 @@
+use crate::error::HxError;
+use crate::hxposed::AsyncCookie;
+use crate::hxposed::requests::SyscallRequest;
+use crate::hxposed::requests::async_call::AsyncInfo;
+use crate::hxposed::responses::async_call::{AsyncCompletion, AsyncResponse};
+use crate::hxposed::responses::SyscallResponse;
+use crate::hxposed::transport::transport;
+use crate::intern::win::{
+    CloseHandle, CreateEventA, RegisterWaitForSingleObject, UnregisterWaitEx, WaitForSingleObject,
+};
+use alloc::boxed::Box;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::ptr::null_mut;
+use core::task::{Context, Poll, Waker};
+use spin::mutex::SpinMutex;
+
+const INFINITE: u32 = u32::MAX;
+const WT_EXECUTEONLYONCE: u32 = 0x8;
+// UnregisterWaitEx waits for a running callback when given this.
+const INVALID_HANDLE_VALUE: u64 = u64::MAX;
+
+///
+/// # Syscall Async
+///
+/// Sends requests as slow calls. See [`HxAsyncCall`].
+///
+pub trait SyscallAsync<T: SyscallRequest> {
+    fn send_async(self) -> Result<HxAsyncCall<T>, HxError>;
+}
+
+impl<T> SyscallAsync<T> for T
+where
+    T: SyscallRequest,
+{
+    ///
+    /// # Send Async
+    ///
+    /// Queues the request to the hypervisor's worker and returns right away.
+    ///
+    /// ## Return
+    /// * [`HxAsyncCall`] - The queued call. Await it, or [`HxAsyncCall::wait`] on it.
+    /// * [`HxError`] - The request could not be queued.
+    fn send_async(self) -> Result<HxAsyncCall<T>, HxError> {
+        let waiter = Box::new(Waiter {
+            // manual reset, so checking it doesn't eat the signal.
+            event: unsafe { CreateEventA(null_mut(), 1, 0, null_mut()) },
+            waker: SpinMutex::new(None),
+        });
+        let completion = Box::new(AsyncCompletion::default());
+
+        let mut raw = self.into_raw();
+        AsyncInfo {
+            event: waiter.event,
+            completion: completion.as_ref() as *const _ as _,
+        }
+        .attach(&mut raw);
+
+        let response = transport().send(&mut raw);
+        if response.result.error_code() != 0 {
+            return Err(HxError::from_response(&response));
+        }
+
+        Ok(HxAsyncCall {
+            cookie: AsyncResponse::from_raw(response).cookie,
+            completion,
+            waiter,
+            wait: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+struct Waiter {
+    event: u64,
+    waker: SpinMutex<Option<Waker>>,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.event);
+        }
+    }
+}
+
+///
+/// # HxAsyncCall
+///
+/// A slow call in flight. It's a [`Future`], woken when the hypervisor signals completion.
+///
+/// ## Remarks
+/// - Dropping an unfinished call blocks until it finishes. The hypervisor writes to memory owned by this.
+/// - No thread waits for it. The first poll registers a wait callback on the event, which wakes the task.
+///
+pub struct HxAsyncCall<T: SyscallRequest> {
+    cookie: AsyncCookie,
+    completion: Box<AsyncCompletion>,
+    // boxed, the wait callback holds a pointer to it.
+    waiter: Box<Waiter>,
+    /// Registered wait handle. 0 until first polled.
+    wait: u64,
+    phantom: PhantomData<fn() -> T>,
+}
+
+unsafe impl<T: SyscallRequest> Send for HxAsyncCall<T> {}
+
+impl<T: SyscallRequest> Drop for HxAsyncCall<T> {
+    fn drop(&mut self) {
+        if !self.is_completed() {
+            unsafe {
+                WaitForSingleObject(self.waiter.event, INFINITE);
+            }
+        }
+
+        // the callback must be done with the waiter before it goes.
+        if self.wait != 0 {
+            unsafe {
+                UnregisterWaitEx(self.wait, INVALID_HANDLE_VALUE);
+            }
+        }
+    }
+}
+
+impl<T: SyscallRequest> HxAsyncCall<T> {
+    pub fn cookie(&self) -> AsyncCookie {
+        self.cookie
+    }
+
+    ///
+    /// # Is Completed
+    ///
+    /// Polls the call without blocking.
+    ///
+    pub fn is_completed(&self) -> bool {
+        unsafe { (&raw const self.completion.completed).read_volatile() != 0 }
+    }
+
+    ///
+    /// # Wait
+    ///
+    /// Blocks until the call completes.
+    ///
+    /// ## Return
+    /// * `T::Response` - Response of the call.
+    /// * [`HxError`] - The error it failed with.
+    pub fn wait(self) -> Result<T::Response, HxError> {
+        unsafe {
+            WaitForSingleObject(self.waiter.event, INFINITE);
+        }
+        self.result()
+    }
+
+    fn result(&self) -> Result<T::Response, HxError> {
+        let response = unsafe { (&raw const self.completion.response).read_volatile() };
+
+        if response.result.error_code() != 0 {
+            Err(HxError::from_response(&response))
+        } else {
+            Ok(T::Response::from_raw(response))
+        }
+    }
+
+    unsafe extern "C" fn completed(context: *mut u8, _timed_out: u8) {
+        let waiter = unsafe { &*(context as *const Waiter) };
+
+        if let Some(waker) = waiter.waker.lock().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: SyscallRequest> Future for HxAsyncCall<T> {
+    type Output = Result<T::Response, HxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if this.is_completed() {
+            return Poll::Ready(this.result());
+        }
+
+        *this.waiter.waker.lock() = Some(cx.waker().clone());
+
+        // it might have completed before the waker was in place.
+        if this.is_completed() {
+            return Poll::Ready(this.result());
+        }
+
+        if this.wait == 0 {
+            let registered = unsafe {
+                RegisterWaitForSingleObject(
+                    &mut this.wait,
+                    this.waiter.event,
+                    Self::completed,
+                    this.waiter.as_ref() as *const Waiter as _,
+                    INFINITE,
+                    WT_EXECUTEONLYONCE,
+                )
+            };
+
+            // nothing would wake us. better block than hang.
+            if registered == 0 {
+                this.wait = 0;
+                unsafe {
+                    WaitForSingleObject(this.waiter.event, INFINITE);
+                }
+                return Poll::Ready(this.result());
+            }
+        }
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,141 @@
+use crate::error::HxError;
+use crate::hxposed::requests::batch::BatchRequest;
+use crate::hxposed::requests::{HxRequest, Syscall, SyscallRequest};
+use crate::hxposed::responses::{HxResponse, SyscallResponse};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+///
+/// # HxBatch
+///
+/// Queues typed requests and submits them all in one go.
+///
+/// ## Example
+/// ```ignore
+/// let mut batch = HxBatch::new();
+/// let protection = batch.push(GetProcessFieldRequest {
+///     process: process.object(),
+///     field: ProcessField::Protection(ProcessProtection::new()),
+/// });
+/// let signers = batch.push(GetProcessFieldRequest {
+///     process: process.object(),
+///     field: ProcessField::Signers(ProcessSignatureLevels::new()),
+/// });
+///
+/// batch.submit()?;
+///
+/// let protection = batch.get(&protection)?;
+/// let signers = batch.get(&signers)?;
+/// ```
+///
+/// ## Remarks
+/// - Requests can't depend on each other's results. Opening a process and querying it in the same batch won't work.
+/// - Batches larger than [`BatchRequest::MAX_COUNT`] are split into multiple calls.
+///
+#[derive(Default, Debug)]
+pub struct HxBatch {
+    requests: Vec<HxRequest>,
+    responses: Vec<HxResponse>,
+}
+
+///
+/// # Batch Entry
+///
+/// Ticket for a request queued in [`HxBatch`]. Redeem it with [`HxBatch::get`].
+///
+#[derive(Debug)]
+pub struct BatchEntry<T: SyscallRequest> {
+    index: usize,
+    phantom: PhantomData<T>,
+}
+
+impl HxBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    ///
+    /// # Push
+    ///
+    /// Queues a request.
+    ///
+    /// ## Return
+    /// * [`BatchEntry`] - Used to get the response once the batch is submitted.
+    pub fn push<T: SyscallRequest>(&mut self, request: T) -> BatchEntry<T> {
+        self.requests.push(request.into_raw());
+
+        BatchEntry {
+            index: self.requests.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// # Submit
+    ///
+    /// Executes every queued request.
+    ///
+    /// ## Return
+    /// * `()` - All requests were executed. This says nothing about whether they succeeded. See [`Self::get`].
+    /// * [`HxError::InvalidParameters`] - Hypervisor couldn't access the buffers.
+    pub fn submit(&mut self) -> Result<(), HxError> {
+        self.responses.clear();
+        self.responses
+            .resize(self.requests.len(), HxResponse::default());
+
+        let chunk = BatchRequest::MAX_COUNT as usize;
+        for (requests, responses) in self
+            .requests
+            .chunks(chunk)
+            .zip(self.responses.chunks_mut(chunk))
+        {
+            BatchRequest {
+                requests: requests.as_ptr() as _,
+                responses: responses.as_mut_ptr() as _,
+                count: requests.len() as _,
+            }
+            .send()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// # Get
+    ///
+    /// Gets the response of a queued request.
+    ///
+    /// ## Panic
+    /// - If the batch was not submitted.
+    ///
+    /// ## Return
+    /// * `T::Response` - Response of the request.
+    /// * [`HxError`] - The error this request failed with.
+    pub fn get<T: SyscallRequest>(&self, entry: &BatchEntry<T>) -> Result<T::Response, HxError> {
+        let response = self.responses[entry.index];
+
+        if response.result.error_code() != 0 {
+            Err(HxError::from_response(&response))
+        } else {
+            Ok(T::Response::from_raw(response))
+        }
+    }
+
+    ///
+    /// # Clear
+    ///
+    /// Removes every request and response, so the batch can be reused.
+    ///
+    pub fn clear(&mut self) {
+        self.requests.clear();
+        self.responses.clear();
+    }
+}
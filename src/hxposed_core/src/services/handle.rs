@@ -1,8 +1,10 @@
 use crate::error::HxError;
-use crate::hxposed::{Handle, HxObject};
+use crate::hxposed::{Handle, HxObject, ObjectType, ProcessObject};
 use crate::hxposed::requests::handle::*;
 use crate::hxposed::responses::handle::*;
 use crate::hxposed::requests::Syscall;
+use crate::services::process::HxProcess;
+use alloc::string::String;
 
 /// # HxHandle
 ///
@@ -10,13 +12,41 @@ use crate::hxposed::requests::Syscall;
 ///
 /// ## Getting a handle with full access rights
 ///
-/// To get a handle with full rights, do the steps below:
+/// Use `to_handle` of the object, e.g. [`HxProcess::to_handle`](crate::services::process::HxProcess::to_handle) with [`Self::HANDLE_ALL_ACCESS`].
+///
+/// Or, for objects HxPosed can't open, do the steps below:
 /// 1. Create a handle to current process with `PROCESS_QUERY_LIMITED_INFORMATION` (to be sure)
 /// 2. Upgrade the handle via [`Self::upgrade`] to all access rights
 /// 3. Change the underlying object via [`Self::set_object`] to desired object
 /// 4. Use the handle as is.
+///
+/// ## Handles of other processes
+///
+/// [`Self::from_remote`] makes one that lives in another process's handle table. Every method acts on that table then.
+/// Use it with [`HxProcess::handles`] to [`Self::downgrade`] or [`Self::force_close`] what a process holds.
 pub struct HxHandle {
-    pub handle: Handle
+    pub handle: Handle,
+    /// Whose handle table. 0 is the caller's.
+    process: ProcessObject,
+}
+
+///
+/// # HxHandle Info
+///
+/// A handle in the handle table of a process. See [`HxProcess::handles`](crate::services::process::HxProcess::handles).
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HxHandleInfo {
+    /// Value of the handle, in the process it belongs to.
+    pub handle: Handle,
+    /// Address of the object in kernel. Can be given to [`HxHandle::set_object`].
+    pub object: HxObject,
+    /// Object type name, e.g. `Process`, `File`, `Key`.
+    pub type_name: String,
+    /// Object name. [`None`] for unnamed objects, and ones the driver won't ask.
+    pub name: Option<String>,
+    pub granted_access: u32,
+    pub attributes: HandleAttributes,
 }
 
 impl HxHandle {
@@ -28,7 +58,43 @@ impl HxHandle {
     ///
     /// A new instance of HxHandle from handle object.
     pub fn from_handle(handle: u64) -> HxHandle {
-        Self { handle }
+        Self { handle, process: 0 }
+    }
+
+    ///
+    /// # From Remote
+    ///
+    /// A handle in the handle table of `process`, instead of yours.
+    ///
+    /// ## Remarks
+    /// - Keep `process` open while you use it. Its object is what the requests go to.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// for info in process.handles().unwrap().filter(|x| x.type_name == "Process") {
+    ///     HxHandle::from_remote(&process, info.handle).downgrade(PROCESS_QUERY_LIMITED_INFORMATION).unwrap();
+    /// }
+    /// ```
+    pub fn from_remote(process: &HxProcess, handle: Handle) -> HxHandle {
+        Self {
+            handle,
+            process: process.object(),
+        }
+    }
+
+    ///
+    /// # Create
+    ///
+    /// Opens a handle to an object the caller has open. See [`CreateHandleRequest`].
+    ///
+    pub(crate) fn create(object: ObjectType, access_rights: u32) -> Result<HxHandle, HxError> {
+        CreateHandleRequest {
+            object,
+            access_rights,
+        }
+        .send()
+        .map(|x| Self::from_handle(x.handle))
     }
 
     ///
@@ -47,7 +113,7 @@ impl HxHandle {
         UpgradeHandleRequest {
             handle: self.handle,
             access_rights,
-            process: 0
+            process: self.process
         }.send().map(|_| ())
     }
 
@@ -60,17 +126,38 @@ impl HxHandle {
     /// `new_object` - Address of the object in kernel
     ///
     /// ## Remarks
-    /// - It is undefined what happens (probably BSOD) if you set it to an invalid object
-    /// - There is no checking for that. So be careful and always take pointers from Hx structures
+    /// - The driver checks that `new_object` is an object, of the same type as the current one. See [`Self::force_set_object`] to change the type.
+    /// - Still, always take pointers from Hx structures. A freed object passes the checks just fine
     /// - You still need to adjust the handle access rights to access the object
     /// - The handle stays valid even if you close the Hx object associated with it (e.g. [`HxProcess`])
     /// - The handle is still a normal handle object you can close with `CloseHandle`
     /// - This handles the kernel-mode reference counts. So don't worry and enjoy the handle
+    ///
+    /// ## Returns
+    /// * Nothing - Swapped.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::TypeMismatch`](crate::hxposed::error::NotAllowedReason::TypeMismatch). Not an object, or not of the same type.
     pub fn set_object(&mut self, new_object: HxObject) -> Result<(), HxError> {
         SwapHandleObjectRequest {
             handle: self.handle,
-            process: 0,
-            object: new_object
+            process: self.process,
+            object: new_object,
+            force: false,
+        }.send().map(|_| ())
+    }
+
+    ///
+    /// # Force Set Object
+    ///
+    /// [`Self::set_object`], minus the type check. `new_object` must still be an object.
+    ///
+    /// ## Remarks
+    /// - Owner of the handle keeps treating it as the old type. Passing a `Token` where a `Process` was expected is on you.
+    pub fn force_set_object(&mut self, new_object: HxObject) -> Result<(), HxError> {
+        SwapHandleObjectRequest {
+            handle: self.handle,
+            process: self.process,
+            object: new_object,
+            force: true,
         }.send().map(|_| ())
     }
 
@@ -84,7 +171,56 @@ impl HxHandle {
     pub fn get_object(&mut self) -> Result<GetHandleObjectResponse, HxError> {
         GetHandleObjectRequest {
             handle: self.handle,
-            process: 0,
+            process: self.process,
         }.send()
     }
+
+    ///
+    /// # Downgrade
+    ///
+    /// Takes access rights away from the handle. Opposite of [`Self::upgrade`].
+    ///
+    /// ## Arguments
+    /// `access_rights` - Rights to keep. Others are removed, and none are added.
+    ///
+    /// ## Remarks
+    /// - Owner finds out the next time it uses the handle. Rights it checked earlier stay checked.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::HANDLE_MANAGE`](crate::hxposed::permissions::PluginPermissions::HANDLE_MANAGE)
+    pub fn downgrade(&mut self, access_rights: u32) -> Result<(), HxError> {
+        DowngradeHandleRequest {
+            handle: self.handle,
+            process: self.process,
+            access_rights,
+        }
+        .send()
+        .map(|_| ())
+    }
+
+    ///
+    /// # Force Close
+    ///
+    /// Closes the handle in the process it belongs to, as if it called `CloseHandle` itself.
+    ///
+    /// ## Remarks
+    /// - Owner gets `STATUS_INVALID_HANDLE` for it from then on. Code that doesn't expect that may crash.
+    /// - Handles protected from close are left alone. See [`HxHandleInfo::attributes`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::HANDLE_MANAGE`](crate::hxposed::permissions::PluginPermissions::HANDLE_MANAGE)
+    ///
+    /// ## Returns
+    /// * Nothing - Closed.
+    /// * [`HxError::NotFound`] - [`NotFoundReason::Handle`](crate::hxposed::error::NotFoundReason::Handle). No such handle.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::HandleProtected`](crate::hxposed::error::NotAllowedReason::HandleProtected). Handle is protected from close.
+    /// * [`HxError::NtError`] - Closing failed.
+    pub fn force_close(self) -> Result<(), HxError> {
+        CloseRemoteHandleRequest {
+            handle: self.handle,
+            process: self.process,
+        }
+        .send()
+        .map(|_| ())
+    }
 }
\ No newline at end of file
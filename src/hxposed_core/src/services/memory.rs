@@ -2,11 +2,40 @@
 
 use crate::error::HxError;
 use crate::hxposed::requests::memory::*;
+use crate::hxposed::error::NotAllowedReason;
 use crate::hxposed::requests::Syscall;
-use crate::hxposed::responses::memory::PageAttributeResponse;
+use crate::hxposed::responses::memory::{
+    MemoryRegionEntry, MemoryRegionsResponse, PageAttributeResponse, VadType,
+};
 use crate::hxposed::responses::HxResponse;
 use crate::hxposed::ProcessObject;
 use crate::services::memory_map::HxMemoryDescriptor;
+use crate::services::output::{table_entries, table_string};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+///
+/// # Pod
+///
+/// Types any bit pattern is a valid instance of. What [`HxMemory::read`] can build out of another process's bytes.
+///
+/// ## Safety
+/// - Every bit pattern of `size_of::<Self>()` bytes must be a valid [`Self`]. So no `bool`, `char`, enums or references.
+///
+pub unsafe trait Pod: Copy {}
+
+macro_rules! pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
 
 #[derive(Debug)]
 pub struct HxMemory {
@@ -69,9 +98,9 @@ impl HxMemory {
     /// - Size of [`T`] must not be bigger than [`u32::MAX`].
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::MEMORY_PHYSICAL`]
-    /// - [`PluginPermissions::MEMORY_VIRTUAL`]
-    /// - [`PluginPermissions::MEMORY_ALLOCATION`]
+    /// * [`PluginPermissions::MEMORY_PHYSICAL`]
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    /// * [`PluginPermissions::MEMORY_ALLOCATION`]
     ///
     /// ## Return
     /// * [`HxMemoryDescriptor<T>`] - An abstract representation of the allocation. See [`HxMemoryDescriptor`].
@@ -122,4 +151,279 @@ impl HxMemory {
             .send()?
             .rmd)
     }
+
+    ///
+    /// # Read Bytes
+    ///
+    /// Copies memory at `address` in the process into `buffer`.
+    ///
+    /// ## Arguments
+    /// * `address` - Virtual address in the process.
+    /// * `buffer` - Where to copy to. Its length is how much to read.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Return
+    /// * [`usize`] - Bytes read. Less than `buffer.len()` when a page in the way isn't readable.
+    /// * [`HxError::InvalidParameters`] - `buffer` is longer than [`u32::MAX`].
+    pub fn read_bytes(&self, address: u64, buffer: &mut [u8]) -> Result<usize, HxError> {
+        let size = u32::try_from(buffer.len()).map_err(|_| HxError::InvalidParameters(1))?;
+
+        Ok(ReadVirtualMemoryRequest {
+            addr_space: self.process,
+            address,
+            buffer: buffer.as_mut_ptr() as _,
+            size,
+        }
+        .send()?
+        .bytes_copied as _)
+    }
+
+    ///
+    /// # Write Bytes
+    ///
+    /// Copies `buffer` to `address` in the process.
+    ///
+    /// ## Arguments
+    /// * `address` - Virtual address in the process.
+    /// * `buffer` - What to write.
+    ///
+    /// ## Remarks
+    /// - Page protection is respected. Read-only pages stay read-only, and stop the copy.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Return
+    /// * [`usize`] - Bytes written. Less than `buffer.len()` when a page in the way isn't writable.
+    /// * [`HxError::InvalidParameters`] - `buffer` is longer than [`u32::MAX`].
+    pub fn write_bytes(&self, address: u64, buffer: &[u8]) -> Result<usize, HxError> {
+        let size = u32::try_from(buffer.len()).map_err(|_| HxError::InvalidParameters(1))?;
+
+        Ok(WriteVirtualMemoryRequest {
+            addr_space: self.process,
+            address,
+            buffer: buffer.as_ptr() as _,
+            size,
+        }
+        .send()?
+        .bytes_copied as _)
+    }
+
+    ///
+    /// # Read<T>
+    ///
+    /// Reads an instance of [`T`] at `address` in the process.
+    ///
+    /// ## Remarks
+    /// - Bytes are taken as they are. Hence the [`Pod`] bound.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Return
+    /// * [`T`] - The value.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`]. Only part of it could be read.
+    pub fn read<T: Pod>(&self, address: u64) -> Result<T, HxError> {
+        let mut value = MaybeUninit::<T>::uninit();
+        let buffer = unsafe {
+            core::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, size_of::<T>())
+        };
+
+        match self.read_bytes(address, buffer)? {
+            x if x == size_of::<T>() => Ok(unsafe { value.assume_init() }),
+            _ => Err(HxError::NotAllowed(NotAllowedReason::AccessViolation)),
+        }
+    }
+
+    ///
+    /// # Write<T>
+    ///
+    /// Writes `value` to `address` in the process.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Return
+    /// * Nothing - All of it was written.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`]. Only part of it could be written.
+    pub fn write<T: Copy>(&self, address: u64, value: &T) -> Result<(), HxError> {
+        let buffer = unsafe {
+            core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>())
+        };
+
+        match self.write_bytes(address, buffer)? {
+            x if x == size_of::<T>() => Ok(()),
+            _ => Err(HxError::NotAllowed(NotAllowedReason::AccessViolation)),
+        }
+    }
+
+    ///
+    /// # Ptr<T>
+    ///
+    /// A typed pointer to `address` in the process. See [`RemotePtr`].
+    ///
+    pub fn ptr<T: Copy>(&self, address: u64) -> RemotePtr<'_, T> {
+        RemotePtr {
+            memory: self,
+            address,
+            phantom: PhantomData,
+        }
+    }
+}
+
+///
+/// # RemotePtr<T>
+///
+/// Pointer to a [`T`] in another process. Reads and writes go through [`HxMemory::read`] and [`HxMemory::write`].
+///
+/// Borrows the [`HxMemory`] it came from, so the process stays open as long as this lives.
+///
+/// ## Example
+/// ```rust
+/// let process = HxProcess::open(1234).unwrap();
+/// let counter = process.memory.ptr::<u32>(0x7FF6_1234_0000);
+///
+/// counter.write(&(counter.read().unwrap() + 1)).unwrap();
+/// ```
+pub struct RemotePtr<'a, T: Copy> {
+    memory: &'a HxMemory,
+    address: u64,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Copy> Clone for RemotePtr<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Copy> Copy for RemotePtr<'_, T> {}
+
+impl<T: Copy> fmt::Debug for RemotePtr<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RemotePtr({:#x} in {:#x})", self.address, self.memory.process)
+    }
+}
+
+impl<'a, T: Copy> RemotePtr<'a, T> {
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    pub fn read(&self) -> Result<T, HxError>
+    where
+        T: Pod,
+    {
+        self.memory.read(self.address)
+    }
+
+    pub fn write(&self, value: &T) -> Result<(), HxError> {
+        self.memory.write(self.address, value)
+    }
+
+    ///
+    /// # Add
+    ///
+    /// Same as `pointer::add`. Moves `count` [`T`]s forward.
+    ///
+    pub fn add(&self, count: usize) -> Self {
+        Self {
+            address: self
+                .address
+                .wrapping_add((count * size_of::<T>()) as u64),
+            ..*self
+        }
+    }
+
+    ///
+    /// # Cast<U>
+    ///
+    /// Same address, different type.
+    ///
+    pub fn cast<U: Copy>(&self) -> RemotePtr<'a, U> {
+        RemotePtr {
+            memory: self.memory,
+            address: self.address,
+            phantom: PhantomData,
+        }
+    }
+}
+
+///
+/// # Memory Regions
+///
+/// Regions of an address space. See [`HxProcess::memory_regions`](crate::services::process::HxProcess::memory_regions).
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HxMemoryRegions {
+    /// Regions, in address order.
+    pub regions: Vec<HxMemoryRegion>,
+    /// The walk gave up on part of the VAD tree. Regions in there are missing.
+    pub truncated: bool,
+}
+
+///
+/// # Memory Region
+///
+/// A region of an address space, as the VAD tree describes it. See [`HxProcess::memory_regions`](crate::services::process::HxProcess::memory_regions).
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HxMemoryRegion {
+    /// First page of the region.
+    pub start_vpn: u64,
+    /// Last page of the region. Inclusive.
+    pub end_vpn: u64,
+    /// Committed pages.
+    pub commit_charge: u64,
+    /// `PAGE_*` protection the region was reserved with. Pages may differ.
+    pub protection: u32,
+    pub vad_type: VadType,
+    /// Private memory, as opposed to a mapped section.
+    pub private: bool,
+    /// Backing file, for images and mapped files. Relative to its volume, e.g. `\Windows\System32\ntdll.dll`.
+    pub file_name: Option<String>,
+}
+
+impl HxMemoryRegion {
+    pub fn base_address(&self) -> u64 {
+        self.start_vpn << 12
+    }
+
+    pub fn size(&self) -> u64 {
+        (self.end_vpn - self.start_vpn + 1) << 12
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        (self.start_vpn..=self.end_vpn).contains(&(address >> 12))
+    }
+
+    ///
+    /// # From Response
+    ///
+    /// Decodes the blob in [`MemoryRegionsResponse`].
+    ///
+    /// ## Return
+    /// * [`Vec<HxMemoryRegion>`] - Regions, in address order.
+    /// * [`HxError::InvalidParameters`] - Entries or names are out of the blob.
+    pub(crate) fn from_response(
+        response: &MemoryRegionsResponse,
+        blob: &[u8],
+    ) -> Result<Vec<Self>, HxError> {
+        table_entries::<MemoryRegionEntry>(blob, response.count)?
+            .into_iter()
+            .map(|entry| {
+                Ok(Self {
+                    start_vpn: entry.start_vpn,
+                    end_vpn: entry.end_vpn,
+                    commit_charge: entry.commit_charge,
+                    protection: entry.page_protection(),
+                    vad_type: VadType::from_bits(entry.vad_type),
+                    private: entry.private != 0,
+                    file_name: table_string(blob, entry.file_name)?,
+                })
+            })
+            .collect()
+    }
 }
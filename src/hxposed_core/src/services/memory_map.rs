@@ -35,7 +35,7 @@ unsafe impl<T> Send for HxMemoryDescriptor<T> {}
 /// If this isn't mapped to current process, using this structure will result in segmentation fault.
 /// You have 2 options:
 /// 1. Create a new [`HxMemoryDescriptor`] that describes same pages using [`HxMemoryDescriptor::new_describe`]. And map it into your own address space.
-/// 2. Use [`HxMemory::write`] and [`HxMemory::read`].
+/// 2. Use [`HxMemory::write`] and [`HxMemory::read`](crate::services::memory::HxMemory::read). Or a [`RemotePtr`](crate::services::memory::RemotePtr).
 ///
 /// Personally I would choose the first one.
 pub struct HxMemoryGuard<'process, T> {
@@ -81,7 +81,7 @@ impl<'a, T> HxMemoryGuard<'a, T> {
 impl<T> Drop for HxMemoryDescriptor<T> {
     fn drop(&mut self) {
         if self.owns {
-            let _ = FreeMemoryRequest { obj: self.rmd }.send();
+            FreeMemoryRequest { obj: self.rmd }.send_forget();
         }
     }
 }
@@ -0,0 +1,435 @@
+//!
+//! # Minidump
+//!
+//! Writes `MDMP` files, the format `MiniDumpWriteDump` produces and debuggers open.
+//!
+//! The writer only knows about [`Minidump`]. Where its contents come from is up to the caller,
+//! [`HxProcess::minidump`](crate::services::process::HxProcess::minidump) being one source.
+//!
+
+use crate::error::HxError;
+use crate::intern::win::{GetNativeSystemInfo, OsVersionInfo, RtlGetVersion, SystemInfo};
+use crate::services::memory::HxMemory;
+use crate::services::types::thread_fields::ThreadContext;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const SIGNATURE: u32 = 0x504D_444D; // "MDMP"
+const VERSION: u32 = 0xA793;
+const MINIDUMP_WITH_FULL_MEMORY: u64 = 0x2;
+
+const THREAD_LIST_STREAM: u32 = 3;
+const MODULE_LIST_STREAM: u32 = 4;
+const MEMORY_LIST_STREAM: u32 = 5;
+const SYSTEM_INFO_STREAM: u32 = 7;
+const MEMORY64_LIST_STREAM: u32 = 9;
+const STREAM_COUNT: usize = 5;
+
+const HEADER_SIZE: usize = 32;
+const DIRECTORY_ENTRY_SIZE: usize = 12;
+const SYSTEM_INFO_SIZE: usize = 56;
+const THREAD_SIZE: usize = 48;
+const MODULE_SIZE: usize = 108;
+const MEMORY_DESCRIPTOR_SIZE: usize = 16;
+const MEMORY64_DESCRIPTOR_SIZE: usize = 16;
+
+const PAGE_SIZE: u64 = 0x1000;
+// a read request copies at most this much. keeps the buffer from getting huge.
+const READ_CHUNK: u64 = 0x10_0000;
+
+///
+/// # Minidump
+///
+/// Everything that goes into a dump. See [`Self::to_bytes`].
+///
+#[derive(Clone, Debug, Default)]
+pub struct Minidump {
+    pub system_info: MinidumpSystemInfo,
+    pub threads: Vec<MinidumpThread>,
+    pub modules: Vec<MinidumpModule>,
+    /// Goes into `Memory64ListStream`. Thread stacks are separate, in `MemoryListStream`.
+    pub memory: Vec<MinidumpMemory>,
+    /// Seconds since 1970. 0 if unknown.
+    pub timestamp: u32,
+}
+
+///
+/// # Minidump System Info
+///
+/// `MINIDUMP_SYSTEM_INFO`, minus the CPU information.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinidumpSystemInfo {
+    /// `PROCESSOR_ARCHITECTURE_*`. 9 for AMD64.
+    pub processor_architecture: u16,
+    pub processor_level: u16,
+    pub processor_revision: u16,
+    pub number_of_processors: u8,
+    /// `VER_NT_*`.
+    pub product_type: u8,
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub build_number: u32,
+}
+
+///
+/// # Minidump Thread
+///
+/// A thread, its registers and its stack.
+///
+#[derive(Clone, Debug)]
+pub struct MinidumpThread {
+    pub id: u32,
+    /// Before the dump was taken.
+    pub suspend_count: u32,
+    pub priority_class: u32,
+    pub priority: u32,
+    /// 0 if unknown.
+    pub teb: u64,
+    pub context: ThreadContext,
+    /// Usually from `rsp` to the end of the stack region. May be empty.
+    pub stack: MinidumpMemory,
+}
+
+///
+/// # Minidump Module
+///
+/// A loaded image.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MinidumpModule {
+    pub base: u64,
+    pub size: u32,
+    /// `CheckSum` of the PE optional header. 0 if unknown.
+    pub checksum: u32,
+    /// `TimeDateStamp` of the PE file header. 0 if unknown.
+    pub timestamp: u32,
+    /// Full path. Debuggers look symbols up with it.
+    pub name: String,
+}
+
+///
+/// # Minidump Memory
+///
+/// Contents of a range of the address space.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MinidumpMemory {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+impl MinidumpSystemInfo {
+    ///
+    /// # Current
+    ///
+    /// Describes the machine we run on. That's also the machine of every process HxPosed can dump.
+    ///
+    pub fn current() -> Self {
+        let mut system = SystemInfo::default();
+        let mut version = OsVersionInfo {
+            size: size_of::<OsVersionInfo>() as _,
+            major_version: 0,
+            minor_version: 0,
+            build_number: 0,
+            platform_id: 0,
+            csd_version: [0; 128],
+            service_pack_major: 0,
+            service_pack_minor: 0,
+            suite_mask: 0,
+            product_type: 0,
+            reserved: 0,
+        };
+
+        unsafe {
+            GetNativeSystemInfo(&mut system);
+            RtlGetVersion(&mut version);
+        }
+
+        Self {
+            processor_architecture: system.processor_architecture,
+            processor_level: system.processor_level,
+            processor_revision: system.processor_revision,
+            number_of_processors: system.number_of_processors.min(u8::MAX as _) as _,
+            product_type: version.product_type,
+            major_version: version.major_version,
+            minor_version: version.minor_version,
+            build_number: version.build_number,
+        }
+    }
+}
+
+impl Default for MinidumpSystemInfo {
+    fn default() -> Self {
+        Self {
+            processor_architecture: 9,
+            processor_level: 0,
+            processor_revision: 0,
+            number_of_processors: 0,
+            product_type: 0,
+            major_version: 0,
+            minor_version: 0,
+            build_number: 0,
+        }
+    }
+}
+
+impl Minidump {
+    ///
+    /// # To Bytes
+    ///
+    /// Writes the dump. Has a thread list, module list, memory list, `Memory64` list and system info stream.
+    ///
+    /// ## Remarks
+    /// - [`Self::memory`] is written last, so only it may go past 4GB. Everything else has to fit below.
+    ///
+    /// ## Return
+    /// * [`Vec<u8>`] - Contents of the `.dmp` file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = DumpWriter::default();
+
+        // header
+        w.u32(SIGNATURE);
+        w.u32(VERSION);
+        w.u32(STREAM_COUNT as _);
+        w.u32(HEADER_SIZE as _);
+        w.u32(0); // checksum
+        w.u32(self.timestamp);
+        w.u64(MINIDUMP_WITH_FULL_MEMORY);
+
+        let directory = w.rva();
+        w.zeroes(STREAM_COUNT * DIRECTORY_ENTRY_SIZE);
+        let mut streams = Vec::with_capacity(STREAM_COUNT);
+
+        // system info
+        let start = w.rva();
+        let info = &self.system_info;
+        w.u16(info.processor_architecture);
+        w.u16(info.processor_level);
+        w.u16(info.processor_revision);
+        w.u8(info.number_of_processors);
+        w.u8(info.product_type);
+        w.u32(info.major_version);
+        w.u32(info.minor_version);
+        w.u32(info.build_number);
+        w.u32(2); // VER_PLATFORM_WIN32_NT
+        let csd_version = w.rva();
+        w.u32(0);
+        w.u16(0); // suite mask
+        w.u16(0);
+        w.zeroes(24); // cpu information
+        streams.push((SYSTEM_INFO_STREAM, start, SYSTEM_INFO_SIZE));
+
+        let rva = w.string("");
+        w.patch_u32(csd_version, rva);
+
+        // thread list. stacks and contexts are patched in later.
+        let start = w.rva();
+        w.u32(self.threads.len() as _);
+        let mut thread_entries = Vec::with_capacity(self.threads.len());
+        for thread in &self.threads {
+            thread_entries.push(w.rva());
+            w.u32(thread.id);
+            w.u32(thread.suspend_count);
+            w.u32(thread.priority_class);
+            w.u32(thread.priority);
+            w.u64(thread.teb);
+            w.u64(thread.stack.address);
+            w.zeroes(8); // stack location
+            w.zeroes(8); // context location
+        }
+        streams.push((THREAD_LIST_STREAM, start, 4 + self.threads.len() * THREAD_SIZE));
+
+        // module list. names come after.
+        let start = w.rva();
+        w.u32(self.modules.len() as _);
+        let mut module_entries = Vec::with_capacity(self.modules.len());
+        for module in &self.modules {
+            module_entries.push(w.rva());
+            w.u64(module.base);
+            w.u32(module.size);
+            w.u32(module.checksum);
+            w.u32(module.timestamp);
+            w.u32(0); // name
+            w.zeroes(52); // VS_FIXEDFILEINFO
+            w.zeroes(8); // cv record
+            w.zeroes(8); // misc record
+            w.zeroes(16); // reserved
+        }
+        streams.push((MODULE_LIST_STREAM, start, 4 + self.modules.len() * MODULE_SIZE));
+
+        for (module, entry) in self.modules.iter().zip(&module_entries) {
+            let rva = w.string(&module.name);
+            w.patch_u32(*entry + 20, rva);
+        }
+
+        for (thread, entry) in self.threads.iter().zip(&thread_entries) {
+            w.align(16);
+            let rva = w.rva();
+            w.bytes(thread.context.as_bytes());
+            w.patch_u32(*entry + 40, size_of::<ThreadContext>() as _);
+            w.patch_u32(*entry + 44, rva as _);
+        }
+
+        // memory list. just the stacks, each shared with its thread.
+        let start = w.rva();
+        w.u32(self.threads.len() as _);
+        let mut stack_entries = Vec::with_capacity(self.threads.len());
+        for thread in &self.threads {
+            stack_entries.push(w.rva());
+            w.u64(thread.stack.address);
+            w.u32(thread.stack.data.len() as _);
+            w.u32(0);
+        }
+        streams.push((
+            MEMORY_LIST_STREAM,
+            start,
+            4 + self.threads.len() * MEMORY_DESCRIPTOR_SIZE,
+        ));
+
+        for ((thread, entry), stack) in self.threads.iter().zip(&thread_entries).zip(&stack_entries) {
+            let rva = w.rva();
+            w.bytes(&thread.stack.data);
+            w.patch_u32(*entry + 32, thread.stack.data.len() as _);
+            w.patch_u32(*entry + 36, rva as _);
+            w.patch_u32(*stack + 12, rva as _);
+        }
+
+        // memory64 list. its data has to be contiguous, and last.
+        let start = w.rva();
+        let size = 16 + self.memory.len() * MEMORY64_DESCRIPTOR_SIZE;
+        w.u64(self.memory.len() as _);
+        w.u64((start + size) as _);
+        for memory in &self.memory {
+            w.u64(memory.address);
+            w.u64(memory.data.len() as _);
+        }
+        streams.push((MEMORY64_LIST_STREAM, start, size));
+
+        for memory in &self.memory {
+            w.bytes(&memory.data);
+        }
+
+        for (i, (stream_type, rva, size)) in streams.into_iter().enumerate() {
+            let entry = directory + i * DIRECTORY_ENTRY_SIZE;
+            w.patch_u32(entry, stream_type);
+            w.patch_u32(entry + 4, size as _);
+            w.patch_u32(entry + 8, rva as _);
+        }
+
+        w.bytes
+    }
+}
+
+///
+/// # Read Readable
+///
+/// Reads `size` bytes from `address`, skipping pages that can't be read.
+///
+/// ## Return
+/// * [`Vec<MinidumpMemory>`] - Runs of readable memory, in address order.
+pub(crate) fn read_readable(
+    memory: &HxMemory,
+    address: u64,
+    size: u64,
+) -> Result<Vec<MinidumpMemory>, HxError> {
+    let end = address + size;
+    let mut runs = Vec::new();
+    let mut run = MinidumpMemory {
+        address,
+        data: Vec::new(),
+    };
+    let mut current = address;
+
+    while current < end {
+        let length = (end - current).min(READ_CHUNK) as usize;
+        let mut buffer = alloc::vec![0u8; length];
+
+        let read = match memory.read_bytes(current, &mut buffer) {
+            Ok(x) => x,
+            // not mapped, or paged out for good.
+            Err(HxError::NtError(_)) => 0,
+            Err(e) => return Err(e),
+        };
+
+        run.data.extend_from_slice(&buffer[..read]);
+        current += read as u64;
+
+        if read < length {
+            // page at `current` isn't readable. close the run, go on from the next one.
+            let next = (current & !(PAGE_SIZE - 1)) + PAGE_SIZE;
+            if !run.data.is_empty() {
+                runs.push(run);
+            }
+            run = MinidumpMemory {
+                address: next,
+                data: Vec::new(),
+            };
+            current = next;
+        }
+    }
+
+    if !run.data.is_empty() {
+        runs.push(run);
+    }
+
+    Ok(runs)
+}
+
+#[derive(Default)]
+struct DumpWriter {
+    bytes: Vec<u8>,
+}
+
+impl DumpWriter {
+    fn rva(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    fn zeroes(&mut self, count: usize) {
+        self.bytes.resize(self.bytes.len() + count, 0);
+    }
+
+    fn align(&mut self, alignment: usize) {
+        self.zeroes(self.rva().next_multiple_of(alignment) - self.rva());
+    }
+
+    fn patch_u32(&mut self, at: usize, value: u32) {
+        self.bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// `MINIDUMP_STRING`. Length in bytes, then UTF-16 with a terminator that isn't counted.
+    fn string(&mut self, string: &str) -> u32 {
+        self.align(4);
+        let rva = self.rva() as u32;
+        let utf16 = string.encode_utf16().collect::<Vec<_>>();
+
+        self.u32((utf16.len() * 2) as _);
+        for c in utf16 {
+            self.u16(c);
+        }
+        self.u16(0);
+
+        rva
+    }
+}
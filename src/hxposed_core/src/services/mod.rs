@@ -1,8 +1,16 @@
 #[cfg(feature = "usermode")]
+pub mod async_call;
+#[cfg(feature = "usermode")]
+pub mod batch;
+#[cfg(feature = "usermode")]
 pub mod memory;
 #[cfg(feature = "usermode")]
 pub mod memory_map;
 #[cfg(feature = "usermode")]
+pub mod minidump;
+#[cfg(feature = "usermode")]
+pub mod output;
+#[cfg(feature = "usermode")]
 pub mod process;
 #[cfg(feature = "usermode")]
 pub mod security;
@@ -14,5 +22,7 @@ pub mod callbacks;
 pub mod cpu;
 #[cfg(feature = "usermode")]
 pub mod handle;
+#[cfg(feature = "usermode")]
+pub mod status;
 
 pub mod types;
\ No newline at end of file
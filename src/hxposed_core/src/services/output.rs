@@ -0,0 +1,283 @@
+use crate::error::HxError;
+use crate::hxposed::error::NotAllowedReason;
+use crate::hxposed::output::{OutputBufferHeader, OutputSlice};
+use crate::hxposed::requests::output::RegisterOutputBufferRequest;
+use crate::hxposed::requests::{Syscall, SyscallRequest};
+use crate::services::async_call::SyscallAsync;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hint::spin_loop;
+use core::sync::atomic::{AtomicU8, AtomicU32, AtomicU64, Ordering};
+
+const UNREGISTERED: u8 = 0;
+const REGISTERING: u8 = 1;
+const REGISTERED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNREGISTERED);
+static ADDRESS: AtomicU64 = AtomicU64::new(0);
+static SIZE: AtomicU32 = AtomicU32::new(0);
+/// Bumped before every registration. Results from before that may be overwritten, or in the old buffer.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+///
+/// # HxOutputBuffer
+///
+/// The output buffer of this process. Variable-length results, like [`HxProcess::get_nt_path`](crate::services::process::HxProcess::get_nt_path), come back through it.
+///
+/// It's registered on first use with [`Self::DEFAULT_SIZE`]. Call [`Self::register`] before that for a bigger one.
+/// The hypervisor picks where it goes.
+///
+/// See [`OutputBufferHeader`] for the layout.
+///
+pub struct HxOutputBuffer {}
+
+impl HxOutputBuffer {
+    /// 64 KiB. Plenty for paths and thread lists. Grows when a result doesn't fit even when it's empty.
+    pub const DEFAULT_SIZE: u32 = 0x10000;
+    /// The buffer never grows past this.
+    pub const MAX_SIZE: u32 = OutputBufferHeader::MAX_SIZE;
+
+    ///
+    /// # Register
+    ///
+    /// Has the hypervisor allocate the output buffer and map it into this process.
+    ///
+    /// ## Arguments
+    /// * `size` - Size of the buffer. Rounded up to page size.
+    ///
+    /// ## Remarks
+    /// - The buffer is emptied. Results not read yet are gone. The wrappers here notice, and ask again.
+    /// - A replaced buffer stays mapped. It's just not written to anymore.
+    ///
+    /// ## Return
+    /// * [`u64`] - Where the buffer is mapped.
+    /// * [`HxError::InvalidParameters`] - `size` is more than [`Self::MAX_SIZE`].
+    pub fn register(size: u32) -> Result<u64, HxError> {
+        // before the buffer changes. whoever reads after this can tell.
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+
+        let result = RegisterOutputBufferRequest { size }.send()?;
+
+        // address first. see Self::read.
+        ADDRESS.store(result.address, Ordering::Release);
+        SIZE.store(result.size, Ordering::Release);
+        STATE.store(REGISTERED, Ordering::Release);
+
+        Ok(result.address)
+    }
+
+    ///
+    /// # Ensure
+    ///
+    /// Registers the buffer with the defaults, unless it already is.
+    ///
+    /// Must be called before sending requests with variable-length results. The wrappers here do that for you.
+    /// Raw requests, e.g. in [`HxBatch`](crate::services::batch::HxBatch), don't.
+    ///
+    pub fn ensure() -> Result<(), HxError> {
+        Self::get().map(|_| ())
+    }
+
+    ///
+    /// # Header
+    ///
+    /// Gets the header of the buffer, registering it first if needed.
+    ///
+    pub fn header() -> Result<OutputBufferHeader, HxError> {
+        let (address, _) = Self::get()?;
+        Ok(unsafe { (address as *const OutputBufferHeader).read_volatile() })
+    }
+
+    ///
+    /// # Read
+    ///
+    /// Copies a result out of the buffer.
+    ///
+    /// ## Remarks
+    /// - Read results of raw requests right away. A call from another thread that doesn't fit empties the buffer.
+    ///
+    /// ## Return
+    /// * [`Vec<T>`] - The result.
+    /// * [`HxError::InvalidParameters`] - `slice` is not in the buffer, or its length is not a multiple of `T`.
+    pub fn read<T: Copy>(slice: OutputSlice) -> Result<Vec<T>, HxError> {
+        // size first. buffers only get bigger, so a size never belongs to a smaller buffer than the address.
+        let size = SIZE.load(Ordering::Acquire);
+        let address = ADDRESS.load(Ordering::Acquire);
+
+        // never trust the other side with bounds.
+        if !slice.is_within(size) || !(slice.length() as usize).is_multiple_of(size_of::<T>()) {
+            return Err(HxError::InvalidParameters(0));
+        }
+
+        let count = slice.length() as usize / size_of::<T>();
+        let source = (address + slice.offset() as u64) as *const T;
+
+        Ok((0..count)
+            .map(|i| unsafe { source.add(i).read_unaligned() })
+            .collect())
+    }
+
+    ///
+    /// # Read UTF-16
+    ///
+    /// Same as [`Self::read`], decoding the result as a UTF-16 string.
+    ///
+    /// ## Return
+    /// * [`String`] - The string.
+    /// * [`HxError::InvalidParameters`] - Out of bounds, or not valid UTF-16.
+    pub fn read_utf16(slice: OutputSlice) -> Result<String, HxError> {
+        String::from_utf16(&Self::read::<u16>(slice)?).map_err(|_| HxError::InvalidParameters(0))
+    }
+
+    ///
+    /// # Send
+    ///
+    /// Sends a request with a variable-length result, and copies the result out with `read`.
+    ///
+    /// ## Remarks
+    /// - If the result does not fit, the buffer is emptied and the request sent again. Only if it doesn't fit an empty buffer either, it grows.
+    /// - If another thread emptied or replaced the buffer before `read` was done, the request is sent again.
+    ///
+    /// ## Return
+    /// * `R` - What `read` made of the response.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`]. Did not fit the bigger buffer either.
+    pub(crate) fn send<T: SyscallRequest + Clone, R>(
+        request: T,
+        read: impl Fn(T::Response) -> Result<R, HxError>,
+    ) -> Result<R, HxError> {
+        let mut attempt = 0;
+        loop {
+            Self::ensure()?;
+            let generation = GENERATION.load(Ordering::SeqCst);
+            let response = request.clone().send();
+
+            if let Some(x) = Self::settle(generation, &mut attempt, response, &read)? {
+                return Ok(x);
+            }
+        }
+    }
+
+    ///
+    /// # Send Async
+    ///
+    /// Same as [`Self::send`], as a slow call.
+    ///
+    pub(crate) async fn send_async<T: SyscallRequest + Clone, R>(
+        request: T,
+        read: impl Fn(T::Response) -> Result<R, HxError>,
+    ) -> Result<R, HxError> {
+        let mut attempt = 0;
+        loop {
+            Self::ensure()?;
+            let generation = GENERATION.load(Ordering::SeqCst);
+            let response = request.clone().send_async()?.await;
+
+            if let Some(x) = Self::settle(generation, &mut attempt, response, &read)? {
+                return Ok(x);
+            }
+        }
+    }
+
+    // [`None`] is to send again. first time it didn't fit empties the buffer, second time grows it.
+    fn settle<S, R>(
+        generation: u64,
+        attempt: &mut u8,
+        response: Result<S, HxError>,
+        read: impl Fn(S) -> Result<R, HxError>,
+    ) -> Result<Option<R>, HxError> {
+        let response = match response {
+            Err(HxError::NotAllowed(NotAllowedReason::BufferTooSmall)) if *attempt < 2 => {
+                let size = SIZE.load(Ordering::Acquire);
+                Self::register(match *attempt {
+                    0 => size,
+                    _ => size.saturating_mul(2).clamp(Self::DEFAULT_SIZE, Self::MAX_SIZE),
+                })?;
+                *attempt += 1;
+                return Ok(None);
+            }
+            x => x?,
+        };
+
+        let result = read(response);
+
+        // someone registered in between. what we read may not be ours, errors included.
+        match GENERATION.load(Ordering::SeqCst) == generation {
+            true => result.map(Some),
+            false => Ok(None),
+        }
+    }
+
+    fn get() -> Result<(u64, u32), HxError> {
+        loop {
+            match STATE.compare_exchange(
+                UNREGISTERED,
+                REGISTERING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    if let Err(e) = Self::register(Self::DEFAULT_SIZE) {
+                        STATE.store(UNREGISTERED, Ordering::Release);
+                        return Err(e);
+                    }
+                }
+                Err(REGISTERING) => spin_loop(),
+                Err(_) => {
+                    return Ok((
+                        ADDRESS.load(Ordering::Acquire),
+                        SIZE.load(Ordering::Acquire),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+///
+/// # Table Entries
+///
+/// Some results are a table. `count` entries, followed by the UTF-16 strings they point into.
+/// Gets the entries out of one, read with [`HxOutputBuffer::read`].
+///
+/// ## Return
+/// * [`Vec<T>`] - Entries.
+/// * [`HxError::InvalidParameters`] - `blob` is too short for `count` of them.
+pub(crate) fn table_entries<T: Copy>(blob: &[u8], count: u32) -> Result<Vec<T>, HxError> {
+    if count as usize * size_of::<T>() > blob.len() {
+        return Err(HxError::InvalidParameters(0));
+    }
+
+    let entries = blob.as_ptr() as *const T;
+    Ok((0..count as usize)
+        .map(|i| unsafe { entries.add(i).read_unaligned() })
+        .collect())
+}
+
+///
+/// # Table String
+///
+/// Gets a string an entry of a table points to. See [`table_entries`].
+///
+/// ## Arguments
+/// * `slice` - Where the string is. Relative to the start of the table, not the buffer.
+///
+/// ## Return
+/// * [`Some`] - The string.
+/// * [`None`] - `slice` is empty.
+/// * [`HxError::InvalidParameters`] - `slice` is out of `blob`, or splits a character.
+pub(crate) fn table_string(blob: &[u8], slice: OutputSlice) -> Result<Option<String>, HxError> {
+    let offset = slice.offset() as usize;
+    let length = slice.length() as usize;
+
+    match blob.get(offset..offset + length) {
+        _ if length == 0 => Ok(None),
+        Some(bytes) if length.is_multiple_of(2) => {
+            let units = bytes
+                .chunks_exact(2)
+                .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                .collect::<Vec<_>>();
+            Ok(Some(String::from_utf16_lossy(&units)))
+        }
+        _ => Err(HxError::InvalidParameters(1)),
+    }
+}
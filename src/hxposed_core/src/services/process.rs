@@ -2,18 +2,35 @@
 #![allow(dead_code)]
 
 use crate::error::HxError;
+use crate::hxposed::error::{NotAllowedReason, NotFoundReason};
+use crate::hxposed::requests::handle::EnumerateHandlesRequest;
+use crate::hxposed::requests::memory::EnumerateMemoryRegionsRequest;
 use crate::hxposed::requests::process::*;
 use crate::hxposed::requests::Syscall;
 use crate::hxposed::responses::empty::EmptyResponse;
-use crate::hxposed::responses::process::GetProcessFieldResponse;
-use crate::hxposed::ObjectType;
+use crate::hxposed::utils::transaction::Transaction;
+use crate::hxposed::responses::handle::HandleEntry;
+use crate::hxposed::responses::process::{GetProcessFieldResponse, ModuleEntry, ProcessEntry};
+use crate::hxposed::output::OutputSlice;
+use crate::hxposed::{ObjectType, ProcessObject};
 use crate::intern::win::GetCurrentProcessId;
-use crate::services::memory::HxMemory;
+use crate::services::async_call::SyscallAsync;
+use crate::services::handle::{HxHandle, HxHandleInfo};
+use crate::services::memory::{HxMemory, HxMemoryRegion, HxMemoryRegions};
+use crate::services::minidump;
+use crate::services::minidump::{
+    Minidump, MinidumpMemory, MinidumpModule, MinidumpSystemInfo, MinidumpThread,
+};
+use crate::services::output::{HxOutputBuffer, table_entries, table_string};
 use crate::services::security::HxToken;
+use crate::services::thread::HxThread;
 use crate::services::types::process_fields::*;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
-use core::arch::asm;
+use core::hash::{Hash, Hasher};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
 
 #[derive(Debug)]
 pub struct HxProcess {
@@ -22,16 +39,253 @@ pub struct HxProcess {
     pub(crate) addr: u64,
 }
 
+///
+/// # HxProcess Node
+///
+/// A process and the processes it created. See [`HxProcess::tree`].
+///
+#[derive(Clone, Debug)]
+pub struct HxProcessNode {
+    pub entry: ProcessEntry,
+    pub children: Vec<HxProcessNode>,
+}
+
+///
+/// # HxEnvironment
+///
+/// Environment variables of a process. See [`HxProcess::environment`].
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HxEnvironment {
+    /// Names and values, in order.
+    pub variables: Vec<(String, String)>,
+    /// Block didn't fit in the output buffer. Variables past the cut are missing.
+    pub truncated: bool,
+}
+
+///
+/// # HxFrozen Process
+///
+/// A suspended process. See [`HxProcess::freeze`].
+///
+pub struct HxFrozenProcess<'a> {
+    process: &'a mut HxProcess,
+    previous_count: u32,
+}
+
+impl HxFrozenProcess<'_> {
+    ///
+    /// # Previous Count
+    ///
+    /// Highest suspend count its threads had before freezing. 0 means it was running. See [`HxProcess::suspend`].
+    ///
+    pub fn previous_count(&self) -> u32 {
+        self.previous_count
+    }
+
+    ///
+    /// # Thaw
+    ///
+    /// Resumes the process now, instead of on drop. For when you care whether it worked.
+    ///
+    /// ## Returns
+    /// * [`u32`] - See [`HxProcess::resume`].
+    /// * [`HxError::NtError`] - Resuming a thread failed. It stays suspended.
+    pub fn thaw(self) -> Result<u32, HxError> {
+        let this = ManuallyDrop::new(self);
+        this.process.resume()
+    }
+}
+
+impl Deref for HxFrozenProcess<'_> {
+    type Target = HxProcess;
+
+    fn deref(&self) -> &Self::Target {
+        self.process
+    }
+}
+
+impl DerefMut for HxFrozenProcess<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.process
+    }
+}
+
+impl Drop for HxFrozenProcess<'_> {
+    fn drop(&mut self) {
+        // nothing to do about it failing. process is probably gone.
+        let _ = self.process.resume();
+    }
+}
+
+///
+/// # HxProcess Snapshot
+///
+/// Security relevant state of a process. See [`HxProcess::snapshot`] and [`HxProcess::apply`].
+///
+/// ## Remarks
+/// - The primary token is kept open, so it's still there when the snapshot is applied.
+/// - Serialize it with [`Self::to_bytes`], e.g. to put a process back after a crash. The token is left out.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct HxProcessSnapshot {
+    pub protection: ProcessProtection,
+    pub signature_levels: ProcessSignatureLevels,
+    pub mitigations: MitigationOptions,
+    pub mitigations3: MitigationOptions3,
+    /// [`None`] leaves the token as it is when applied.
+    pub token: Option<HxSnapshotToken>,
+    pub directory_table_base: u64,
+    pub user_directory_table_base: u64,
+}
+
+///
+/// # HxSnapshot Token
+///
+/// Primary token of a [`HxProcessSnapshot`].
+///
+#[derive(Clone, Debug)]
+pub struct HxSnapshotToken {
+    /// Kernel address. Tells tokens apart. Not reused by another token while this one is open.
+    pub address: u64,
+    pub token: Arc<HxToken>,
+}
+
+impl PartialEq for HxSnapshotToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address
+    }
+}
+
+impl Eq for HxSnapshotToken {}
+
+impl Hash for HxSnapshotToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
+impl HxProcessSnapshot {
+    /// Size of the serialized snapshot.
+    pub const SIZE: usize = 31;
+
+    ///
+    /// # To Bytes
+    ///
+    /// Serializes the snapshot. Fields are little endian, in declaration order, without padding.
+    ///
+    /// ## Remarks
+    /// - Not the token. A kernel address means nothing once nothing holds it open.
+    ///
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0] = self.protection.into_bits();
+        bytes[1..3].copy_from_slice(&self.signature_levels.into_bits().to_le_bytes());
+        bytes[3..11].copy_from_slice(&self.mitigations.into_bits().to_le_bytes());
+        bytes[11..15].copy_from_slice(&self.mitigations3.into_bits().to_le_bytes());
+        bytes[15..23].copy_from_slice(&self.directory_table_base.to_le_bytes());
+        bytes[23..31].copy_from_slice(&self.user_directory_table_base.to_le_bytes());
+        bytes
+    }
+
+    ///
+    /// # From Bytes
+    ///
+    /// Deserializes a snapshot written by [`Self::to_bytes`]. Without a token, so applying it leaves the token alone.
+    ///
+    /// ## Return
+    /// * [`None`] - `bytes` is not [`Self::SIZE`] long.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes: &[u8; Self::SIZE] = bytes.try_into().ok()?;
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+
+        Some(Self {
+            protection: ProcessProtection::from_bits(bytes[0]),
+            signature_levels: ProcessSignatureLevels::from_bits(u16::from_le_bytes([
+                bytes[1], bytes[2],
+            ])),
+            mitigations: MitigationOptions::from_bits(u64_at(3)),
+            mitigations3: MitigationOptions3::from_bits(u32::from_le_bytes(
+                bytes[11..15].try_into().unwrap(),
+            )),
+            token: None,
+            directory_table_base: u64_at(15),
+            user_directory_table_base: u64_at(23),
+        })
+    }
+}
+
+///
+/// # HxModule
+///
+/// A module loaded into a process. See [`HxProcess::modules`].
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HxModule {
+    pub base: u64,
+    pub size: u32,
+    /// 0 if it has none.
+    pub entry_point: u64,
+    /// Full path, as the loader has it. Usually a DOS path.
+    pub path: String,
+    /// Loaded by the 32-bit loader of a WoW64 process.
+    pub wow64: bool,
+}
+
+impl HxModule {
+    pub fn contains(&self, address: u64) -> bool {
+        (self.base..self.base + self.size as u64).contains(&address)
+    }
+}
+
 impl Drop for HxProcess {
     fn drop(&mut self) {
-        let _ = CloseProcessRequest {
+        CloseProcessRequest {
             process: self.addr,
         }
-        .send();
+        .send_forget();
     }
 }
 
 impl HxProcess {
+    ///
+    /// # Object
+    ///
+    /// Gets the object this process is tracked by. Use it for raw requests, e.g. in [`HxBatch`](crate::services::batch::HxBatch).
+    ///
+    pub fn object(&self) -> ProcessObject {
+        self.addr
+    }
+
+    ///
+    /// # To Handle
+    ///
+    /// Opens a real handle to the process, in your handle table.
+    ///
+    /// ## Arguments
+    /// * `access_rights` - `PROCESS_*` rights the handle gets. Not checked against anything.
+    ///
+    /// ## Remarks
+    /// - Protected processes included. The handle is not stripped, like ones from `OpenProcess` would be.
+    /// - The handle is yours. It outlives this [`HxProcess`]. Close it with `CloseHandle`.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::HANDLE_MANAGE`]
+    ///
+    /// ## Returns
+    /// * [`HxHandle`] - The new handle.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// let lsass = HxProcess::open(lsass_id).unwrap();
+    /// let handle = lsass.to_handle(HxHandle::HANDLE_ALL_ACCESS).unwrap();
+    /// ReadProcessMemory(handle.handle, address, buffer, size, null_mut());
+    /// ```
+    pub fn to_handle(&self, access_rights: u32) -> Result<HxHandle, HxError> {
+        HxHandle::create(ObjectType::Process(self.addr), access_rights)
+    }
+
     pub fn system() -> Self {
         Self::open(4).unwrap()
     }
@@ -50,7 +304,7 @@ impl HxProcess {
     /// Gets `Token` field of `_EPROCESS` structure.
     ///
     /// ## Permissions
-    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
     /// * [`PluginPermissions::SECURITY_MANAGE`]
     ///
     /// ## Returns
@@ -74,7 +328,7 @@ impl HxProcess {
     /// Swaps the primary token of the process.
     ///
     /// ## Warning
-    /// - This happens while the process is RUNNING.
+    /// - This happens while the process is RUNNING. Unless you [`Self::freeze`] it first.
     /// - The results can be disastrous.
     /// - This isn't supported in any way.
     /// - You have been warned.
@@ -123,7 +377,7 @@ impl HxProcess {
     /// Sets the internal `MitigationFlags1` and `MitigationFlags2` fields of `_EPROCESS`.
     ///
     /// ## Permissions
-    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
     ///
     pub fn set_mitigation_options(
         &self,
@@ -159,6 +413,122 @@ impl HxProcess {
         }
     }
 
+    ///
+    /// # Update Mitigation Options
+    ///
+    /// Reads the mitigation options, lets `update` change them and writes them back.
+    ///
+    /// ## Arguments
+    /// * `update` - Changes the options. Use the grouped setters, e.g. [`MitigationOptions::set_dynamic_code`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    ///
+    /// ## Remarks
+    /// - Not atomic. Something else changing the flags in between is overwritten.
+    ///
+    /// ## Return
+    /// * [`MitigationDiff`] - What changed. Nothing is written if it is empty.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let diff = process.update_mitigation_options(|x| {
+    ///     x.set_dynamic_code(DynamicCode {
+    ///         disabled: true,
+    ///         ..x.dynamic_code()
+    ///     })
+    /// })?;
+    ///
+    /// println!("mitigations of {}: {}", process.id, diff);
+    /// ```
+    pub fn update_mitigation_options(
+        &self,
+        update: impl FnOnce(&mut MitigationOptions),
+    ) -> Result<MitigationDiff, HxError> {
+        let old = self.get_mitigation_options()?;
+        let mut new = old;
+        update(&mut new);
+
+        let diff = old.diff(&new);
+        if !diff.is_empty() {
+            self.set_mitigation_options(new)?;
+        }
+
+        Ok(diff)
+    }
+
+    ///
+    /// # Get Mitigation Policies
+    ///
+    /// Same as [`Self::get_mitigation_options`], grouped by policy.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`ProcessMitigationOptions`] - Grouped mitigation flags.
+    pub fn get_mitigation_policies(&self) -> Result<ProcessMitigationOptions, HxError> {
+        Ok(self.get_mitigation_options()?.into())
+    }
+
+    ///
+    /// # Set Mitigation Policies
+    ///
+    /// Same as [`Self::set_mitigation_options`], grouped by policy.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    ///
+    pub fn set_mitigation_policies(
+        &self,
+        policies: ProcessMitigationOptions,
+    ) -> Result<EmptyResponse, HxError> {
+        self.set_mitigation_options(policies.into())
+    }
+
+    ///
+    /// # Set Mitigation Options 3
+    ///
+    /// Sets the internal `MitigationFlags3` field of `_EPROCESS`.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    ///
+    pub fn set_mitigation_options3(
+        &self,
+        options: MitigationOptions3,
+    ) -> Result<EmptyResponse, HxError> {
+        SetProcessFieldRequest {
+            process: self.addr,
+            field: ProcessField::MitigationFlags3(options),
+        }
+        .send()
+    }
+
+    ///
+    /// # Get Mitigation Options 3
+    ///
+    /// Gets the internal `MitigationFlags3` field of `_EPROCESS`.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`MitigationOptions3`] - Contains the mitigation flags.
+    pub fn get_mitigation_options3(&self) -> Result<MitigationOptions3, HxError> {
+        let result = GetProcessFieldRequest {
+            process: self.addr,
+            field: ProcessField::MitigationFlags3(MitigationOptions3::default()),
+        }
+        .send()?;
+
+        match result.field {
+            ProcessField::MitigationFlags3(x) => Ok(x),
+            _ => unreachable!(),
+        }
+    }
+
     ///
     /// # Get Threads
     ///
@@ -172,22 +542,18 @@ impl HxProcess {
     ///
     /// ## Returns
     /// * [`Vec<u32>`] - Vector containing the ids of threads under specified process.
-    pub fn get_threads(&self) -> Result<&[u32], HxError> {
-        match (GetProcessFieldRequest {
-            process: self.addr,
-            field: ProcessField::Threads(0),
-        })
-        .send()?.field
-        {
-            ProcessField::Threads(offset) => unsafe {
-                let length = *((0x20090000 + offset) as *const usize);
-                Ok(core::slice::from_raw_parts(
-                    (0x20090000usize + (offset as usize) + size_of::<usize>()) as *const u32,
-                    length,
-                ))
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many threads for the output buffer. See [`HxOutputBuffer`].
+    pub fn get_threads(&self) -> Result<Vec<u32>, HxError> {
+        HxOutputBuffer::send(
+            GetProcessFieldRequest {
+                process: self.addr,
+                field: ProcessField::Threads(OutputSlice::new()),
             },
-            _ => unreachable!(),
-        }
+            |response| match response.field {
+                ProcessField::Threads(slice) => HxOutputBuffer::read(slice),
+                _ => unreachable!(),
+            },
+        )
     }
 
     ///
@@ -199,7 +565,7 @@ impl HxProcess {
     /// * `id` - Process id
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
     ///
     /// ## Returns
     /// * [`Result`] containing [`HxProcess`] or error.
@@ -218,19 +584,625 @@ impl HxProcess {
         Ok(Self {
             id,
             memory: HxMemory {
-                process: call.object.into_raw().1,
+                process: call.object.into_raw_enum().1,
             },
-            addr: call.object.into_raw().1,
+            addr: call.object.into_raw_enum().1,
         })
     }
 
+    ///
+    /// # Enumerate
+    ///
+    /// Walks the active process list of the system.
+    ///
+    /// ## Remarks
+    /// - This is a snapshot. Processes come and go while you iterate.
+    /// - Use [`Self::open`] with [`ProcessEntry::id`] to do anything with one.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`Iterator`] over [`ProcessEntry`].
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many processes for the output buffer. See [`HxOutputBuffer`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// for process in HxProcess::enumerate().await.unwrap() {
+    ///     println!("{} {}", process.id, process.name());
+    /// }
+    /// ```
+    pub async fn enumerate() -> Result<impl Iterator<Item = ProcessEntry>, HxError> {
+        let processes = HxOutputBuffer::send_async(EnumerateProcessesRequest, |result| {
+            HxOutputBuffer::read::<ProcessEntry>(result.processes)
+        })
+        .await?;
+        Ok(processes.into_iter())
+    }
+
+    ///
+    /// # Memory Regions
+    ///
+    /// Walks the VAD tree of the process.
+    ///
+    /// ## Remarks
+    /// - This is a snapshot. Regions come and go while you iterate.
+    /// - Only reserved regions are here. Free space between them isn't.
+    /// - A VAD tree deeper than any balanced one can be is changing under the walk. What's below the cut is left out. See [`HxMemoryRegions::truncated`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Returns
+    /// * [`HxMemoryRegions`] - [`HxMemoryRegion`]s, in address order.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many regions for the output buffer. See [`HxOutputBuffer`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// for region in process.memory_regions().await.unwrap().regions {
+    ///     println!("{:#x} {:?}", region.base_address(), region.file_name);
+    /// }
+    /// ```
+    pub async fn memory_regions(&self) -> Result<HxMemoryRegions, HxError> {
+        let (result, blob) = HxOutputBuffer::send_async(
+            EnumerateMemoryRegionsRequest {
+                addr_space: self.addr,
+            },
+            |result| {
+                let blob = HxOutputBuffer::read::<u8>(result.regions)?;
+                Ok((result, blob))
+            },
+        )
+        .await?;
+
+        Ok(HxMemoryRegions {
+            regions: HxMemoryRegion::from_response(&result, &blob)?,
+            truncated: result.truncated,
+        })
+    }
+
+    ///
+    /// # Modules
+    ///
+    /// Walks the loader's module list in the PEB of the process. For WoW64 processes, the 32-bit PEB's too.
+    ///
+    /// ## Remarks
+    /// - This is a snapshot. Modules come and go while you iterate.
+    /// - The list lives in user memory. The process can lie in it, or break it. A broken list just ends early.
+    /// - Native modules come first, then the 32-bit ones. Each in load order.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Returns
+    /// * [`Iterator`] over [`HxModule`].
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many modules for the output buffer. See [`HxOutputBuffer`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// let rip = 0x7FFA_1234_5678;
+    /// match process.modules().await.unwrap().find(|x| x.contains(rip)) {
+    ///     Some(module) => println!("{}+{:#x}", module.path, rip - module.base),
+    ///     None => println!("{:#x}", rip),
+    /// }
+    /// ```
+    pub async fn modules(&self) -> Result<impl Iterator<Item = HxModule>, HxError> {
+        let (result, blob) =
+            HxOutputBuffer::send_async(EnumerateModulesRequest { process: self.addr }, |result| {
+                let blob = HxOutputBuffer::read::<u8>(result.modules)?;
+                Ok((result, blob))
+            })
+            .await?;
+
+        Ok(table_entries::<ModuleEntry>(&blob, result.count)?
+            .into_iter()
+            .map(|entry| {
+                Ok(HxModule {
+                    base: entry.base,
+                    size: entry.size,
+                    entry_point: entry.entry_point,
+                    path: table_string(&blob, entry.path)?.unwrap_or_default(),
+                    wow64: entry.wow64 != 0,
+                })
+            })
+            .collect::<Result<Vec<_>, HxError>>()?
+            .into_iter())
+    }
+
+    ///
+    /// # Handles
+    ///
+    /// Walks the handle table of the process.
+    ///
+    /// ## Remarks
+    /// - This is a snapshot. Handles come and go while you iterate.
+    /// - Names are best effort. Objects without names, and files the driver won't block on, have [`None`].
+    /// - Handles are not yours. To use one, open the object yourself, or swap it into one of your handles. See [`HxHandle`](crate::services::handle::HxHandle).
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::HANDLE_MANAGE`]
+    ///
+    /// ## Returns
+    /// * [`Iterator`] over [`HxHandleInfo`], in handle order.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many handles for the output buffer. See [`HxOutputBuffer`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// for handle in process.handles().await.unwrap().filter(|x| x.type_name == "Key") {
+    ///     println!("{:#x} {:?}", handle.handle, handle.name);
+    /// }
+    /// ```
+    pub async fn handles(&self) -> Result<impl Iterator<Item = HxHandleInfo>, HxError> {
+        let (result, blob) =
+            HxOutputBuffer::send_async(EnumerateHandlesRequest { process: self.addr }, |result| {
+                let blob = HxOutputBuffer::read::<u8>(result.handles)?;
+                Ok((result, blob))
+            })
+            .await?;
+
+        Ok(table_entries::<HandleEntry>(&blob, result.count)?
+            .into_iter()
+            .map(|entry| {
+                Ok(HxHandleInfo {
+                    handle: entry.handle,
+                    object: entry.object,
+                    type_name: table_string(&blob, entry.type_name)?.unwrap_or_default(),
+                    name: table_string(&blob, entry.name)?,
+                    granted_access: entry.granted_access,
+                    attributes: entry.attributes,
+                })
+            })
+            .collect::<Result<Vec<_>, HxError>>()?
+            .into_iter())
+    }
+
+    ///
+    /// # Minidump
+    ///
+    /// Collects a full memory dump of the process. Protected processes included.
+    ///
+    /// ## Remarks
+    /// - The process is frozen for the whole dump, so memory and registers are from the same moment. See [`Self::freeze`].
+    /// - NT keeps suspend counts per thread, freezing only tells the highest. Every thread gets that one.
+    /// - Threads killed from elsewhere meanwhile are left out.
+    /// - Regions with neither committed pages nor a backing file are left out. Unreadable pages are skipped.
+    /// - So are regions the VAD walk gave up on. See [`HxMemoryRegions::truncated`].
+    /// - TEBs are not known. They are 0.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::THREAD_EXECUTIVE`]
+    /// * [`PluginPermissions::MEMORY_VIRTUAL`]
+    ///
+    /// ## Returns
+    /// * [`Minidump`] - Write it with [`Minidump::to_bytes`].
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::BufferTooSmall`](crate::hxposed::error::NotAllowedReason::BufferTooSmall). Too many threads, modules or regions for the output buffer. See [`HxOutputBuffer`].
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::CurrentProcess`]. Can't freeze it.
+    /// * [`HxError::NotFound`] - Freezing is not available. See [`Self::suspend`].
+    /// * [`HxError::NtError`] - Freezing, taking registers, or resuming failed. Resuming is reported over anything else.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// let dump = process.minidump().await?;
+    /// std::fs::write("lsass.dmp", dump.to_bytes())?;
+    /// ```
+    pub async fn minidump(&mut self) -> Result<Minidump, HxError> {
+        let frozen = self.freeze()?;
+        let dump = frozen.capture_minidump(frozen.previous_count()).await;
+
+        // a process left suspended is worse than a dump that failed.
+        frozen.thaw()?;
+        dump
+    }
+
+    async fn capture_minidump(&self, suspend_count: u32) -> Result<Minidump, HxError> {
+        // threads come and go. only these mean it's gone, anything else is a real failure.
+        fn gone(error: &HxError) -> bool {
+            const STATUS_THREAD_IS_TERMINATING: u32 = 0xC000004B;
+            matches!(
+                error,
+                HxError::NotFound(NotFoundReason::Thread)
+                    | HxError::NtError(STATUS_THREAD_IS_TERMINATING)
+            )
+        }
+
+        let regions = self.memory_regions().await?.regions;
+        let mut threads = Vec::new();
+
+        for id in self.get_threads()? {
+            let thread = match HxThread::open(id) {
+                Ok(x) => x,
+                Err(e) if gone(&e) => continue,
+                Err(e) => return Err(e),
+            };
+
+            let context = match thread.get_context().await {
+                Ok(x) => x,
+                Err(e) if gone(&e) => continue,
+                Err(e) => return Err(e),
+            };
+
+            // from rsp up to where the stack begins.
+            let stack = match regions.iter().find(|x| x.contains(context.rsp)) {
+                Some(region) => minidump::read_readable(
+                    &self.memory,
+                    context.rsp,
+                    region.base_address() + region.size() - context.rsp,
+                )?
+                .into_iter()
+                .next()
+                .unwrap_or_default(),
+                None => MinidumpMemory::default(),
+            };
+
+            threads.push(MinidumpThread {
+                id,
+                suspend_count,
+                priority_class: 0,
+                priority: 0,
+                teb: 0,
+                context,
+                stack,
+            });
+        }
+
+        let modules = self
+            .modules()
+            .await?
+            .map(|module| {
+                // IMAGE_DOS_HEADER.e_lfanew, then IMAGE_NT_HEADERS64. best effort, headers can be paged out.
+                let headers = self.memory.read::<u32>(module.base + 0x3C).ok();
+                let read = |offset: u64| {
+                    headers
+                        .and_then(|x| self.memory.read::<u32>(module.base + x as u64 + offset).ok())
+                        .unwrap_or(0)
+                };
+
+                MinidumpModule {
+                    base: module.base,
+                    size: module.size,
+                    checksum: read(0x58),
+                    timestamp: read(0x8),
+                    name: module.path,
+                }
+            })
+            .collect();
+
+        let mut memory = Vec::new();
+        for region in regions
+            .iter()
+            .filter(|x| x.commit_charge != 0 || x.file_name.is_some())
+        {
+            memory.extend(minidump::read_readable(
+                &self.memory,
+                region.base_address(),
+                region.size(),
+            )?);
+        }
+
+        Ok(Minidump {
+            system_info: MinidumpSystemInfo::current(),
+            threads,
+            modules,
+            memory,
+            timestamp: 0,
+        })
+    }
+
+    ///
+    /// # Tree
+    ///
+    /// Same as [`Self::enumerate`], arranged by parent.
+    ///
+    /// ## Remarks
+    /// - Processes whose parent is gone are roots. So is `System`.
+    /// - A process is only a child of an id if it was created after it. Ids get reused.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`Vec<HxProcessNode>`] - Roots, ordered by creation time.
+    pub async fn tree() -> Result<Vec<HxProcessNode>, HxError> {
+        let mut entries = Self::enumerate().await?.collect::<Vec<_>>();
+        entries.sort_by_key(|x| x.create_time);
+
+        fn children(entries: &[ProcessEntry], parent: &ProcessEntry) -> Vec<HxProcessNode> {
+            entries
+                .iter()
+                .filter(|x| parent.is_parent_of(x))
+                .map(|x| HxProcessNode {
+                    entry: *x,
+                    children: children(entries, x),
+                })
+                .collect()
+        }
+
+        Ok(entries
+            .iter()
+            .filter(|x| !entries.iter().any(|parent| parent.is_parent_of(x)))
+            .map(|x| HxProcessNode {
+                entry: *x,
+                children: children(&entries, x),
+            })
+            .collect())
+    }
+
+    ///
+    /// # Kill
+    ///
+    /// Terminates the process.
+    ///
+    /// ## Arguments
+    /// * `exit_code` - NT status the process exits with. E.g. `0xC000013A` for `STATUS_CONTROL_C_EXIT`.
+    ///
+    /// ## Remarks
+    /// - Works on protected processes too. No need to [`Self::set_protection`] first.
+    /// - The process stays open. Close it by dropping this.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    ///
+    /// ## Returns
+    /// * [`EmptyResponse`] - Process is terminating.
+    /// * [`HxError::NtError`] - `PsTerminateProcess` failed. E.g. `STATUS_PROCESS_IS_TERMINATING`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// process.kill(0).await.unwrap();
+    /// ```
+    pub async fn kill(&self, exit_code: u32) -> Result<EmptyResponse, HxError> {
+        KillProcessRequest {
+            process: self.addr,
+            exit_code,
+        }
+        .send_async()?
+        .await
+    }
+
+    ///
+    /// # Suspend
+    ///
+    /// Suspends every thread of the process. Works on protected processes too.
+    ///
+    /// ## Remarks
+    /// - NT keeps suspend counts per thread. Use [`HxThread::suspend`](crate::services::thread::HxThread::suspend) for one of them.
+    /// - Suspensions stack. It runs again after as many [`Self::resume`]s.
+    /// - Not the current process. The calling thread would never come back.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`u32`] - Highest suspend count its threads had before. 0 means it was running.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::CurrentProcess`].
+    /// * [`HxError::NtError`] - Suspending a thread failed. The ones before it are resumed.
+    /// * [`HxError::NotFound`] - [`NotFoundReason::ServiceFunction`](crate::hxposed::error::NotFoundReason::ServiceFunction). Not available on this Windows build. See [`HxPosed::require`](crate::services::status::HxPosed::require).
+    pub fn suspend(&self) -> Result<u32, HxError> {
+        Ok(SuspendProcessRequest { process: self.addr }
+            .send()?
+            .previous_count)
+    }
+
+    ///
+    /// # Resume
+    ///
+    /// Undoes one [`Self::suspend`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`u32`] - Highest suspend count its threads had before.
+    /// * [`HxError::NtError`] - Resuming a thread failed.
+    pub fn resume(&self) -> Result<u32, HxError> {
+        Ok(ResumeProcessRequest { process: self.addr }
+            .send()?
+            .previous_count)
+    }
+
+    ///
+    /// # Freeze
+    ///
+    /// Suspends the process until the returned guard is dropped.
+    ///
+    /// The guard derefs to the process, so edits can be made through it while nothing runs.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`HxFrozenProcess`] - Guard. Resumes the process on drop.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::CurrentProcess`]. It's the current process.
+    /// * [`HxError::NtError`] - See [`Self::suspend`].
+    /// * [`HxError::NotFound`] - See [`Self::suspend`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// let mut frozen = process.freeze().unwrap();
+    /// frozen.set_protection(ProcessProtection::new()).unwrap();
+    /// frozen.set_signature_levels(ProcessSignatureLevels::new()).unwrap();
+    /// // resumed here.
+    /// ```
+    pub fn freeze(&mut self) -> Result<HxFrozenProcess<'_>, HxError> {
+        if self.id == unsafe { GetCurrentProcessId() } {
+            return Err(HxError::NotAllowed(NotAllowedReason::CurrentProcess));
+        }
+
+        let previous_count = self.suspend()?;
+        Ok(HxFrozenProcess {
+            process: self,
+            previous_count,
+        })
+    }
+
+    ///
+    /// # Snapshot
+    ///
+    /// Captures protection, signature levels, mitigation flags, primary token and directory bases of the process.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::SECURITY_MANAGE`] - The token is opened.
+    ///
+    /// ## Returns
+    /// * [`HxProcessSnapshot`] - Put it back with [`Self::apply`].
+    pub fn snapshot(&self) -> Result<HxProcessSnapshot, HxError> {
+        let token = match (GetProcessFieldRequest {
+            process: self.addr,
+            field: ProcessField::Token(0),
+        }
+        .send()?)
+        .field
+        {
+            ProcessField::Token(addr) => HxSnapshotToken {
+                address: addr,
+                token: Arc::new(HxToken::from_raw_object(addr)?),
+            },
+            _ => unreachable!(),
+        };
+
+        Ok(HxProcessSnapshot {
+            protection: self.get_protection()?,
+            signature_levels: self.get_signature_levels()?,
+            mitigations: self.get_mitigation_options()?,
+            mitigations3: self.get_mitigation_options3()?,
+            token: Some(token),
+            directory_table_base: self.get_directory_base()?,
+            user_directory_table_base: self.get_user_directory_base()?,
+        })
+    }
+
+    ///
+    /// # Apply
+    ///
+    /// Writes the fields of `snapshot` that differ from the current state of the process.
+    /// All or nothing: if writing any of them fails, the ones already written are put back.
+    ///
+    /// ## Arguments
+    /// * `snapshot` - State to put the process in. Usually from [`Self::snapshot`].
+    ///
+    /// ## Warning
+    /// - Rolling back is best effort. If that fails too, nothing more can be done.
+    /// - Changing directory bases or the token of a running process is as dangerous as it sounds. [`Self::freeze`] it first.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
+    /// * [`PluginPermissions::SECURITY_MANAGE`] - Only if the token differs.
+    ///
+    /// ## Returns
+    /// * Nothing - Process is now in the state of `snapshot`.
+    /// * [`HxError`] - Of the write that failed. Process is back to where it was.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let snapshot = process.snapshot()?;
+    /// process.set_protection(ProcessProtection::new())?;
+    ///
+    /// // do your thing with the unprotected process.
+    ///
+    /// process.apply(&snapshot)?;
+    /// ```
+    pub fn apply(&self, snapshot: &HxProcessSnapshot) -> Result<(), HxError> {
+        let current = self.snapshot()?;
+        let mut fields = Vec::new();
+
+        if current.mitigations != snapshot.mitigations {
+            fields.push((
+                ProcessField::MitigationFlags(current.mitigations),
+                ProcessField::MitigationFlags(snapshot.mitigations),
+            ));
+        }
+        if current.mitigations3 != snapshot.mitigations3 {
+            fields.push((
+                ProcessField::MitigationFlags3(current.mitigations3),
+                ProcessField::MitigationFlags3(snapshot.mitigations3),
+            ));
+        }
+        if current.signature_levels != snapshot.signature_levels {
+            fields.push((
+                ProcessField::Signers(current.signature_levels),
+                ProcessField::Signers(snapshot.signature_levels),
+            ));
+        }
+        if current.protection != snapshot.protection {
+            fields.push((
+                ProcessField::Protection(current.protection),
+                ProcessField::Protection(snapshot.protection),
+            ));
+        }
+        if current.directory_table_base != snapshot.directory_table_base {
+            fields.push((
+                ProcessField::DirectoryTableBase(current.directory_table_base),
+                ProcessField::DirectoryTableBase(snapshot.directory_table_base),
+            ));
+        }
+        if current.user_directory_table_base != snapshot.user_directory_table_base {
+            fields.push((
+                ProcessField::UserDirectoryTableBase(current.user_directory_table_base),
+                ProcessField::UserDirectoryTableBase(snapshot.user_directory_table_base),
+            ));
+        }
+
+        // both are open already. held by the snapshots, so neither is gone, and the old one stays for the rollback.
+        let tokens = match (&current.token, &snapshot.token) {
+            (Some(old), Some(new)) if old != new => Some((old.token.clone(), new.token.clone())),
+            _ => None,
+        };
+
+        let mut tx = Transaction::new();
+        let process = self.addr;
+
+        for (old, new) in fields {
+            SetProcessFieldRequest {
+                process,
+                field: new,
+            }
+            .send()?;
+
+            tx.enlist(move || {
+                let _ = SetProcessFieldRequest { process, field: old }.send();
+            });
+        }
+
+        if let Some((old, new)) = tokens {
+            SetProcessFieldRequest {
+                process,
+                field: ProcessField::Token(new.addr),
+            }
+            .send()?;
+
+            // keeps the old token open until the transaction is done.
+            tx.enlist(move || {
+                let _ = SetProcessFieldRequest {
+                    process,
+                    field: ProcessField::Token(old.addr),
+                }
+                .send();
+            });
+        }
+
+        tx.commit();
+        Ok(())
+    }
+
     ///
     /// # Set Protection
     ///
     /// Sets the internal process protection object. The `_PS_PROTECTION`.
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
     ///
     /// ## Returns
     /// * [`EmptyResponse`] - Empty. You can use [`Self::get_protection`] to check the operation if you have anxiety problems.
@@ -265,7 +1237,7 @@ impl HxProcess {
     /// Sets the internal process protection object. The `SignatureLevel` and `SectionSignatureLevel`.
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_SECURITY`]
     ///
     /// ## Returns
     /// * [`EmptyResponse`] - Empty.
@@ -310,7 +1282,7 @@ impl HxProcess {
     /// - Issue a bug report if you observe a panic.
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
     ///
     /// ## Returns
     /// * [`ProcessSignatureLevels`] - Signature levels (both `SignatureLevel` and `SectionSignatureLevel`)
@@ -344,7 +1316,7 @@ impl HxProcess {
     /// - Issue a bug report if you observe a panic.
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
     ///
     /// ## Returns
     /// * [`ProcessProtection`] - Full path of the process.
@@ -376,7 +1348,7 @@ impl HxProcess {
     /// E.g. it starts with (\\?\), not C:.
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::PROCESS_EXECUTIVE`]
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
     ///
     /// ## Panic
     /// - This function panics if hypervisor returns anything else than [`GetProcessFieldResponse::NtPath`]. Which it SHOULD NOT.
@@ -384,24 +1356,135 @@ impl HxProcess {
     ///
     /// ## Return
     /// * [`String`] - Full path of the process.
-    /// * [`HxError::not_found`] - Unable to decode string from UTF16.
+    /// * [`HxError::InvalidParameters`] - Unable to decode string from UTF16.
     pub fn get_nt_path(&self) -> Result<String, HxError> {
-        let mut vec = Vec::<u8>::with_capacity(1024);
-        match (GetProcessFieldRequest {
-            process: self.addr,
-            field: ProcessField::NtPath(vec.as_ptr() as _),
-        })
-        .send()?.field
-        {
-            ProcessField::NtPath(length) => {
-                unsafe {
-                    vec.set_len(length as usize);
+        HxOutputBuffer::send(
+            GetProcessFieldRequest {
+                process: self.addr,
+                field: ProcessField::NtPath(OutputSlice::new()),
+            },
+            |response| match response.field {
+                ProcessField::NtPath(slice) => HxOutputBuffer::read_utf16(slice),
+                _ => unreachable!(),
+            },
+        )
+    }
+
+    ///
+    /// # Command Line
+    ///
+    /// Gets the command line of the process, out of `PEB->ProcessParameters`.
+    ///
+    /// ## Remarks
+    /// - The process can change its own parameters. This is what they are now, not what it was started with.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`String`] - Command line. Empty for processes without a PEB, like `System`.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`](crate::hxposed::error::NotAllowedReason::AccessViolation). Parameters are unreadable.
+    pub fn command_line(&self) -> Result<String, HxError> {
+        self.get_parameter(ProcessField::CommandLine(OutputSlice::new()))
+    }
+
+    ///
+    /// # Current Directory
+    ///
+    /// Gets the current directory of the process. Same remarks as [`Self::command_line`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`String`] - DOS path, with a trailing backslash.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`](crate::hxposed::error::NotAllowedReason::AccessViolation). Parameters are unreadable.
+    pub fn current_directory(&self) -> Result<String, HxError> {
+        self.get_parameter(ProcessField::CurrentDirectory(OutputSlice::new()))
+    }
+
+    ///
+    /// # Window Title
+    ///
+    /// Gets the window title the process was started with. Same remarks as [`Self::command_line`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`String`] - Usually the image path, for processes started from a shortcut or console.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`](crate::hxposed::error::NotAllowedReason::AccessViolation). Parameters are unreadable.
+    pub fn window_title(&self) -> Result<String, HxError> {
+        self.get_parameter(ProcessField::WindowTitle(OutputSlice::new()))
+    }
+
+    ///
+    /// # Environment
+    ///
+    /// Gets the environment variables of the process. Same remarks as [`Self::command_line`].
+    ///
+    /// ## Remarks
+    /// - Order is kept, so are duplicates.
+    /// - Names may start with `=`. Those are per-drive current directories, like `=C:=C:\Windows`.
+    /// - Cut to what's left of the output buffer, instead of failing. See [`HxEnvironment::truncated`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::PROCESS_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`HxEnvironment`] - Names and values.
+    /// * [`HxError::NotAllowed`] - [`NotAllowedReason::AccessViolation`](crate::hxposed::error::NotAllowedReason::AccessViolation). Parameters are unreadable.
+    pub fn environment(&self) -> Result<HxEnvironment, HxError> {
+        let (mut block, truncated) = HxOutputBuffer::send(
+            GetProcessFieldRequest {
+                process: self.addr,
+                field: ProcessField::Environment(OutputSlice::new()),
+            },
+            |response| match response.field {
+                ProcessField::Environment(slice) => {
+                    Ok((HxOutputBuffer::read::<u16>(slice)?, response.truncated))
                 }
-                String::from_utf8(vec).map_err(|_| {
-                    HxError::InvalidParameters(0)
-                })
+                _ => unreachable!(),
             },
-            _ => unreachable!(),
+        )?;
+
+        // the last variable may be cut in half. drop what's after the last complete one.
+        if truncated {
+            let end = block.iter().rposition(|x| *x == 0).map_or(0, |x| x + 1);
+            block.truncate(end);
         }
+
+        let variables = block
+            .split(|x| *x == 0)
+            .take_while(|x| !x.is_empty())
+            .map(|x| {
+                let variable = String::from_utf16_lossy(x);
+                // skip the first character. the name never is empty, but it may be `=`.
+                match variable.char_indices().skip(1).find(|(_, c)| *c == '=') {
+                    Some((i, _)) => (variable[..i].into(), variable[i + 1..].into()),
+                    None => (variable, String::new()),
+                }
+            })
+            .collect();
+
+        Ok(HxEnvironment {
+            variables,
+            truncated,
+        })
+    }
+
+    fn get_parameter(&self, field: ProcessField) -> Result<String, HxError> {
+        HxOutputBuffer::send(
+            GetProcessFieldRequest {
+                process: self.addr,
+                field,
+            },
+            |response| match response.field {
+                ProcessField::CommandLine(slice)
+                | ProcessField::CurrentDirectory(slice)
+                | ProcessField::WindowTitle(slice) => HxOutputBuffer::read_utf16(slice),
+                _ => unreachable!(),
+            },
+        )
     }
 }
@@ -6,7 +6,10 @@ use crate::hxposed::requests::security::*;
 use crate::hxposed::requests::Syscall;
 use crate::hxposed::responses::empty::EmptyResponse;
 use crate::hxposed::responses::security::GetTokenFieldResponse;
+use crate::hxposed::output::OutputSlice;
 use crate::hxposed::{ObjectType, TokenObject};
+use crate::services::handle::HxHandle;
+use crate::services::output::HxOutputBuffer;
 use crate::services::types::security_fields::TokenPrivilege;
 use alloc::string::String;
 
@@ -17,15 +20,45 @@ pub struct HxToken {
 
 impl Drop for HxToken {
     fn drop(&mut self) {
-        let _ = CloseTokenRequest { token: self.addr }.send();
+        CloseTokenRequest { token: self.addr }.send_forget();
     }
 }
 
 impl HxToken {
-    pub(crate) fn from_raw_object(addr: TokenObject) -> Result<HxToken, HxError> {
-        OpenTokenRequest { token: addr }.send()?;
+    ///
+    /// # Object
+    ///
+    /// Gets the object this token is tracked by. Use it for raw requests, e.g. in [`HxBatch`](crate::services::batch::HxBatch).
+    ///
+    pub fn object(&self) -> TokenObject {
+        self.addr
+    }
 
-        Ok(Self { addr })
+    ///
+    /// # To Handle
+    ///
+    /// Opens a handle to the token in your handle table. Usable with `SetThreadToken`, `CreateProcessAsUser` and such.
+    ///
+    /// ## Arguments
+    /// * `access_rights` - `TOKEN_*` rights the handle gets.
+    ///
+    /// ## Remarks
+    /// - Close it with `CloseHandle`.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::HANDLE_MANAGE`]
+    ///
+    /// ## Returns
+    /// * [`HxHandle`] - The new handle.
+    pub fn to_handle(&self, access_rights: u32) -> Result<HxHandle, HxError> {
+        HxHandle::create(ObjectType::Token(self.addr), access_rights)
+    }
+
+    pub(crate) fn from_raw_object(addr: TokenObject) -> Result<HxToken, HxError> {
+        match (OpenTokenRequest { token: addr }.send()?).object {
+            ObjectType::Token(x) => Ok(Self { addr: x }),
+            _ => unreachable!(),
+        }
     }
 
     ///
@@ -222,20 +255,15 @@ impl HxToken {
     /// ## Return
     /// * [`String`] - A beautiful string.
     pub async fn get_account_name(&self) -> Result<String, HxError> {
-        let mut vec = Vec::<u8>::with_capacity(1024);
-        match (GetTokenFieldRequest {
-            token: self.addr,
-            field: TokenField::AccountName(vec.as_ptr() as _),
-        })
-        .send()?
-        {
-            GetTokenFieldResponse::AccountName(offset) => {
-                unsafe {
-                    vec.set_len(offset as _);
-                }
-                String::from_utf8(vec).map_err(|_| HxError::InvalidParameters(0))
-            }
-            _ => unreachable!(),
-        }
+        HxOutputBuffer::send(
+            GetTokenFieldRequest {
+                token: self.addr,
+                field: TokenField::AccountName(OutputSlice::new()),
+            },
+            |response| match response {
+                GetTokenFieldResponse::AccountName(slice) => HxOutputBuffer::read_utf16(slice),
+                _ => unreachable!(),
+            },
+        )
     }
 }
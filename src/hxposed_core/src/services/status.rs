@@ -0,0 +1,50 @@
+use crate::error::HxError;
+use crate::hxposed::func::ServiceFunction;
+use crate::hxposed::requests::Syscall;
+use crate::hxposed::requests::status::StatusRequest;
+use crate::hxposed::responses::status::StatusResponse;
+use crate::hxposed::status::Capabilities;
+
+pub struct HxPosed {}
+
+impl HxPosed {
+    /// # Status
+    ///
+    /// Queries the state of the hypervisor.
+    ///
+    /// ## Return
+    /// * [`StatusResponse`] - State, protocol version and capabilities.
+    /// * [`HxError::HvNotLoaded`] - If hypervisor is not loaded.
+    pub fn status() -> Result<StatusResponse, HxError> {
+        StatusRequest.send()
+    }
+
+    /// # Capabilities
+    ///
+    /// Gets the service functions implemented by the running driver.
+    ///
+    /// ## Remarks
+    /// - Drivers before protocol version 2 don't report capabilities. For those, everything is reported as implemented.
+    ///
+    /// ## Return
+    /// * [`Capabilities`] - Bitmap of implemented functions.
+    /// * [`HxError::HvNotLoaded`] - If hypervisor is not loaded.
+    pub fn capabilities() -> Result<Capabilities, HxError> {
+        Ok(Self::status()?.capabilities)
+    }
+
+    /// # Require
+    ///
+    /// Checks whether the running driver implements `function`. Use this to fail early, before making calls.
+    ///
+    /// ## Return
+    /// * `()` - If `function` is implemented.
+    /// * [`HxError::NotSupported`] - If it's not.
+    /// * [`HxError::HvNotLoaded`] - If hypervisor is not loaded.
+    pub fn require(function: ServiceFunction) -> Result<(), HxError> {
+        match Self::capabilities()?.supports(function) {
+            true => Ok(()),
+            false => Err(HxError::NotSupported(function)),
+        }
+    }
+}
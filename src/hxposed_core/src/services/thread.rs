@@ -7,8 +7,13 @@ use crate::hxposed::requests::thread::*;
 use crate::hxposed::requests::Syscall;
 use crate::hxposed::responses::empty::EmptyResponse;
 use crate::hxposed::responses::thread::GetThreadFieldResponse;
+use crate::hxposed::{ObjectType, ThreadObject};
+use crate::services::handle::HxHandle;
 use crate::intern::win::GetCurrentThreadId;
+use crate::services::output::HxOutputBuffer;
 use crate::services::security::HxToken;
+use crate::services::types::thread_fields::ThreadContext;
+use crate::hxposed::output::OutputSlice;
 
 pub struct HxThread {
     pub id: u32,
@@ -17,14 +22,40 @@ pub struct HxThread {
 
 impl Drop for HxThread {
     fn drop(&mut self) {
-        let _ = CloseThreadRequest {
+        CloseThreadRequest {
             thread: self.addr,
         }
-        .send();
+        .send_forget();
     }
 }
 
 impl HxThread {
+    ///
+    /// # Object
+    ///
+    /// Gets the object this thread is tracked by. Use it for raw requests, e.g. in [`HxBatch`](crate::services::batch::HxBatch).
+    ///
+    pub fn object(&self) -> ThreadObject {
+        self.addr
+    }
+
+    ///
+    /// # To Handle
+    ///
+    /// Opens a handle to the thread in your handle table, with `access_rights` granted as is.
+    ///
+    /// ## Remarks
+    /// - Close it with `CloseHandle`. Dropping the [`HxThread`] doesn't.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::HANDLE_MANAGE`]
+    ///
+    /// ## Returns
+    /// * [`HxHandle`] - The new handle.
+    pub fn to_handle(&self, access_rights: u32) -> Result<HxHandle, HxError> {
+        HxHandle::create(ObjectType::Thread(self.addr), access_rights)
+    }
+
     ///
     /// # Current
     ///
@@ -36,13 +67,51 @@ impl HxThread {
         Self::open(unsafe { GetCurrentThreadId() })
     }
 
+    ///
+    /// # Suspend
+    ///
+    /// Suspends the thread. Works on threads of protected processes too.
+    ///
+    /// ## Remarks
+    /// - Suspensions stack. It runs again after as many [`Self::resume`]s.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::THREAD_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`u32`] - Suspend count before this. 0 if it was running.
+    /// * [`HxError::NtError`] - E.g. `STATUS_SUSPEND_COUNT_EXCEEDED`, or `STATUS_THREAD_IS_TERMINATING`.
+    /// * [`HxError::NotFound`] - [`NotFoundReason::ServiceFunction`](crate::hxposed::error::NotFoundReason::ServiceFunction). Not available on this Windows build. See [`HxPosed::require`](crate::services::status::HxPosed::require).
+    pub fn suspend(&self) -> Result<u32, HxError> {
+        Ok(SuspendThreadRequest { thread: self.addr }
+            .send()?
+            .previous_count)
+    }
+
+    ///
+    /// # Resume
+    ///
+    /// Undoes one [`Self::suspend`].
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::THREAD_EXECUTIVE`]
+    ///
+    /// ## Returns
+    /// * [`u32`] - Suspend count before this. 1 if it runs again now, 0 if it was not suspended.
+    /// * [`HxError::NtError`] - Resuming failed.
+    pub fn resume(&self) -> Result<u32, HxError> {
+        Ok(ResumeThreadRequest { thread: self.addr }
+            .send()?
+            .previous_count)
+    }
+
     ///
     /// # Swap Impersonation Token
     ///
     /// Swaps the impersonation token of the thread.
     ///
     /// ## Warning
-    /// - This happens while the thread is EXECUTING.
+    /// - This happens while the thread is EXECUTING. Unless you [`Self::suspend`] it first.
     /// - The results can be disastrous.
     /// - This isn't supported in any way.
     /// - You have been warned.
@@ -53,14 +122,12 @@ impl HxThread {
     ///
     /// ## Arguments
     /// - `token` - New token. See [`HxToken`]
-    pub async fn swap_impersonation_token(
-        &self,
-        token: &HxToken,
-    ) -> Result<EmptyResponse, HxError> {
+    pub fn swap_impersonation_token(&self, token: &HxToken) -> Result<EmptyResponse, HxError> {
         SetThreadFieldRequest {
             thread: self.addr,
             field: ThreadField::AdjustedClientToken(token.addr),
-        }.send()
+        }
+        .send()
     }
 
     ///
@@ -75,11 +142,12 @@ impl HxThread {
     /// ## Return
     /// * [`HxToken`] - Impersonation token.
     /// * [`HxError`] - Most likely thread is not impersonating.
-    pub async fn get_impersonation_token(&self) -> Result<HxToken, HxError> {
+    pub fn get_impersonation_token(&self) -> Result<HxToken, HxError> {
         match (GetThreadFieldRequest {
             thread: self.addr,
             field: ThreadField::AdjustedClientToken(0),
-        }).send()?
+        })
+        .send()?
         {
             GetThreadFieldResponse::AdjustedClientToken(x) => {
                 Ok(HxToken::from_raw_object(x)?)
@@ -88,6 +156,37 @@ impl HxThread {
         }
     }
 
+    ///
+    /// # Get Context
+    ///
+    /// Gets the user mode registers of the thread. Protected processes included.
+    ///
+    /// ## Remarks
+    /// - The thread has to run a kernel APC to hand them over. A thread that can't, e.g. one waiting non-alertably in kernel forever, blocks the worker.
+    /// - Registers keep changing while the thread runs. [`Self::suspend`] it first for a stable view.
+    ///
+    /// ## Permissions
+    /// * [`PluginPermissions::THREAD_EXECUTIVE`]
+    ///
+    /// ## Return
+    /// * [`ThreadContext`] - With [`ThreadContext::CONTEXT_ALL`].
+    /// * [`HxError::NtError`] - `PsGetContextThread` failed. E.g. thread is a system thread, or terminating.
+    pub async fn get_context(&self) -> Result<ThreadContext, HxError> {
+        HxOutputBuffer::send_async(
+            GetThreadFieldRequest {
+                thread: self.addr,
+                field: ThreadField::Context(OutputSlice::new()),
+            },
+            |response| match response {
+                GetThreadFieldResponse::Context(slice) => HxOutputBuffer::read::<ThreadContext>(slice),
+                _ => unreachable!(),
+            },
+        )
+        .await?
+        .pop()
+        .ok_or(HxError::InvalidParameters(0))
+    }
+
     ///
     /// # Is Impersonating
     ///
@@ -120,7 +219,7 @@ impl HxThread {
     /// * `id` - Thread id
     ///
     /// ## Permissions
-    /// - [`PluginPermissions::THREAD_EXECUTIVE`]
+    /// * [`PluginPermissions::THREAD_EXECUTIVE`]
     ///
     /// ## Returns
     /// * [`Result`] containing [`HxThread`] or error.
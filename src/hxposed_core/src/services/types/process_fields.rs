@@ -1,4 +1,6 @@
+use alloc::vec::Vec;
 use bitfield_struct::bitfield;
+use core::fmt::{self, Display, Formatter};
 
 #[bitfield(u8)]
 #[derive(Eq, PartialEq, Hash)]
@@ -107,8 +109,222 @@ pub struct MitigationOptions2 {
     pub user_cet_set_context_ip_validation_relaxed_mode: bool,
 }
 
-/*#[derive(Debug, Default, Clone)]
-#[repr(C)]
+///
+/// # Mitigation Options 3
+///
+/// `MitigationFlags3` of `_EPROCESS`. Only bits with a stable meaning across builds are named.
+///
+#[bitfield(u32)]
+#[derive(Eq, PartialEq, Hash)]
+pub struct MitigationOptions3 {
+    pub restrict_core_sharing: bool,
+    pub audit_restrict_core_sharing: bool,
+    /// Not named here. Kept as they are.
+    #[bits(30)]
+    pub reserved: u32,
+}
+
+// maps grouped policies onto the bits of a mitigation field.
+macro_rules! mitigation_policies {
+    ($target:ident: $offset:literal {
+        $(
+            $(#[$meta:meta])*
+            $group:ident => $getter:ident, $setter:ident, $with:ident {
+                $($field:ident = $bit:literal),* $(,)?
+            }
+        )*
+    }) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+            pub struct $group {
+                $(pub $field: bool,)*
+            }
+        )*
+
+        impl $target {
+            /// Every named bit, as `(name, bit)`.
+            const POLICY_BITS: &[(&str, u32)] = &[
+                $($((concat!(stringify!($getter), ".", stringify!($field)), $bit),)*)*
+            ];
+
+            $(
+                #[doc = concat!("Gets [`", stringify!($group), "`] out of the bits.")]
+                pub const fn $getter(&self) -> $group {
+                    let bits = self.into_bits();
+                    $group {
+                        $($field: bits & (1 << $bit) != 0,)*
+                    }
+                }
+
+                #[doc = concat!("Sets the bits of [`", stringify!($group), "`]. Others are left as they are.")]
+                pub const fn $setter(&mut self, policy: $group) {
+                    let mut bits = self.into_bits();
+                    $(
+                        if policy.$field {
+                            bits |= 1 << $bit;
+                        } else {
+                            bits &= !(1 << $bit);
+                        }
+                    )*
+                    *self = Self::from_bits(bits);
+                }
+
+                #[doc = concat!("Builder version of [`Self::", stringify!($setter), "`].")]
+                pub const fn $with(mut self, policy: $group) -> Self {
+                    self.$setter(policy);
+                    self
+                }
+            )*
+
+            ///
+            /// # Diff
+            ///
+            /// Compares `self` to `new`.
+            ///
+            /// ## Return
+            /// * [`MitigationDiff`] - Bits that were turned on and off, in bit order.
+            pub fn diff(&self, new: &Self) -> MitigationDiff {
+                let changed = (self.into_bits() ^ new.into_bits()) as u64;
+                let mut diff = MitigationDiff::default();
+
+                for bit in 0..size_of::<Self>() as u32 * 8 {
+                    if changed & (1 << bit) == 0 {
+                        continue;
+                    }
+
+                    diff.changes.push(MitigationChange {
+                        name: Self::POLICY_BITS
+                            .iter()
+                            .find(|(_, x)| *x == bit)
+                            .map(|(name, _)| *name),
+                        bit: bit + $offset,
+                        enabled: (new.into_bits() as u64) & (1 << bit) != 0,
+                    });
+                }
+
+                diff
+            }
+        }
+    };
+}
+
+mitigation_policies!(MitigationOptions: 0 {
+    ControlFlowGuard => control_flow_guard, set_control_flow_guard, with_control_flow_guard {
+        enabled = 0,
+        export_suppression = 1,
+        strict = 2,
+        xtended_deprecated = 57,
+        audit_xtended_deprecated = 58,
+    }
+
+    ImageRules => image_rules, set_image_rules, with_image_rules {
+        disallow_stripped_images = 3,
+        force_relocate_images = 4,
+        disable_non_system_fonts = 16,
+        audit_non_system_fonts = 17,
+        prefer_system32_images = 18,
+        prohibit_remote_image_map = 19,
+        audit_prohibit_remote_image_map = 20,
+        prohibit_low_il_image_map = 21,
+        audit_prohibit_low_il_image_map = 22,
+        signature_mitigation_opt_in = 23,
+        audit_block_non_microsoft_binaries = 24,
+        audit_block_non_microsoft_binaries_allow_store = 25,
+        loader_integrity_continuity_enabled = 26,
+        audit_loader_integrity_continuity = 27,
+    }
+
+    AslrRules => aslr, set_aslr, with_aslr {
+        high_entropy = 5,
+    }
+
+    MiscRules => misc_rules, set_misc_rules, with_misc_rules {
+        stack_randomization_disabled = 6,
+        extension_point_disable = 7,
+        restrict_indirect_branch_prediction = 30,
+        isolate_security_domain = 31,
+        disable_page_combine = 44,
+        speculative_store_bypass_disable = 45,
+    }
+
+    DynamicCode => dynamic_code, set_dynamic_code, with_dynamic_code {
+        disabled = 8,
+        disabled_opt_out = 9,
+        disabled_allow_remote_downgrade = 10,
+        audit = 11,
+    }
+
+    Win32kRules => win32k_rules, set_win32k_rules, with_win32k_rules {
+        disallow = 12,
+        audit_disallow = 13,
+        enable_filtered = 14,
+        audit_enable_filtered = 15,
+    }
+
+    ModuleTamperingRules => module_tampering, set_module_tampering, with_module_tampering {
+        enable = 28,
+        no_inherit = 29,
+    }
+
+    ExportAddressFilter => export_address_filter, set_export_address_filter, with_export_address_filter {
+        enable = 32,
+        audit_enable = 33,
+        enable_plus = 34,
+        audit_enable_plus = 35,
+    }
+
+    RopRules => rop, set_rop, with_rop {
+        stack_pivot = 36,
+        audit_stack_pivot = 37,
+        caller_check = 38,
+        audit_caller_check = 39,
+        sim_exec = 40,
+        audit_sim_exec = 41,
+    }
+
+    ImportAddressFilter => import_address_filter, set_import_address_filter, with_import_address_filter {
+        enable = 42,
+        audit_enable = 43,
+    }
+
+    /// User mode CET shadow stacks.
+    CetRules => cet, set_cet, with_cet {
+        shadow_stacks = 46,
+        audit_shadow_stacks = 47,
+        audit_shadow_stacks_logged = 48,
+        set_context_ip_validation = 49,
+        audit_set_context_ip_validation = 50,
+        audit_set_context_ip_validation_logged = 51,
+        strict_mode = 52,
+        block_non_cet_binaries = 53,
+        block_non_cet_binaries_non_ehcont = 54,
+        audit_block_non_cet_binaries = 55,
+        audit_block_non_cet_binaries_logged = 56,
+        dynamic_apis_out_of_proc_only = 62,
+        set_context_ip_validation_relaxed_mode = 63,
+    }
+
+    PointerAuthRules => pointer_auth, set_pointer_auth, with_pointer_auth {
+        user_ip = 59,
+        audit_user_ip = 60,
+        audit_user_ip_logged = 61,
+    }
+});
+
+mitigation_policies!(MitigationOptions3: 64 {
+    CoreSharingRules => core_sharing, set_core_sharing, with_core_sharing {
+        restrict = 0,
+        audit_restrict = 1,
+    }
+});
+
+///
+/// # Process Mitigation Options
+///
+/// [`MitigationOptions`], grouped by policy. Every bit belongs to exactly one group, so converting back and forth loses nothing.
+///
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct ProcessMitigationOptions {
     pub control_flow_guard: ControlFlowGuard,
     pub image_rules: ImageRules,
@@ -117,87 +333,126 @@ pub struct ProcessMitigationOptions {
     pub win32k_rules: Win32kRules,
     pub export_address_filter: ExportAddressFilter,
     pub import_address_filter: ImportAddressFilter,
-    pub misc_rules: MiscRules
+    pub misc_rules: MiscRules,
+    pub module_tampering: ModuleTamperingRules,
+    pub rop: RopRules,
+    pub cet: CetRules,
+    pub pointer_auth: PointerAuthRules,
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct Win32kRules {
-    pub disallow: bool,
-    pub audit_disallow: bool,
-    pub enable_filtered: bool,
-    pub audit_enable_filtered: bool,
+impl From<MitigationOptions> for ProcessMitigationOptions {
+    fn from(options: MitigationOptions) -> Self {
+        Self {
+            control_flow_guard: options.control_flow_guard(),
+            image_rules: options.image_rules(),
+            aslr: options.aslr(),
+            dynamic_code: options.dynamic_code(),
+            win32k_rules: options.win32k_rules(),
+            export_address_filter: options.export_address_filter(),
+            import_address_filter: options.import_address_filter(),
+            misc_rules: options.misc_rules(),
+            module_tampering: options.module_tampering(),
+            rop: options.rop(),
+            cet: options.cet(),
+            pointer_auth: options.pointer_auth(),
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct ExportAddressFilter {
-    pub enable: bool,
-    pub audit_enable: bool,
-    pub enable_plus: bool,
-    pub audit_enable_plus: bool,
+impl From<ProcessMitigationOptions> for MitigationOptions {
+    fn from(options: ProcessMitigationOptions) -> Self {
+        MitigationOptions::new()
+            .with_control_flow_guard(options.control_flow_guard)
+            .with_image_rules(options.image_rules)
+            .with_aslr(options.aslr)
+            .with_dynamic_code(options.dynamic_code)
+            .with_win32k_rules(options.win32k_rules)
+            .with_export_address_filter(options.export_address_filter)
+            .with_import_address_filter(options.import_address_filter)
+            .with_misc_rules(options.misc_rules)
+            .with_module_tampering(options.module_tampering)
+            .with_rop(options.rop)
+            .with_cet(options.cet)
+            .with_pointer_auth(options.pointer_auth)
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct ImportAddressFilter {
-    pub enable: bool,
-    pub audit_enable: bool,
+///
+/// # Mitigation Change
+///
+/// A single bit that differs between two sets of mitigation options.
+///
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MitigationChange {
+    /// `group.policy`. [`None`] for bits that have no name.
+    pub name: Option<&'static str>,
+    /// Bit index. `MitigationFlags3` bits start at 64.
+    pub bit: u32,
+    /// Whether the bit was turned on.
+    pub enabled: bool,
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct MiscRules {
-    pub stack_randomization_disabled: bool,
-    pub restrict_indirect_branch_prediction: bool,
+impl Display for MitigationChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sign = if self.enabled { '+' } else { '-' };
+        match self.name {
+            Some(name) => write!(f, "{}{}", sign, name),
+            None => write!(f, "{}bit{}", sign, self.bit),
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct ModuleTamperingRules {
-    pub enable: bool,
-    pub no_inherit: bool,
+///
+/// # Mitigation Diff
+///
+/// What changed between two sets of mitigation options. See [`MitigationOptions::diff`].
+///
+/// ## Remarks
+/// - Displays as `+control_flow_guard.strict, -dynamic_code.disabled`. Or `none` if nothing changed.
+/// - Diffs of [`MitigationOptions`] and [`MitigationOptions3`] can be merged with [`Extend`].
+///
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct MitigationDiff {
+    pub changes: Vec<MitigationChange>,
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct DynamicCode {
-    pub disabled: bool,
-    pub disabled_opt_out: bool,
-    pub disabled_allow_remote_downgrade: bool,
-    pub audit: bool
+impl MitigationDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct AslrRules{
-    pub high_entropy: bool,
+impl Extend<MitigationChange> for MitigationDiff {
+    fn extend<T: IntoIterator<Item = MitigationChange>>(&mut self, iter: T) {
+        self.changes.extend(iter)
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct ImageRules {
-    pub disallow_stripped_images: bool,
-    pub force_relocate_images: bool,
-    pub disable_non_system_fonts: bool,
-    pub audit_non_system_fonts: bool,
-    pub prefer_system32_images: bool,
-    pub prohibit_remote_image_map: bool,
-    pub audit_prohibit_remote_image_map: bool,
-    pub prohibit_low_il_image_map: bool,
-    pub audit_prohibit_low_il_image_map: bool,
-    pub audit_block_non_microsoft_binaries: bool,
-    pub audit_block_non_microsoft_binaries_allow_store: bool,
-    pub loader_integrity_continuity_enabled: bool,
+impl IntoIterator for MitigationDiff {
+    type Item = MitigationChange;
+    type IntoIter = alloc::vec::IntoIter<MitigationChange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
 }
 
-#[derive(Debug, Default, Clone)]
-#[repr(C)]
-pub struct ControlFlowGuard{
-    pub enabled: bool,
-    pub export_suppression: bool,
-    pub strict: bool,
-}*/
+impl Display for MitigationDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "none");
+        }
+
+        for (i, change) in self.changes.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
 #[repr(u8)]
@@ -0,0 +1,97 @@
+///
+/// # Thread Context
+///
+/// The AMD64 `CONTEXT`, as `GetThreadContext` returns it. 1232 bytes, 16 aligned.
+///
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(C, align(16))]
+pub struct ThreadContext {
+    pub p1_home: u64,
+    pub p2_home: u64,
+    pub p3_home: u64,
+    pub p4_home: u64,
+    pub p5_home: u64,
+    pub p6_home: u64,
+    /// `CONTEXT_*` flags. Which parts below are valid.
+    pub context_flags: u32,
+    pub mx_csr: u32,
+    pub seg_cs: u16,
+    pub seg_ds: u16,
+    pub seg_es: u16,
+    pub seg_fs: u16,
+    pub seg_gs: u16,
+    pub seg_ss: u16,
+    pub eflags: u32,
+    pub dr0: u64,
+    pub dr1: u64,
+    pub dr2: u64,
+    pub dr3: u64,
+    pub dr6: u64,
+    pub dr7: u64,
+    pub rax: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rbx: u64,
+    pub rsp: u64,
+    pub rbp: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub r8: u64,
+    pub r9: u64,
+    pub r10: u64,
+    pub r11: u64,
+    pub r12: u64,
+    pub r13: u64,
+    pub r14: u64,
+    pub r15: u64,
+    pub rip: u64,
+    /// `XMM_SAVE_AREA32`, as `fxsave` writes it.
+    pub flt_save: [u8; 512],
+    pub vector_register: [u128; 26],
+    pub vector_control: u64,
+    pub debug_control: u64,
+    pub last_branch_to_rip: u64,
+    pub last_branch_from_rip: u64,
+    pub last_exception_to_rip: u64,
+    pub last_exception_from_rip: u64,
+}
+
+impl ThreadContext {
+    pub const CONTEXT_AMD64: u32 = 0x10_0000;
+    pub const CONTEXT_CONTROL: u32 = Self::CONTEXT_AMD64 | 0x1;
+    pub const CONTEXT_INTEGER: u32 = Self::CONTEXT_AMD64 | 0x2;
+    pub const CONTEXT_SEGMENTS: u32 = Self::CONTEXT_AMD64 | 0x4;
+    pub const CONTEXT_FLOATING_POINT: u32 = Self::CONTEXT_AMD64 | 0x8;
+    pub const CONTEXT_DEBUG_REGISTERS: u32 = Self::CONTEXT_AMD64 | 0x10;
+    pub const CONTEXT_FULL: u32 =
+        Self::CONTEXT_CONTROL | Self::CONTEXT_INTEGER | Self::CONTEXT_FLOATING_POINT;
+    pub const CONTEXT_ALL: u32 =
+        Self::CONTEXT_FULL | Self::CONTEXT_SEGMENTS | Self::CONTEXT_DEBUG_REGISTERS;
+
+    ///
+    /// # New
+    ///
+    /// All zero, asking for `context_flags`.
+    ///
+    pub const fn new(context_flags: u32) -> Self {
+        // every field is an integer. zero is valid for all of them.
+        let mut context: Self = unsafe { core::mem::zeroed() };
+        context.context_flags = context_flags;
+        context
+    }
+
+    ///
+    /// # As Bytes
+    ///
+    /// The context as NT lays it out. E.g. for writing it into a minidump.
+    ///
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>()) }
+    }
+}
+
+impl Default for ThreadContext {
+    fn default() -> Self {
+        Self::new(Self::CONTEXT_ALL)
+    }
+}
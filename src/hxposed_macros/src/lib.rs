@@ -0,0 +1,622 @@
+//!
+//! # HxPosed Macros
+//!
+//! Derives the raw conversions of `hxposed_core` requests, responses and field enums.
+//!
+//! Every type says which slot of `HxRequest`/`HxResponse` each of its fields lives in, and the
+//! conversions in both directions are generated from that. So they can't disagree.
+//!
+//! A round-trip test is generated for every derived type too.
+//!
+//! ## Remarks
+//! - Generated code refers to `crate::hxposed`. These are meant for `hxposed_core` only.
+//!
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, Fields, Ident, Token, Type, parse_macro_input,
+};
+
+const REQUEST_SLOTS: [&str; 7] = [
+    "arg1",
+    "arg2",
+    "arg3",
+    "extended_arg1",
+    "extended_arg2",
+    "extended_arg3",
+    "extended_arg4",
+];
+
+const RESPONSE_SLOTS: [&str; 7] = REQUEST_SLOTS;
+
+///
+/// # Syscall Request
+///
+/// Implements `SyscallRequest`.
+///
+/// ## Attributes
+/// * `#[syscall(call = <HxCall constructor>, response = <type>)]` - On the struct.
+/// * `#[raw(<slot>)]` - On each field. The field type must implement `RawValue`, or `RawExtendedValue` for extended slots.
+/// * `#[raw(<slot>, <slot>)]` - On each field that is a [`RawEnum`]. First slot gets the id, second the value.
+///
+/// Slots are `arg1`-`arg3` and `extended_arg1`-`extended_arg4`. Using an extended one sets `extended_args_present`.
+///
+/// ## Example
+/// ```ignore
+/// #[derive(SyscallRequest)]
+/// #[syscall(call = get_process_field, response = GetProcessFieldResponse)]
+/// pub struct GetProcessFieldRequest {
+///     #[raw(arg1)]
+///     pub process: ProcessObject,
+///     #[raw(arg2, arg3)]
+///     pub field: ProcessField,
+/// }
+/// ```
+#[proc_macro_derive(SyscallRequest, attributes(syscall, raw))]
+pub fn derive_syscall_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_request(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+///
+/// # Syscall Response
+///
+/// Implements `SyscallResponse`.
+///
+/// ## Attributes
+/// * `#[raw(<slot>)]`, `#[raw(<slot>, <slot>)]` - On each field, same as [`SyscallRequest`].
+///
+/// Slots are `arg1`-`arg3` and `extended_arg1`-`extended_arg4`. Using an extended one sets `extended_results_present`.
+///
+/// Enums are supported too. They must derive [`RawEnum`], and take `arg1` and `arg2`.
+///
+#[proc_macro_derive(SyscallResponse, attributes(raw))]
+pub fn derive_syscall_response(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_response(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+///
+/// # Raw Enum
+///
+/// Generates `into_raw_enum` and `from_raw_enum`, which convert the enum to and from an `(id, value)` pair.
+///
+/// ## Attributes
+/// * `#[raw(unknown)]` - On a unit variant. Unrecognized ids decode to it. Without one, they panic.
+///
+/// ## Remarks
+/// - Every variant needs an explicit discriminant. That's its id.
+/// - Variants either have no fields, or a single field implementing `RawValue`.
+///
+#[proc_macro_derive(RawEnum, attributes(raw))]
+pub fn derive_raw_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_raw_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Slot {
+    Value(Ident),
+    Enum(Ident, Ident),
+}
+
+struct RawField {
+    ident: Ident,
+    ty: Type,
+    slot: Slot,
+}
+
+fn raw_args(attrs: &[Attribute]) -> syn::Result<Option<(Span, Vec<Ident>)>> {
+    let mut found = None;
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("raw")) {
+        if found.is_some() {
+            return Err(Error::new(attr.span(), "duplicate #[raw] attribute"));
+        }
+        let args = attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+        found = Some((attr.span(), args.into_iter().collect()));
+    }
+
+    Ok(found)
+}
+
+fn raw_fields(data: &Data, slots: &[&str]) -> syn::Result<Vec<RawField>> {
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(Span::call_site(), "expected a struct")),
+    };
+
+    let mut used = Vec::<String>::new();
+    let mut out = Vec::new();
+
+    for field in fields.iter() {
+        let ident = match &field.ident {
+            Some(x) => x.clone(),
+            None => return Err(Error::new(field.span(), "fields must be named")),
+        };
+        let (span, args) = match raw_args(&field.attrs)? {
+            Some(x) => x,
+            None => {
+                return Err(Error::new(
+                    field.span(),
+                    "missing #[raw(..)], which slot does this go in?",
+                ));
+            }
+        };
+
+        for arg in args.iter() {
+            let name = arg.to_string();
+            if !slots.contains(&name.as_str()) {
+                return Err(Error::new(
+                    arg.span(),
+                    format!("unknown slot, expected one of {}", slots.join(", ")),
+                ));
+            }
+            if used.contains(&name) {
+                return Err(Error::new(arg.span(), "slot is already used"));
+            }
+            used.push(name);
+        }
+
+        let slot = match args.as_slice() {
+            [x] => Slot::Value(x.clone()),
+            [x, y] => Slot::Enum(x.clone(), y.clone()),
+            _ => return Err(Error::new(span, "expected one or two slots")),
+        };
+
+        out.push(RawField {
+            ident,
+            ty: field.ty.clone(),
+            slot,
+        });
+    }
+
+    Ok(out)
+}
+
+fn is_extended(slot: &Ident) -> bool {
+    slot.to_string().starts_with("extended_")
+}
+
+fn uses_extended(fields: &[RawField]) -> bool {
+    fields.iter().any(|f| match &f.slot {
+        Slot::Value(x) => is_extended(x),
+        Slot::Enum(x, y) => is_extended(x) || is_extended(y),
+    })
+}
+
+/// Reads a slot as `u64`.
+fn read_slot(source: &TokenStream2, slot: &Ident) -> TokenStream2 {
+    if is_extended(slot) {
+        quote!((#source.#slot as u64))
+    } else {
+        quote!(#source.#slot)
+    }
+}
+
+/// Writes a `u64` to a slot.
+fn write_slot(target: &TokenStream2, slot: &Ident, value: TokenStream2) -> TokenStream2 {
+    if is_extended(slot) {
+        quote!(#target.#slot = (#value) as u128;)
+    } else {
+        quote!(#target.#slot = #value;)
+    }
+}
+
+fn into_slots(target: &TokenStream2, fields: &[RawField]) -> TokenStream2 {
+    let raw_value = quote!(crate::hxposed::raw::RawValue);
+    let raw_extended = quote!(crate::hxposed::raw::RawExtendedValue);
+
+    fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            match &field.slot {
+                Slot::Value(slot) if is_extended(slot) => quote! {
+                    #target.#slot = <#ty as #raw_extended>::into_raw_extended(self.#ident);
+                },
+                Slot::Value(slot) => write_slot(
+                    target,
+                    slot,
+                    quote!(<#ty as #raw_value>::into_raw_value(self.#ident)),
+                ),
+                Slot::Enum(id, value) => {
+                    let id = write_slot(target, id, quote!(__id));
+                    let value = write_slot(target, value, quote!(__value));
+                    quote! {
+                        let (__id, __value) = self.#ident.into_raw_enum();
+                        #id
+                        #value
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+fn from_slots(source: &TokenStream2, fields: &[RawField]) -> TokenStream2 {
+    let raw_value = quote!(crate::hxposed::raw::RawValue);
+    let raw_extended = quote!(crate::hxposed::raw::RawExtendedValue);
+
+    fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            match &field.slot {
+                Slot::Value(slot) if is_extended(slot) => {
+                    quote!(#ident: <#ty as #raw_extended>::from_raw_extended(#source.#slot),)
+                }
+                Slot::Value(slot) => {
+                    let slot = read_slot(source, slot);
+                    quote!(#ident: <#ty as #raw_value>::from_raw_value(#slot),)
+                }
+                Slot::Enum(id, value) => {
+                    let id = read_slot(source, id);
+                    let value = read_slot(source, value);
+                    quote!(#ident: <#ty>::from_raw_enum(#id, #value),)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Fills every used slot with a value that survives a round trip.
+fn sample_slots(target: &TokenStream2, fields: &[RawField]) -> TokenStream2 {
+    let raw_value = quote!(crate::hxposed::raw::RawValue);
+    let raw_extended = quote!(crate::hxposed::raw::RawExtendedValue);
+
+    fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            match &field.slot {
+                Slot::Value(slot) if is_extended(slot) => quote! {
+                    #target.#slot = <#ty as #raw_extended>::into_raw_extended(
+                        <#ty as #raw_extended>::from_raw_extended(1)
+                    );
+                },
+                Slot::Value(slot) => write_slot(
+                    target,
+                    slot,
+                    quote!(<#ty as #raw_value>::into_raw_value(
+                        <#ty as #raw_value>::from_raw_value(1)
+                    )),
+                ),
+                Slot::Enum(id, value) => {
+                    let id = write_slot(target, id, quote!(__id));
+                    let value = write_slot(target, value, quote!(__value));
+                    quote! {
+                        let (__id, __value) = <#ty>::raw_sample();
+                        #id
+                        #value
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+fn construct(fields: &Fields, body: TokenStream2) -> TokenStream2 {
+    match fields {
+        Fields::Unit => quote!(Self),
+        _ => quote!(Self { #body }),
+    }
+}
+
+fn test_module(derive: &str, ident: &Ident, body: TokenStream2) -> TokenStream2 {
+    let module = format_ident!("__{}_round_trip_{}", derive, ident);
+
+    quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            use super::*;
+
+            #[test]
+            fn round_trip() {
+                #body
+            }
+        }
+    }
+}
+
+fn expand_request(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let mut call = None;
+    let mut response = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("syscall")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("call") {
+                call = Some(meta.value()?.parse::<Ident>()?);
+            } else if meta.path.is_ident("response") {
+                response = Some(meta.value()?.parse::<Type>()?);
+            } else {
+                return Err(meta.error("expected `call` or `response`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let call = call.ok_or_else(|| Error::new(ident.span(), "missing #[syscall(call = ..)]"))?;
+    let response =
+        response.ok_or_else(|| Error::new(ident.span(), "missing #[syscall(response = ..)]"))?;
+
+    let fields = raw_fields(&input.data, &REQUEST_SLOTS)?;
+    let extended = uses_extended(&fields);
+
+    let raw = quote!(raw);
+    let request = quote!(request);
+    let into = into_slots(&raw, &fields);
+    let from = construct(
+        match &input.data {
+            Data::Struct(data) => &data.fields,
+            _ => unreachable!(),
+        },
+        from_slots(&request, &fields),
+    );
+    let sample = sample_slots(&raw, &fields);
+    let set_extended = if extended {
+        quote!(raw.call.set_extended_args_present(true);)
+    } else {
+        quote!()
+    };
+
+    let test = test_module(
+        "request",
+        ident,
+        quote! {
+            use crate::hxposed::requests::{HxRequest, SyscallRequest};
+
+            let mut raw = HxRequest {
+                call: crate::hxposed::call::HxCall::#call(),
+                ..Default::default()
+            };
+            #set_extended
+            #sample
+
+            let back = <super::#ident as SyscallRequest>::from_raw(&raw).into_raw();
+            assert_eq!(back.call.into_bits(), raw.call.into_bits());
+            assert_eq!(back.arg1, raw.arg1);
+            assert_eq!(back.arg2, raw.arg2);
+            assert_eq!(back.arg3, raw.arg3);
+            assert_eq!(back.extended_arg1, raw.extended_arg1);
+            assert_eq!(back.extended_arg2, raw.extended_arg2);
+            assert_eq!(back.extended_arg3, raw.extended_arg3);
+            assert_eq!(back.extended_arg4, raw.extended_arg4);
+        },
+    );
+
+    Ok(quote! {
+        impl crate::hxposed::requests::SyscallRequest for #ident {
+            type Response = #response;
+
+            fn into_raw(self) -> crate::hxposed::requests::HxRequest {
+                let mut raw = crate::hxposed::requests::HxRequest {
+                    call: crate::hxposed::call::HxCall::#call(),
+                    ..Default::default()
+                };
+                #set_extended
+                #into
+                raw
+            }
+
+            fn from_raw(request: &crate::hxposed::requests::HxRequest) -> Self {
+                #from
+            }
+        }
+
+        #test
+    })
+}
+
+fn expand_response(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    let mut extended = false;
+    let (into, from, sample) = match &input.data {
+        Data::Enum(_) => (
+            quote! {
+                let (id, value) = self.into_raw_enum();
+                raw.arg1 = id;
+                raw.arg2 = value;
+            },
+            quote!(Self::from_raw_enum(raw.arg1, raw.arg2)),
+            quote! {
+                let (id, value) = super::#ident::raw_sample();
+                raw.arg1 = id;
+                raw.arg2 = value;
+            },
+        ),
+        Data::Struct(data) => {
+            let fields = raw_fields(&input.data, &RESPONSE_SLOTS)?;
+            let raw = quote!(raw);
+            extended = uses_extended(&fields);
+            (
+                into_slots(&raw, &fields),
+                construct(&data.fields, from_slots(&raw, &fields)),
+                sample_slots(&raw, &fields),
+            )
+        }
+        Data::Union(_) => return Err(Error::new(ident.span(), "unions are not supported")),
+    };
+    let set_extended = if extended {
+        quote!(raw.result.set_extended_results_present(true);)
+    } else {
+        quote!()
+    };
+
+    let test = test_module(
+        "response",
+        ident,
+        quote! {
+            use crate::hxposed::responses::{HxResponse, SyscallResponse};
+
+            let mut raw = HxResponse {
+                result: crate::hxposed::call::HxResult::ok(),
+                ..Default::default()
+            };
+            #set_extended
+            #sample
+
+            assert_eq!(<super::#ident as SyscallResponse>::from_raw(raw).into_raw(), raw);
+        },
+    );
+
+    Ok(quote! {
+        impl crate::hxposed::responses::SyscallResponse for #ident {
+            fn from_raw(raw: crate::hxposed::responses::HxResponse) -> Self {
+                #from
+            }
+
+            fn into_raw(self) -> crate::hxposed::responses::HxResponse {
+                let mut raw = crate::hxposed::responses::HxResponse {
+                    result: crate::hxposed::call::HxResult::ok(),
+                    ..Default::default()
+                };
+                #set_extended
+                #into
+                raw
+            }
+        }
+
+        #test
+    })
+}
+
+fn expand_raw_enum(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let raw_value = quote!(crate::hxposed::raw::RawValue);
+
+    let data = match &input.data {
+        Data::Enum(x) => x,
+        _ => return Err(Error::new(ident.span(), "expected an enum")),
+    };
+
+    let mut into = Vec::new();
+    let mut from = Vec::new();
+    let mut checks = Vec::new();
+    let mut unknown = None::<Ident>;
+    let mut sample = None::<Expr>;
+
+    for variant in data.variants.iter() {
+        let name = &variant.ident;
+        let id = match &variant.discriminant {
+            Some((_, expr)) => expr,
+            None => {
+                return Err(Error::new(
+                    variant.span(),
+                    "missing discriminant, it's the id of the variant",
+                ));
+            }
+        };
+
+        let is_unknown = match raw_args(&variant.attrs)? {
+            Some((span, args)) => match args.as_slice() {
+                [x] if x == "unknown" => true,
+                _ => return Err(Error::new(span, "expected #[raw(unknown)]")),
+            },
+            None => false,
+        };
+
+        match &variant.fields {
+            Fields::Unit => {
+                into.push(quote!(Self::#name => ((#id) as u64, 0),));
+                if !is_unknown {
+                    from.push(quote!(x if x == (#id) as u64 => Self::#name,));
+                }
+                checks.push(quote! {
+                    assert_eq!(
+                        super::#ident::from_raw_enum((#id) as u64, 0).into_raw_enum(),
+                        ((#id) as u64, 0)
+                    );
+                });
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 && !is_unknown => {
+                let ty = &fields.unnamed[0].ty;
+                into.push(quote! {
+                    Self::#name(x) => ((#id) as u64, <#ty as #raw_value>::into_raw_value(x)),
+                });
+                from.push(quote! {
+                    x if x == (#id) as u64 => Self::#name(<#ty as #raw_value>::from_raw_value(value)),
+                });
+                checks.push(quote! {
+                    let value = <#ty as #raw_value>::into_raw_value(
+                        <#ty as #raw_value>::from_raw_value(1)
+                    );
+                    assert_eq!(
+                        super::#ident::from_raw_enum((#id) as u64, value).into_raw_enum(),
+                        ((#id) as u64, value)
+                    );
+                });
+            }
+            _ => {
+                return Err(Error::new(
+                    variant.span(),
+                    if is_unknown {
+                        "#[raw(unknown)] variant can't have fields"
+                    } else {
+                        "variants can have at most one unnamed field"
+                    },
+                ));
+            }
+        }
+
+        if is_unknown {
+            if unknown.is_some() {
+                return Err(Error::new(variant.span(), "only one variant can be unknown"));
+            }
+            unknown = Some(name.clone());
+        } else if sample.is_none() {
+            sample = Some(id.clone());
+        }
+    }
+
+    let fallback = match &unknown {
+        Some(x) => quote!(_ => Self::#x,),
+        None => quote!(_ => panic!("Invalid object id: {}", object),),
+    };
+    let sample = match &sample {
+        Some(x) => quote!((#x) as u64),
+        None => return Err(Error::new(ident.span(), "needs a variant other than unknown")),
+    };
+
+    let test = test_module("raw_enum", ident, quote!(#(#checks)*));
+
+    // value is unused when all variants are unit.
+    Ok(quote! {
+        impl #ident {
+            pub fn into_raw_enum(self) -> (u64, u64) {
+                match self {
+                    #(#into)*
+                }
+            }
+
+            #[allow(unused_variables)]
+            pub fn from_raw_enum(object: u64, value: u64) -> Self {
+                match object {
+                    #(#from)*
+                    #fallback
+                }
+            }
+
+            #[cfg(test)]
+            pub(crate) fn raw_sample() -> (u64, u64) {
+                Self::from_raw_enum(#sample, 1).into_raw_enum()
+            }
+        }
+
+        #test
+    })
+}
@@ -0,0 +1,440 @@
+use crate::objects::*;
+use crate::services;
+use hxposed_core::host;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::permissions::PluginPermissions;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::requests::notify::ObjectState;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::responses::notify::CallbackInformation;
+use hxposed_core::services::types::process_fields::*;
+use hxposed_core::hxposed::AsyncCookie;
+use hxposed_core::hxposed::responses::async_call::AsyncCompletion;
+use std::collections::{BTreeMap, VecDeque};
+
+///
+/// # Sim Kernel
+///
+/// The simulated world. Holds every process, thread and token, plus the object tracker of the caller.
+///
+/// The caller is the process running the tests. It's added on creation, along with `System` (pid 4).
+///
+/// Use the methods here to set the world up and inspect it after making calls through `hxposed_core`.
+///
+#[derive(Debug)]
+pub struct SimKernel {
+    pub(crate) processes: BTreeMap<SimAddress, SimProcess>,
+    pub(crate) threads: BTreeMap<SimAddress, SimThread>,
+    pub(crate) tokens: BTreeMap<SimAddress, SimToken>,
+    pub(crate) tracker: ObjectTracker,
+    pub(crate) caller: SimAddress,
+    pub(crate) system_token: SimAddress,
+    pub(crate) msrs: BTreeMap<u32, u64>,
+    pub(crate) cr8: u64,
+    pub(crate) pending: VecDeque<SimAsyncWork>,
+    pub(crate) defer_async: bool,
+    /// Set while [`Self::run_async`] dispatches. The driver checks for its worker thread instead.
+    pub(crate) on_worker: bool,
+    pub(crate) permissions: PluginPermissions,
+    pub(crate) faults: Vec<SimFault>,
+    next_address: SimAddress,
+    next_pa: u64,
+    next_id: u32,
+    next_cookie: u64,
+    clock: u64,
+}
+
+impl Default for SimKernel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimKernel {
+    /// Where object addresses start. Looks like kernel pool, so nobody mistakes it for an index.
+    const ADDRESS_BASE: SimAddress = 0xFFFF_C000_0000_0000;
+    /// 2025-01-01 in 100ns intervals since 1601.
+    const BOOT_TIME: u64 = 133_801_920_000_000_000;
+
+    pub fn new() -> Self {
+        let mut me = Self {
+            processes: BTreeMap::new(),
+            threads: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            tracker: ObjectTracker::default(),
+            caller: 0,
+            system_token: 0,
+            msrs: BTreeMap::new(),
+            cr8: 0,
+            pending: VecDeque::new(),
+            defer_async: false,
+            on_worker: false,
+            permissions: PluginPermissions::all(),
+            faults: Vec::new(),
+            next_address: Self::ADDRESS_BASE,
+            next_pa: 0x1_0000_0000,
+            next_id: 0x100,
+            next_cookie: 1,
+            clock: Self::BOOT_TIME,
+        };
+
+        me.system_token = me.add_token(SimToken::system());
+
+        let mut system = SimProcess::new("System");
+        system.id = 4;
+        system.nt_path = "System".into();
+        system.token = me.system_token;
+        system.protection = ProcessProtection::new()
+            .with_protection_type(ProtectionType::Protected)
+            .with_signer(ProtectionSigner::WinTcb);
+        system.signers = ProcessSignatureLevels::new()
+            .with_signature_level(ProcessSignatureLevel::WindowsTcb)
+            .with_section_signature_level(ProcessSignatureLevel::WindowsTcb.into_bits());
+        me.add_process(system);
+        me.add_thread(4, SimThread::default());
+
+        let mut caller = SimProcess::new("hxposed_sim.exe");
+        caller.id = unsafe { host::GetCurrentProcessId() };
+        caller.parent_id = 4;
+        me.add_process(caller.clone());
+        me.add_thread(
+            caller.id,
+            SimThread {
+                id: unsafe { host::GetCurrentThreadId() },
+                ..Default::default()
+            },
+        );
+
+        me.caller = me.process_address(caller.id).unwrap();
+        // driver puts the caller itself at index 0.
+        me.tracker.processes.push(me.caller);
+
+        me
+    }
+
+    ///
+    /// # Dispatch
+    ///
+    /// Services a raw request the same way the driver's `syscall_handler` does.
+    ///
+    pub fn dispatch(&mut self, request: &HxRequest) -> HxResponse {
+        services::dispatch(self, request)
+    }
+
+    ///
+    /// # Add Process
+    ///
+    /// Adds a process to the world. Registered process callbacks are notified.
+    ///
+    /// ## Arguments
+    /// * `process` - Process to add. Zero id, token and directory bases are filled in.
+    ///
+    /// ## Return
+    /// * [`u32`] - Id of the process.
+    pub fn add_process(&mut self, mut process: SimProcess) -> u32 {
+        if process.id == 0 {
+            process.id = self.next_id();
+        }
+        if process.token == 0 {
+            process.token = self.add_token(SimToken::user("User"));
+        }
+        if process.directory_table_base == 0 {
+            process.directory_table_base = self.next_pa();
+        }
+        if process.user_directory_table_base == 0 {
+            process.user_directory_table_base = self.next_pa();
+        }
+        if process.create_time == 0 {
+            self.clock += 10_000_000;
+            process.create_time = self.clock;
+        }
+
+        let id = process.id;
+        let address = self.next_address();
+        self.processes.insert(address, process);
+        self.notify(ObjectType::Process(id as _), ObjectState::Created);
+
+        id
+    }
+
+    ///
+    /// # Remove Process
+    ///
+    /// Removes a process and its threads. Registered process callbacks are notified.
+    ///
+    /// ## Remarks
+    /// - Objects the caller has open stay in the tracker. Using them returns `NotFound`, since the address no longer resolves.
+    pub fn remove_process(&mut self, id: u32) -> Option<SimProcess> {
+        let address = self.process_address(id)?;
+        let process = self.processes.remove(&address)?;
+
+        self.threads.retain(|_, thread| thread.process_id != id);
+        self.notify(ObjectType::Process(id as _), ObjectState::Deleted);
+
+        Some(process)
+    }
+
+    ///
+    /// # Add Thread
+    ///
+    /// Adds a thread to a process. Registered thread callbacks are notified.
+    ///
+    /// ## Return
+    /// * [`u32`] - Id of the thread.
+    /// * [`None`] - Process does not exist.
+    pub fn add_thread(&mut self, process_id: u32, mut thread: SimThread) -> Option<u32> {
+        if thread.id == 0 {
+            thread.id = self.next_id();
+        }
+        thread.process_id = process_id;
+
+        let id = thread.id;
+        self.process_mut(process_id)?.threads.push(id);
+
+        let address = self.next_address();
+        self.threads.insert(address, thread);
+        self.notify(ObjectType::Thread(id as _), ObjectState::Created);
+
+        Some(id)
+    }
+
+    ///
+    /// # Add Token
+    ///
+    /// ## Return
+    /// * [`SimAddress`] - Kernel address of the token. This is what `ProcessField::Token` reports.
+    pub fn add_token(&mut self, token: SimToken) -> SimAddress {
+        let address = self.next_address();
+        self.tokens.insert(address, token);
+        address
+    }
+
+    ///
+    /// # Add Handle
+    ///
+    /// Inserts a handle into the handle table of a process.
+    ///
+    /// ## Return
+    /// * [`u64`] - Handle value.
+    /// * [`None`] - Process does not exist.
+    pub fn add_handle(&mut self, process_id: u32, handle: SimHandle) -> Option<u64> {
+        let handles = &mut self.process_mut(process_id)?.handles;
+        let value = handles.keys().last().map_or(4, |x| x + 4);
+        handles.insert(value, handle);
+
+        Some(value)
+    }
+
+    ///
+    /// # Defer Async
+    ///
+    /// By default, slow calls are completed before the cookie is returned. Set this to leave them pending
+    /// until [`Self::run_async`], so the waiting side can be tested.
+    ///
+    pub fn defer_async(&mut self, defer: bool) {
+        self.defer_async = defer;
+    }
+
+    ///
+    /// # Set Permissions
+    ///
+    /// Permissions the caller is granted. Everything by default, like the driver with caller verification off.
+    ///
+    pub fn set_permissions(&mut self, permissions: PluginPermissions) {
+        self.permissions = permissions;
+    }
+
+    ///
+    /// # Fail Call
+    ///
+    /// Makes a call fail, to test how the caller copes with it.
+    ///
+    /// ## Arguments
+    /// * `function` - Service function to fail.
+    /// * `skip` - Number of calls to `function` to let through first.
+    /// * `response` - Returned instead of servicing the call. Once.
+    ///
+    pub fn fail_call(&mut self, function: ServiceFunction, skip: usize, response: HxResponse) {
+        self.faults.push(SimFault {
+            function,
+            skip,
+            response,
+        });
+    }
+
+    pub(crate) fn take_fault(&mut self, function: ServiceFunction) -> Option<HxResponse> {
+        let index = self.faults.iter().position(|x| x.function == function)?;
+        let fault = &mut self.faults[index];
+
+        if fault.skip != 0 {
+            fault.skip -= 1;
+            return None;
+        }
+
+        Some(self.faults.remove(index).response)
+    }
+
+    ///
+    /// # Run Async
+    ///
+    /// Completes every pending slow call, signaling their events.
+    ///
+    /// ## Return
+    /// * [`usize`] - Number of calls completed.
+    pub fn run_async(&mut self) -> usize {
+        let mut count = 0;
+
+        while let Some(work) = self.pending.pop_front() {
+            self.on_worker = true;
+            let response = self.dispatch(&work.request);
+            self.on_worker = false;
+            let ptr = work.completion as *mut AsyncCompletion;
+
+            unsafe {
+                (&raw mut (*ptr).cookie).write_volatile(work.cookie);
+                (&raw mut (*ptr).response).write_volatile(response);
+                (&raw mut (*ptr).completed).write_volatile(1);
+                host::SetEvent(work.event);
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+
+    ///
+    /// # Pending Async
+    ///
+    /// Slow calls waiting for [`Self::run_async`].
+    ///
+    pub fn pending_async(&self) -> &VecDeque<SimAsyncWork> {
+        &self.pending
+    }
+
+    pub fn set_msr(&mut self, msr: u32, value: u64) {
+        self.msrs.insert(msr, value);
+    }
+
+    pub fn msr(&self, msr: u32) -> Option<u64> {
+        self.msrs.get(&msr).copied()
+    }
+
+    pub fn process(&self, id: u32) -> Option<&SimProcess> {
+        self.processes.values().find(|p| p.id == id)
+    }
+
+    pub fn process_mut(&mut self, id: u32) -> Option<&mut SimProcess> {
+        self.processes.values_mut().find(|p| p.id == id)
+    }
+
+    pub fn process_address(&self, id: u32) -> Option<SimAddress> {
+        self.processes
+            .iter()
+            .find(|(_, p)| p.id == id)
+            .map(|(addr, _)| *addr)
+    }
+
+    ///
+    /// # Object Type Name
+    ///
+    /// `_OBJECT_TYPE.Name` of a simulated object. Empty for addresses the sim doesn't know.
+    ///
+    pub fn object_type_name(&self, address: SimAddress) -> &'static str {
+        if self.processes.contains_key(&address) {
+            "Process"
+        } else if self.threads.contains_key(&address) {
+            "Thread"
+        } else if self.tokens.contains_key(&address) {
+            "Token"
+        } else {
+            ""
+        }
+    }
+
+    pub fn thread(&self, id: u32) -> Option<&SimThread> {
+        self.threads.values().find(|t| t.id == id)
+    }
+
+    pub fn thread_mut(&mut self, id: u32) -> Option<&mut SimThread> {
+        self.threads.values_mut().find(|t| t.id == id)
+    }
+
+    pub fn token(&self, address: SimAddress) -> Option<&SimToken> {
+        self.tokens.get(&address)
+    }
+
+    pub fn token_mut(&mut self, address: SimAddress) -> Option<&mut SimToken> {
+        self.tokens.get_mut(&address)
+    }
+
+    pub fn system_token(&self) -> SimAddress {
+        self.system_token
+    }
+
+    ///
+    /// # Caller
+    ///
+    /// The process making the calls. That's the one running the tests.
+    ///
+    pub fn caller(&self) -> &SimProcess {
+        &self.processes[&self.caller]
+    }
+
+    ///
+    /// # Tracker
+    ///
+    /// Objects the caller currently has open. Handy for leak checks.
+    ///
+    pub fn tracker(&self) -> &ObjectTracker {
+        &self.tracker
+    }
+
+    pub(crate) fn next_address(&mut self) -> SimAddress {
+        self.next_address += 0x1000;
+        self.next_address
+    }
+
+    pub(crate) fn next_pa(&mut self) -> u64 {
+        self.next_pa += 0x1000;
+        self.next_pa
+    }
+
+    pub(crate) fn next_cookie(&mut self) -> AsyncCookie {
+        self.next_cookie += 1;
+        self.next_cookie - 1
+    }
+
+    fn next_id(&mut self) -> u32 {
+        loop {
+            self.next_id += 4;
+            let id = self.next_id;
+            if self.process(id).is_none() && self.thread(id).is_none() {
+                return id;
+            }
+        }
+    }
+
+    fn notify(&mut self, object: ObjectType, state: ObjectState) {
+        let target = match object {
+            ObjectType::Process(_) => ObjectType::Process(0),
+            ObjectType::Thread(_) => ObjectType::Thread(0),
+            _ => return,
+        };
+
+        for callback in self.tracker.callbacks.iter().filter(|c| c.target == target) {
+            let raw = object.into_raw_enum();
+            let info = CallbackInformation {
+                object_type: raw.0,
+                object_value: raw.1,
+                object_state: state.clone(),
+            };
+
+            unsafe {
+                (callback.memory as *mut CallbackInformation).write_volatile(info);
+                host::SetEvent(callback.event_handle);
+            }
+        }
+    }
+}
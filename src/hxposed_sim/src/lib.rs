@@ -0,0 +1,134 @@
+//!
+//! # HxPosed Sim
+//!
+//! A simulated HxPosed kernel that lives in your process.
+//!
+//! It services [`HxRequest`]s against an in-memory object model, using the same
+//! `SyscallRequest::from_raw`/`SyscallResponse::into_raw` code the driver does. So everything in
+//! `hxposed_core::services` can be exercised without Windows, a hypervisor or the driver.
+//!
+//! ## Example
+//! ```no_run
+//! use hxposed_core::services::process::HxProcess;
+//! use hxposed_sim::{Sim, SimProcess};
+//!
+//! let sim = Sim::new();
+//! let pid = sim.with(|k| k.add_process(SimProcess::new("notepad.exe")));
+//!
+//! let process = HxProcess::open(pid).unwrap();
+//! ```
+//!
+//! ## Remarks
+//! - Each thread gets its own world. Tests running in parallel don't see each other.
+//! - Only the caller's address space is real. Mapping into other processes is recorded, nothing else.
+//!
+
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use hxposed_core::hxposed::call::HxResult;
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::transport::{Transport, set_transport};
+use std::sync::Once;
+
+mod kernel;
+pub mod memory;
+pub mod objects;
+mod services;
+
+pub use kernel::SimKernel;
+pub use objects::*;
+
+std::thread_local! {
+    static KERNEL: RefCell<Option<SimKernel>> = const { RefCell::new(None) };
+}
+
+///
+/// # Sim Transport
+///
+/// Routes requests to the [`SimKernel`] of the calling thread.
+///
+/// If the thread has no kernel, requests fail with [`HxError::HvNotLoaded`], just like the real thing when the hypervisor is missing.
+///
+#[derive(Default, Clone, Copy, Debug)]
+pub struct SimTransport;
+
+impl Transport for SimTransport {
+    fn send(&self, request: &mut HxRequest) -> HxResponse {
+        KERNEL.with_borrow_mut(|kernel| match kernel {
+            Some(kernel) => kernel.dispatch(request),
+            None => HxResponse {
+                result: HxResult::from_error(HxError::HvNotLoaded),
+                ..Default::default()
+            },
+        })
+    }
+}
+
+static TRANSPORT: SimTransport = SimTransport;
+static INSTALL: Once = Once::new();
+
+///
+/// # Sim
+///
+/// Gives the calling thread a fresh [`SimKernel`] and routes `hxposed_core` requests to it.
+///
+/// The kernel is torn down on drop.
+///
+pub struct Sim {
+    // the kernel belongs to the thread that created it.
+    _thread_bound: PhantomData<*const ()>,
+}
+
+impl Drop for Sim {
+    fn drop(&mut self) {
+        KERNEL.with_borrow_mut(|kernel| *kernel = None);
+    }
+}
+
+impl Default for Sim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sim {
+    ///
+    /// # New
+    ///
+    /// ## Panic
+    /// - If this thread already has a [`Sim`].
+    pub fn new() -> Self {
+        Self::with_kernel(SimKernel::new())
+    }
+
+    ///
+    /// # With Kernel
+    ///
+    /// Same as [`Self::new`], with a kernel you have set up yourself.
+    ///
+    pub fn with_kernel(kernel: SimKernel) -> Self {
+        INSTALL.call_once(|| set_transport(&TRANSPORT));
+
+        KERNEL.with_borrow_mut(|current| {
+            assert!(current.is_none(), "this thread already has a simulated kernel");
+            *current = Some(kernel);
+        });
+
+        Self {
+            _thread_bound: PhantomData,
+        }
+    }
+
+    ///
+    /// # With
+    ///
+    /// Runs `f` with the kernel. Use it to set the world up, or to check what calls did to it.
+    ///
+    /// ## Panic
+    /// - If `f` makes HxPosed calls itself.
+    pub fn with<R>(&self, f: impl FnOnce(&mut SimKernel) -> R) -> R {
+        KERNEL.with_borrow_mut(|kernel| f(kernel.as_mut().unwrap()))
+    }
+}
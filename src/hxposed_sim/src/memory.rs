@@ -0,0 +1,152 @@
+use std::io;
+
+///
+/// # Shared Memory
+///
+/// Backing pages for a simulated RMD. Every mapping of the same RMD sees the same bytes, like the real thing.
+///
+#[derive(Debug)]
+pub struct SharedMemory {
+    fd: libc::c_int,
+    size: usize,
+}
+
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+impl SharedMemory {
+    pub const PAGE_SIZE: usize = 4096;
+
+    ///
+    /// # New
+    ///
+    /// Creates zeroed backing memory. Size is rounded up to page size, as the driver does.
+    ///
+    pub fn new(size: u32) -> io::Result<Self> {
+        let size = (size as usize).div_ceil(Self::PAGE_SIZE).max(1) * Self::PAGE_SIZE;
+
+        let fd = unsafe { libc::memfd_create(c"hxposed_rmd".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if unsafe { libc::ftruncate(fd, size as _) } != 0 {
+            let error = io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(error);
+        }
+
+        Ok(Self { fd, size })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    ///
+    /// # Map
+    ///
+    /// Maps the memory at exactly `address` in this process.
+    ///
+    /// ## Return
+    /// * [`io::Error`] - Address is not page aligned, or something is already mapped there.
+    pub fn map(&self, address: u64) -> io::Result<()> {
+        if !(address as usize).is_multiple_of(Self::PAGE_SIZE) {
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                address as _,
+                self.size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_FIXED_NOREPLACE,
+                self.fd,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        if ptr as u64 != address {
+            // old kernels ignore MAP_FIXED_NOREPLACE and treat it as a hint.
+            unsafe { libc::munmap(ptr, self.size) };
+            return Err(io::Error::from(io::ErrorKind::AddrInUse));
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// # Map Anywhere
+    ///
+    /// Maps the memory wherever there's room in this process.
+    ///
+    /// ## Return
+    /// * [`u64`] - Where it's mapped.
+    /// * [`io::Error`] - No room.
+    pub fn map_anywhere(&self) -> io::Result<u64> {
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                self.size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                self.fd,
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(ptr as _)
+    }
+
+    pub fn unmap(&self, address: u64) {
+        unsafe {
+            libc::munmap(address as _, self.size);
+        }
+    }
+
+    ///
+    /// # Read
+    ///
+    /// Reads the backing memory, regardless of where it's mapped.
+    ///
+    pub fn read(&self, offset: u64, buffer: &mut [u8]) -> io::Result<usize> {
+        let read = unsafe {
+            libc::pread(self.fd, buffer.as_mut_ptr() as _, buffer.len(), offset as _)
+        };
+
+        if read < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(read as _)
+        }
+    }
+
+    ///
+    /// # Write
+    ///
+    /// Writes the backing memory, regardless of where it's mapped.
+    ///
+    pub fn write(&self, offset: u64, buffer: &[u8]) -> io::Result<usize> {
+        let written =
+            unsafe { libc::pwrite(self.fd, buffer.as_ptr() as _, buffer.len(), offset as _) };
+
+        if written < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(written as _)
+        }
+    }
+}
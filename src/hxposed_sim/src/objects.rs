@@ -0,0 +1,364 @@
+use crate::memory::SharedMemory;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::requests::memory::MemoryType;
+use hxposed_core::hxposed::responses::handle::HandleAttributes;
+use hxposed_core::hxposed::responses::memory::VadType;
+use hxposed_core::hxposed::{AsyncCookie, CallbackObject, ObjectType, ProcessObject, RmdObject, ThreadObject, TokenObject};
+use hxposed_core::services::types::process_fields::*;
+use hxposed_core::services::types::security_fields::*;
+use hxposed_core::services::types::thread_fields::ThreadContext;
+use std::collections::BTreeMap;
+
+/// Kernel address of a simulated object. Plays the role of `PEPROCESS`, `PETHREAD` and friends.
+pub type SimAddress = u64;
+
+///
+/// # Sim Process
+///
+/// Simulated `_EPROCESS`. Only the fields HxPosed exposes are modelled.
+///
+#[derive(Debug, Clone)]
+pub struct SimProcess {
+    pub id: u32,
+    pub parent_id: u32,
+    pub name: String,
+    pub nt_path: String,
+    pub protection: ProcessProtection,
+    pub signers: ProcessSignatureLevels,
+    pub mitigations: MitigationOptions,
+    pub mitigations3: MitigationOptions3,
+    /// Kernel address of the primary token.
+    pub token: SimAddress,
+    pub threads: Vec<u32>,
+    pub directory_table_base: u64,
+    pub user_directory_table_base: u64,
+    pub create_time: u64,
+    pub handles: BTreeMap<u64, SimHandle>,
+    /// Page table entries set through `GetSetPageAttribute`. Keyed by level and page address.
+    pub page_attributes: BTreeMap<(u64, u64), u64>,
+    /// Set once the process is killed. It stays in the world, like a process someone still references.
+    pub exit_status: Option<u32>,
+    /// User memory, keyed by region base. Reads and writes anywhere else fault.
+    pub memory: BTreeMap<u64, Vec<u8>>,
+    /// VAD tree, keyed by start VPN. Independent of `memory`.
+    pub vads: BTreeMap<u64, SimVad>,
+    /// Loader lists, native then 32-bit. In load order.
+    pub modules: Vec<SimModule>,
+    /// `RTL_USER_PROCESS_PARAMETERS`. Empty, like a process without a PEB.
+    pub command_line: String,
+    pub current_directory: String,
+    pub window_title: String,
+    pub environment: Vec<(String, String)>,
+}
+
+impl SimProcess {
+    ///
+    /// # New
+    ///
+    /// A process with no protection, no mitigations and no threads.
+    ///
+    /// ## Arguments
+    /// * `name` - Image name, e.g. `notepad.exe`. Nt path is derived from it.
+    ///
+    /// ## Remarks
+    /// - Id, token and directory bases are assigned by [`SimKernel::add_process`](crate::SimKernel::add_process) if left as 0.
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: 0,
+            parent_id: 0,
+            name: name.into(),
+            nt_path: format!("\\Device\\HarddiskVolume3\\Windows\\System32\\{}", name),
+            protection: ProcessProtection::new(),
+            signers: ProcessSignatureLevels::new(),
+            mitigations: MitigationOptions::new(),
+            mitigations3: MitigationOptions3::new(),
+            token: 0,
+            threads: Vec::new(),
+            directory_table_base: 0,
+            user_directory_table_base: 0,
+            create_time: 0,
+            handles: BTreeMap::new(),
+            page_attributes: BTreeMap::new(),
+            exit_status: None,
+            memory: BTreeMap::new(),
+            vads: BTreeMap::new(),
+            modules: Vec::new(),
+            command_line: String::new(),
+            current_directory: String::new(),
+            window_title: String::new(),
+            environment: Vec::new(),
+        }
+    }
+}
+
+///
+/// # Sim Vad
+///
+/// Simulated `_MMVAD`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SimVad {
+    pub start_vpn: u64,
+    /// Inclusive.
+    pub end_vpn: u64,
+    pub commit_charge: u64,
+    /// `MM_*` protection.
+    pub protection: u8,
+    pub vad_type: VadType,
+    pub private: bool,
+    pub file_name: Option<String>,
+}
+
+///
+/// # Sim Module
+///
+/// Simulated `LDR_DATA_TABLE_ENTRY`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SimModule {
+    pub base: u64,
+    pub size: u32,
+    pub entry_point: u64,
+    pub path: String,
+    /// In the 32-bit PEB.
+    pub wow64: bool,
+}
+
+///
+/// # Sim Thread
+///
+/// Simulated `_ETHREAD`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SimThread {
+    pub id: u32,
+    pub process_id: u32,
+    pub impersonating: bool,
+    /// Kernel address of the impersonation token. 0 if none.
+    pub adjusted_client_token: SimAddress,
+    /// Runs only when 0.
+    pub suspend_count: u32,
+    /// User mode registers.
+    pub context: ThreadContext,
+}
+
+///
+/// # Sim Token
+///
+/// Simulated `_TOKEN`.
+///
+#[derive(Debug, Clone)]
+pub struct SimToken {
+    pub source_name: [u8; 8],
+    pub account_name: String,
+    pub token_type: TokenType,
+    pub integrity_level_index: u32,
+    pub mandatory_policy: u32,
+    pub impersonation_level: ImpersonationLevel,
+    pub present_privileges: TokenPrivilege,
+    pub enabled_privileges: TokenPrivilege,
+    pub enabled_by_default_privileges: TokenPrivilege,
+}
+
+impl SimToken {
+    ///
+    /// # User
+    ///
+    /// A primary token with the privileges a normal user gets.
+    ///
+    pub fn user(account_name: &str) -> Self {
+        let privileges = TokenPrivilege::SeChangeNotifyPrivilege
+            | TokenPrivilege::SeShutdownPrivilege
+            | TokenPrivilege::SeUndockPrivilege
+            | TokenPrivilege::SeIncreaseWorkingSetPrivilege
+            | TokenPrivilege::SeTimeZonePrivilege;
+
+        Self {
+            source_name: *b"User32  ",
+            account_name: account_name.into(),
+            token_type: TokenType::Primary,
+            integrity_level_index: 1,
+            mandatory_policy: 3,
+            impersonation_level: ImpersonationLevel::Anonymous,
+            present_privileges: privileges,
+            enabled_privileges: TokenPrivilege::SeChangeNotifyPrivilege,
+            enabled_by_default_privileges: TokenPrivilege::SeChangeNotifyPrivilege,
+        }
+    }
+
+    ///
+    /// # System
+    ///
+    /// The token of the `System` process. Everything present, almost everything enabled.
+    ///
+    pub fn system() -> Self {
+        let privileges = TokenPrivilege::from_bits_truncate(u64::MAX);
+
+        Self {
+            source_name: *b"*SYSTEM*",
+            account_name: "SYSTEM".into(),
+            token_type: TokenType::Primary,
+            integrity_level_index: 4,
+            mandatory_policy: 1,
+            impersonation_level: ImpersonationLevel::Anonymous,
+            present_privileges: privileges,
+            enabled_privileges: privileges,
+            enabled_by_default_privileges: privileges,
+        }
+    }
+}
+
+///
+/// # Sim Handle
+///
+/// Simulated `_HANDLE_TABLE_ENTRY`.
+///
+#[derive(Debug, Clone, Default)]
+pub struct SimHandle {
+    /// Kernel address of the object body. Its type comes from which of the kernel's maps has it.
+    pub object: SimAddress,
+    pub granted_access: u32,
+    pub attributes: HandleAttributes,
+    /// What `ObQueryNameString` would say. Processes and threads have none.
+    pub name: Option<String>,
+}
+
+///
+/// # Sim Rmd
+///
+/// Simulated raw memory descriptor.
+///
+#[derive(Debug)]
+pub struct SimRmd {
+    pub pa: u64,
+    pub size: u32,
+    pub memory_type: MemoryType,
+    pub memory: Option<SharedMemory>,
+    /// Process address and virtual address of each mapping.
+    pub mappings: Vec<(SimAddress, u64)>,
+}
+
+///
+/// # Sim Callback
+///
+/// A registered notify handler.
+///
+#[derive(Debug, Clone)]
+pub struct SimCallback {
+    pub target: ObjectType,
+    pub event_handle: u64,
+    pub memory: u64,
+}
+
+///
+/// # Sim Async Work
+///
+/// A slow call waiting to be completed. See [`SimKernel::run_async`](crate::SimKernel::run_async).
+///
+#[derive(Debug, Clone)]
+pub struct SimAsyncWork {
+    pub cookie: AsyncCookie,
+    pub request: HxRequest,
+    pub event: u64,
+    pub completion: u64,
+}
+
+///
+/// # Sim Fault
+///
+/// A call to fail. See [`SimKernel::fail_call`](crate::SimKernel::fail_call).
+///
+#[derive(Debug, Clone)]
+pub(crate) struct SimFault {
+    pub function: ServiceFunction,
+    pub skip: usize,
+    pub response: HxResponse,
+}
+
+///
+/// # Object Tracker
+///
+/// Objects the caller has opened. Mirrors the driver's `ObjectTracker`, including its quirks:
+/// objects are indices into vectors, so closing one shifts the indices of the ones opened after it.
+///
+#[derive(Debug, Default)]
+pub struct ObjectTracker {
+    pub callbacks: Vec<SimCallback>,
+    pub threads: Vec<SimAddress>,
+    pub tokens: Vec<SimAddress>,
+    pub processes: Vec<SimAddress>,
+    pub rmds: Vec<SimRmd>,
+}
+
+impl ObjectTracker {
+    pub fn get_open_process(&self, process: ProcessObject) -> Option<SimAddress> {
+        self.processes.get(process as usize).copied()
+    }
+
+    pub fn get_open_thread(&self, thread: ThreadObject) -> Option<SimAddress> {
+        self.threads.get(thread as usize).copied()
+    }
+
+    pub fn get_open_token(&self, token: TokenObject) -> Option<SimAddress> {
+        self.tokens.get(token as usize).copied()
+    }
+
+    pub fn get_rmd(&mut self, rmd: RmdObject) -> Option<&mut SimRmd> {
+        self.rmds.get_mut(rmd as usize)
+    }
+
+    pub fn add_open_process(&mut self, process: SimAddress) -> ProcessObject {
+        self.processes.push(process);
+        (self.processes.len() - 1) as _
+    }
+
+    pub fn add_open_thread(&mut self, thread: SimAddress) -> ThreadObject {
+        self.threads.push(thread);
+        (self.threads.len() - 1) as _
+    }
+
+    pub fn add_open_token(&mut self, token: SimAddress) -> TokenObject {
+        self.tokens.push(token);
+        (self.tokens.len() - 1) as _
+    }
+
+    pub fn add_rmd(&mut self, rmd: SimRmd) -> RmdObject {
+        self.rmds.push(rmd);
+        (self.rmds.len() - 1) as _
+    }
+
+    pub fn add_callback(&mut self, callback: SimCallback) -> CallbackObject {
+        self.callbacks.push(callback);
+        (self.callbacks.len() - 1) as _
+    }
+
+    pub fn pop_open_process(&mut self, process: ProcessObject) -> Option<SimAddress> {
+        pop(&mut self.processes, process)
+    }
+
+    pub fn pop_open_thread(&mut self, thread: ThreadObject) -> Option<SimAddress> {
+        pop(&mut self.threads, thread)
+    }
+
+    pub fn pop_open_token(&mut self, token: TokenObject) -> Option<SimAddress> {
+        pop(&mut self.tokens, token)
+    }
+
+    pub fn pop_rmd(&mut self, rmd: RmdObject) -> Option<SimRmd> {
+        pop(&mut self.rmds, rmd)
+    }
+
+    pub fn pop_open_callback(&mut self, callback: CallbackObject) -> Option<SimCallback> {
+        pop(&mut self.callbacks, callback)
+    }
+}
+
+fn pop<T>(vec: &mut Vec<T>, index: u64) -> Option<T> {
+    if (index as usize) < vec.len() {
+        Some(vec.remove(index as usize))
+    } else {
+        None
+    }
+}
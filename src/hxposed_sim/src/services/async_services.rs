@@ -0,0 +1,37 @@
+use crate::SimKernel;
+use crate::objects::SimAsyncWork;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::requests::async_call::AsyncInfo;
+use hxposed_core::hxposed::responses::async_call::AsyncResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+pub(crate) fn queue(kernel: &mut SimKernel, request: &HxRequest) -> HxResponse {
+    let info = AsyncInfo::from_raw(request.extended_arg4);
+
+    if info.event == 0 {
+        return HxResponse::not_found_what(NotFoundReason::Event);
+    }
+
+    if info.completion == 0 {
+        return HxResponse::invalid_params(1);
+    }
+
+    let cookie = kernel.next_cookie();
+
+    let mut request = request.clone();
+    request.call.set_is_slow(false);
+
+    kernel.pending.push_back(SimAsyncWork {
+        cookie,
+        request,
+        event: info.event,
+        completion: info.completion,
+    });
+
+    if !kernel.defer_async {
+        kernel.run_async();
+    }
+
+    AsyncResponse { cookie }.into_raw()
+}
@@ -0,0 +1,45 @@
+use crate::SimKernel;
+use crate::services::{dispatch, is_user_range};
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::requests::HxRequest;
+use hxposed_core::hxposed::requests::batch::BatchRequest;
+use hxposed_core::hxposed::responses::batch::BatchResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+pub(crate) fn batch(kernel: &mut SimKernel, request: BatchRequest) -> HxResponse {
+    if request.count > BatchRequest::MAX_COUNT {
+        return HxResponse::invalid_params(2);
+    }
+
+    // no SEH here. null is the only unmapped pointer we can catch.
+    if (request.count != 0 && request.requests == 0)
+        || !is_user_range::<HxRequest>(request.requests, request.count as _)
+    {
+        return HxResponse::invalid_params(0);
+    }
+    if (request.count != 0 && request.responses == 0)
+        || !is_user_range::<HxResponse>(request.responses, request.count as _)
+    {
+        return HxResponse::invalid_params(1);
+    }
+
+    let requests = request.requests as *const HxRequest;
+    let responses = request.responses as *mut HxResponse;
+
+    for i in 0..request.count as usize {
+        let entry = unsafe { requests.add(i).read_unaligned() };
+
+        let result = match entry.call.func() {
+            _ if entry.call.is_slow() => HxResponse::invalid_params(0),
+            ServiceFunction::Batch => HxResponse::invalid_params(0),
+            _ => dispatch(kernel, &entry),
+        };
+
+        unsafe { responses.add(i).write_unaligned(result) };
+    }
+
+    BatchResponse {
+        completed: request.count,
+    }
+    .into_raw()
+}
@@ -0,0 +1,46 @@
+use crate::SimKernel;
+use crate::objects::SimCallback;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::notify::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::notify::RegisterNotifyHandlerResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+pub(crate) fn register_callback_receiver(
+    kernel: &mut SimKernel,
+    request: RegisterNotifyHandlerRequest,
+) -> HxResponse {
+    match request.target_object {
+        ObjectType::Process(_) => {}
+        ObjectType::Thread(_) => {}
+        _ => return HxResponse::invalid_params(0),
+    }
+
+    if request.event_handle == 0 {
+        return HxResponse::not_found_what(NotFoundReason::Event);
+    }
+
+    if request.memory == 0 {
+        return HxResponse::invalid_params(1);
+    }
+
+    RegisterNotifyHandlerResponse {
+        callback: kernel.tracker.add_callback(SimCallback {
+            target: request.target_object,
+            event_handle: request.event_handle,
+            memory: request.memory,
+        }),
+    }
+    .into_raw()
+}
+
+pub(crate) fn unregister_callback_receiver(
+    kernel: &mut SimKernel,
+    request: UnregisterNotifyHandlerRequest,
+) -> HxResponse {
+    match kernel.tracker.pop_open_callback(request.callback) {
+        None => HxResponse::not_found_what(NotFoundReason::Callback),
+        Some(_) => EmptyResponse::default(),
+    }
+}
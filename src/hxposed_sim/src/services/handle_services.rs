@@ -0,0 +1,191 @@
+use crate::SimKernel;
+use crate::objects::SimHandle;
+use crate::services::output_services::write_output;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::OutputSlice;
+use hxposed_core::hxposed::requests::handle::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::handle::{
+    CreateHandleResponse, EnumerateHandlesResponse, GetHandleObjectResponse, HandleEntry,
+};
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+use hxposed_core::hxposed::{Handle, ObjectType, ProcessObject};
+
+fn get_handle_entry(
+    kernel: &mut SimKernel,
+    process: ProcessObject,
+    handle: Handle,
+) -> Result<&mut SimHandle, HxResponse> {
+    let process = match kernel
+        .tracker
+        .get_open_process(process)
+        .and_then(|x| kernel.processes.get_mut(&x))
+    {
+        None => return Err(HxResponse::not_found_what(NotFoundReason::Process)),
+        Some(x) => x,
+    };
+
+    process
+        .handles
+        .get_mut(&handle)
+        .ok_or(HxResponse::not_found_what(NotFoundReason::Handle))
+}
+
+pub(crate) fn upgrade_handle(kernel: &mut SimKernel, request: UpgradeHandleRequest) -> HxResponse {
+    match get_handle_entry(kernel, request.process, request.handle) {
+        Err(x) => x,
+        Ok(entry) => {
+            entry.granted_access = request.access_rights;
+            EmptyResponse::default()
+        }
+    }
+}
+
+pub(crate) fn swap_handle_obj(kernel: &mut SimKernel, request: SwapHandleObjectRequest) -> HxResponse {
+    let current = match get_handle_entry(kernel, request.process, request.handle) {
+        Err(x) => return x,
+        Ok(entry) => entry.object,
+    };
+
+    // objects the sim doesn't model are garbage to it.
+    let object_type = kernel.object_type_name(request.object);
+    if object_type.is_empty() || (!request.force && object_type != kernel.object_type_name(current)) {
+        return HxResponse::not_allowed(NotAllowedReason::TypeMismatch);
+    }
+
+    get_handle_entry(kernel, request.process, request.handle)
+        .unwrap()
+        .object = request.object;
+    EmptyResponse::default()
+}
+
+pub(crate) fn downgrade_handle(kernel: &mut SimKernel, request: DowngradeHandleRequest) -> HxResponse {
+    match get_handle_entry(kernel, request.process, request.handle) {
+        Err(x) => x,
+        Ok(entry) => {
+            entry.granted_access &= request.access_rights;
+            EmptyResponse::default()
+        }
+    }
+}
+
+pub(crate) fn close_remote_handle(kernel: &mut SimKernel, request: CloseRemoteHandleRequest) -> HxResponse {
+    let process = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get_mut(&x))
+    {
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+        Some(x) => x,
+    };
+
+    match process.handles.get(&request.handle) {
+        None => HxResponse::not_found_what(NotFoundReason::Handle),
+        Some(x) if x.attributes.protect_from_close() => {
+            HxResponse::not_allowed(NotAllowedReason::HandleProtected)
+        }
+        Some(_) => {
+            process.handles.remove(&request.handle);
+            EmptyResponse::default()
+        }
+    }
+}
+
+pub(crate) fn get_handle_obj(kernel: &mut SimKernel, request: GetHandleObjectRequest) -> HxResponse {
+    match get_handle_entry(kernel, request.process, request.handle) {
+        Err(x) => x,
+        Ok(entry) => GetHandleObjectResponse {
+            object: entry.object,
+            granted_access: entry.granted_access,
+        }
+        .into_raw(),
+    }
+}
+
+pub(crate) fn enumerate_handles(kernel: &mut SimKernel, request: EnumerateHandlesRequest) -> HxResponse {
+    if !kernel.on_worker {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let process = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // same layout as the driver. entries, then type names and names.
+    let entries_size = process.handles.len() * size_of::<HandleEntry>();
+    let mut strings = Vec::<u8>::new();
+    let mut push_string = |string: &str| {
+        let start = strings.len();
+        strings.extend(string.encode_utf16().flat_map(|x| x.to_le_bytes()));
+        OutputSlice::new()
+            .with_offset((entries_size + start) as _)
+            .with_length((strings.len() - start) as _)
+    };
+
+    let entries = process
+        .handles
+        .iter()
+        .map(|(value, handle)| HandleEntry {
+            handle: *value,
+            object: handle.object,
+            type_name: push_string(kernel.object_type_name(handle.object)),
+            name: push_string(handle.name.as_deref().unwrap_or_default()),
+            granted_access: handle.granted_access,
+            attributes: handle.attributes,
+            reserved: [0; 3],
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + strings.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&strings);
+
+    match write_output(&blob) {
+        Ok(handles) => EnumerateHandlesResponse {
+            handles,
+            count: entries.len() as _,
+        }
+        .into_raw(),
+        Err(e) => e,
+    }
+}
+
+pub(crate) fn create_handle(kernel: &mut SimKernel, request: CreateHandleRequest) -> HxResponse {
+    let object = match request.object {
+        ObjectType::Process(x) => match kernel.tracker.get_open_process(x) {
+            Some(x) => x,
+            None => return HxResponse::not_found_what(NotFoundReason::Process),
+        },
+        ObjectType::Thread(x) => match kernel.tracker.get_open_thread(x) {
+            Some(x) => x,
+            None => return HxResponse::not_found_what(NotFoundReason::Thread),
+        },
+        ObjectType::Token(x) => match kernel.tracker.get_open_token(x) {
+            Some(x) => x,
+            None => return HxResponse::not_found_what(NotFoundReason::Token),
+        },
+        _ => return HxResponse::invalid_params(0),
+    };
+
+    // always the caller's table. the driver opens it in the caller's context.
+    let caller = kernel.caller().id;
+    let handle = kernel
+        .add_handle(
+            caller,
+            SimHandle {
+                object,
+                granted_access: request.access_rights,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    CreateHandleResponse { handle }.into_raw()
+}
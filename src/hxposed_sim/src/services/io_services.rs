@@ -0,0 +1,50 @@
+use crate::SimKernel;
+use hxposed_core::hxposed::error::NotAllowedReason;
+use hxposed_core::hxposed::requests::io::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::io::*;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+/// Base of the descriptor tables reported by `sgdt`/`sidt`.
+const GDT_BASE: u64 = 0xFFFF_F800_0000_1000;
+const IDT_BASE: u64 = 0xFFFF_F800_0000_2000;
+
+pub(crate) fn rw_msr(kernel: &mut SimKernel, request: MsrIoRequest) -> HxResponse {
+    // MSRs nobody set up don't exist, so reads and writes #GP like they would on hardware.
+    match request.operation {
+        MsrOperation::Read => match kernel.msr(request.msr) {
+            Some(value) => MsrIoResponse { value }.into_raw(),
+            None => HxResponse::not_allowed(NotAllowedReason::AccessViolation),
+        },
+        MsrOperation::Write => match kernel.msrs.get_mut(&request.msr) {
+            Some(value) => {
+                *value = request.value;
+                EmptyResponse::default()
+            }
+            None => HxResponse::not_allowed(NotAllowedReason::AccessViolation),
+        },
+        MsrOperation::Unknown => HxResponse::invalid_params(0),
+    }
+}
+
+pub(crate) fn exec_privileged(
+    kernel: &mut SimKernel,
+    request: PrivilegedInstructionRequest,
+) -> HxResponse {
+    let instruction = match request.instruction {
+        PrivilegedInstruction::MovToCr8(cr8) => {
+            kernel.cr8 = cr8;
+            return EmptyResponse::default();
+        }
+        PrivilegedInstruction::MovFromCr8(_) => PrivilegedInstruction::MovFromCr8(kernel.cr8),
+        PrivilegedInstruction::Sgdt(_) => PrivilegedInstruction::Sgdt(GDT_BASE),
+        PrivilegedInstruction::Sidt(_) => PrivilegedInstruction::Sidt(IDT_BASE),
+        PrivilegedInstruction::Hlt
+        | PrivilegedInstruction::Lgdt(_)
+        | PrivilegedInstruction::Lidt(_)
+        | PrivilegedInstruction::MovToRFlags(_) => return EmptyResponse::default(),
+        _ => return HxResponse::invalid_params(0),
+    };
+
+    PrivilegedInstructionResponse { instruction }.into_raw()
+}
@@ -0,0 +1,339 @@
+use crate::SimKernel;
+use crate::memory::SharedMemory;
+use crate::objects::SimRmd;
+use crate::services::is_user_range;
+use crate::services::output_services::write_output;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::OutputSlice;
+use hxposed_core::hxposed::requests::memory::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::memory::*;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+
+pub(crate) fn get_set_page_attribute(
+    kernel: &mut SimKernel,
+    request: PageAttributeRequest,
+) -> HxResponse {
+    let (level, va) = match request.paging_type {
+        PagingType::Unknown => return HxResponse::invalid_params(0),
+        PagingType::Pml5(_) => return HxResponse::invalid_params(0),
+        x => x.into_raw_enum(),
+    };
+
+    let process = match kernel
+        .tracker
+        .get_open_process(request.addr_space)
+        .and_then(|x| kernel.processes.get_mut(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let key = (level, va & !0xFFF);
+    match request.operation {
+        PageAttributeOperation::Set => {
+            process.page_attributes.insert(key, request.type_bits);
+            EmptyResponse::default()
+        }
+        PageAttributeOperation::Get => match process.page_attributes.get(&key) {
+            None => HxResponse::not_found_what(NotFoundReason::Mdl),
+            Some(bits) => PageAttributeResponse { type_bits: *bits }.into_raw(),
+        },
+    }
+}
+
+pub(crate) fn translate_address(
+    kernel: &mut SimKernel,
+    request: TranslateAddressRequest,
+) -> HxResponse {
+    let process = match kernel.tracker.get_open_process(request.addr_space) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // only RMD mappings have page tables we know about.
+    kernel
+        .tracker
+        .rmds
+        .iter()
+        .find_map(|rmd| {
+            rmd.mappings
+                .iter()
+                .find(|(p, va)| {
+                    *p == process
+                        && (*va..*va + rmd.size as u64).contains(&request.virtual_addr)
+                })
+                .map(|(_, va)| rmd.pa + (request.virtual_addr - va))
+        })
+        .map_or(HxResponse::invalid_params(1), |physical_addr| {
+            TranslateAddressResponse { physical_addr }.into_raw()
+        })
+}
+
+pub(crate) fn map_va_to_pa(kernel: &mut SimKernel, request: MapRmdRequest) -> HxResponse {
+    let process = match kernel.tracker.get_open_process(request.addr_space) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+    let is_caller = process == kernel.caller;
+
+    let rmd = match kernel.tracker.get_rmd(request.object) {
+        None => return HxResponse::not_found_what(NotFoundReason::Mdl),
+        Some(x) => x,
+    };
+
+    let position = rmd
+        .mappings
+        .iter()
+        .position(|x| *x == (process, request.map_addr));
+
+    match request.operation {
+        MapOperation::Map => {
+            if position.is_some() {
+                return HxResponse::not_allowed(NotAllowedReason::MappingsExist);
+            }
+
+            // only the caller's address space is real.
+            if is_caller {
+                let memory = match &rmd.memory {
+                    Some(x) => x,
+                    None => return HxResponse::not_allowed(NotAllowedReason::PageNotPresent),
+                };
+
+                if memory.map(request.map_addr).is_err() {
+                    return HxResponse::not_allowed(NotAllowedReason::MappingsExist);
+                }
+            }
+
+            rmd.mappings.push((process, request.map_addr));
+            EmptyResponse::default()
+        }
+        MapOperation::Unmap => match position {
+            None => HxResponse::not_found_what(NotFoundReason::Mdl),
+            Some(x) => {
+                rmd.mappings.remove(x);
+                if let (true, Some(memory)) = (is_caller, &rmd.memory) {
+                    memory.unmap(request.map_addr);
+                }
+                EmptyResponse::default()
+            }
+        },
+    }
+}
+
+pub(crate) fn describe_memory(kernel: &mut SimKernel, request: DescribeMemoryRequest) -> HxResponse {
+    // there is no physical memory to describe. contents are zero, and not shared with anything.
+    let memory = SharedMemory::new(request.size).ok();
+    let rmd = kernel.tracker.add_rmd(SimRmd {
+        pa: request.pa,
+        size: request.size,
+        memory_type: MemoryType::NonOwned,
+        memory,
+        mappings: Vec::new(),
+    });
+
+    DescribeMemoryResponse { rmd }.into_raw()
+}
+
+pub(crate) fn allocate_memory(kernel: &mut SimKernel, request: AllocateMemoryRequest) -> HxResponse {
+    match request.memory_type {
+        MemoryType::NonPagedPool | MemoryType::ContiguousPhysical => {}
+        _ => return HxResponse::invalid_params(1),
+    }
+
+    let memory = match SharedMemory::new(request.size) {
+        Ok(x) => x,
+        // STATUS_INSUFFICIENT_RESOURCES
+        Err(_) => return HxResponse::nt_error(0xC000009A),
+    };
+
+    let pa = kernel.next_pa();
+    // keep physical addresses apart like real allocations would be.
+    for _ in 1..memory.size() / SharedMemory::PAGE_SIZE {
+        kernel.next_pa();
+    }
+
+    let rmd = kernel.tracker.add_rmd(SimRmd {
+        pa,
+        size: memory.size() as _,
+        memory_type: request.memory_type,
+        memory: Some(memory),
+        mappings: Vec::new(),
+    });
+
+    AllocateMemoryResponse { rmd }.into_raw()
+}
+
+pub(crate) fn free_memory(kernel: &mut SimKernel, request: FreeMemoryRequest) -> HxResponse {
+    match kernel.tracker.get_rmd(request.obj) {
+        None => HxResponse::not_found_what(NotFoundReason::Mdl),
+        Some(x) if !x.mappings.is_empty() => {
+            HxResponse::not_allowed(NotAllowedReason::MappingsExist)
+        }
+        Some(_) => {
+            kernel.tracker.pop_rmd(request.obj);
+            EmptyResponse::default()
+        }
+    }
+}
+
+pub(crate) fn read_virtual_memory(
+    kernel: &mut SimKernel,
+    request: ReadVirtualMemoryRequest,
+) -> HxResponse {
+    copy_virtual_memory(
+        kernel,
+        request.addr_space,
+        request.address,
+        request.buffer,
+        request.size,
+        false,
+    )
+}
+
+pub(crate) fn write_virtual_memory(
+    kernel: &mut SimKernel,
+    request: WriteVirtualMemoryRequest,
+) -> HxResponse {
+    copy_virtual_memory(
+        kernel,
+        request.addr_space,
+        request.address,
+        request.buffer,
+        request.size,
+        true,
+    )
+}
+
+/// The caller's memory is ours, so that's a plain copy.
+/// Anyone else's is [`SimProcess::memory`](crate::SimProcess::memory), and the copy stops where a region ends.
+fn copy_virtual_memory(
+    kernel: &mut SimKernel,
+    addr_space: u64,
+    address: u64,
+    buffer: u64,
+    size: u32,
+    write: bool,
+) -> HxResponse {
+    // same checks as the driver. kernel memory is not the caller's to copy.
+    if !is_user_range::<u8>(address, size as _) {
+        return HxResponse::invalid_params(1);
+    }
+    if !is_user_range::<u8>(buffer, size as _) {
+        return HxResponse::invalid_params(2);
+    }
+
+    let id = match kernel.tracker.get_open_process(addr_space) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let local = buffer as *mut u8;
+    if id == kernel.caller {
+        unsafe {
+            match write {
+                true => core::ptr::copy(local, address as *mut u8, size as _),
+                false => core::ptr::copy(address as *const u8, local, size as _),
+            }
+        }
+        return VirtualMemoryResponse { bytes_copied: size }.into_raw();
+    }
+
+    let process = match kernel.processes.get_mut(&id) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let region = process
+        .memory
+        .range_mut(..=address)
+        .next_back()
+        .filter(|(base, region)| address < **base + region.len() as u64);
+
+    let copied = match region {
+        None => 0,
+        Some((base, region)) => {
+            let offset = (address - base) as usize;
+            let count = (region.len() - offset).min(size as usize);
+            let remote = &mut region[offset..offset + count];
+            unsafe {
+                match write {
+                    true => core::ptr::copy_nonoverlapping(local, remote.as_mut_ptr(), count),
+                    false => core::ptr::copy_nonoverlapping(remote.as_ptr(), local, count),
+                }
+            }
+            count as u32
+        }
+    };
+
+    VirtualMemoryResponse {
+        bytes_copied: copied,
+    }
+    .into_raw()
+}
+
+pub(crate) fn enumerate_memory_regions(
+    kernel: &mut SimKernel,
+    request: EnumerateMemoryRegionsRequest,
+) -> HxResponse {
+    if !kernel.on_worker {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let process = match kernel
+        .tracker
+        .get_open_process(request.addr_space)
+        .and_then(|x| kernel.processes.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // same layout as the driver. entries, then names.
+    let entries_size = process.vads.len() * size_of::<MemoryRegionEntry>();
+    let mut names = Vec::<u8>::new();
+    let entries = process
+        .vads
+        .values()
+        .map(|vad| {
+            let file_name = match &vad.file_name {
+                None => OutputSlice::new(),
+                Some(name) => {
+                    let start = names.len();
+                    names.extend(name.encode_utf16().flat_map(|x| x.to_le_bytes()));
+                    OutputSlice::new()
+                        .with_offset((entries_size + start) as _)
+                        .with_length((names.len() - start) as _)
+                }
+            };
+
+            MemoryRegionEntry {
+                start_vpn: vad.start_vpn,
+                end_vpn: vad.end_vpn,
+                commit_charge: vad.commit_charge,
+                file_name,
+                protection: vad.protection,
+                vad_type: vad.vad_type.into_bits(),
+                private: vad.private as _,
+                reserved: [0; 5],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + names.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&names);
+
+    match write_output(&blob) {
+        Ok(regions) => MemoryRegionsResponse {
+            regions,
+            count: entries.len() as _,
+            // a map, not a tree. nothing to give up on.
+            truncated: false,
+        }
+        .into_raw(),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,113 @@
+use crate::SimKernel;
+use hxposed_core::hx_services;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::status::StatusRequest;
+use hxposed_core::hxposed::requests::{HxRequest, SyscallRequest};
+use hxposed_core::hxposed::responses::status::StatusResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+use hxposed_core::hxposed::status::{Capabilities, HypervisorStatus, PROTOCOL_VERSION};
+
+pub mod async_services;
+pub mod batch_services;
+pub mod callback_services;
+pub mod handle_services;
+pub mod io_services;
+pub mod memory_services;
+pub mod output_services;
+pub mod process_services;
+pub mod security_services;
+pub mod thread_services;
+
+pub type SimHandler = fn(&mut SimKernel, &HxRequest) -> HxResponse;
+const INV: SimHandler = invalid_handler;
+fn invalid_handler(_kernel: &mut SimKernel, _req: &HxRequest) -> HxResponse {
+    HxResponse::not_found_what(NotFoundReason::ServiceFunction)
+}
+
+fn get_state(_kernel: &mut SimKernel, _request: StatusRequest) -> HxResponse {
+    StatusResponse {
+        state: HypervisorStatus::SystemVirtualized,
+        version: PROTOCOL_VERSION,
+        capabilities: capabilities(),
+    }
+    .into_raw()
+}
+
+/// Same limit the driver holds user pointers to.
+pub(crate) const USER_LIMIT: u64 = 0x7FFF_FFFF_0000;
+
+/// `count` of `T` at `address`, all below [`USER_LIMIT`]. Same check as the driver's.
+pub(crate) fn is_user_range<T>(address: u64, count: usize) -> bool {
+    count
+        .checked_mul(size_of::<T>())
+        .and_then(|size| address.checked_add(size as u64))
+        .is_some_and(|end| end <= USER_LIMIT)
+}
+
+const DISPATCH_TABLE_MAX: usize = 8;
+
+// same registry as the driver. handlers take the kernel first.
+macro_rules! dispatch_table {
+    ($(
+        $name:ident = $id:literal $(=> $ctor:ident($req:ty) -> $resp:ty, $handler:path, $($perm:ident)|+)?;
+    )*) => {
+        static DISPATCH_TABLE: [[SimHandler; 16]; DISPATCH_TABLE_MAX] = {
+            let mut table = [[INV; 16]; DISPATCH_TABLE_MAX];
+            $($(
+                table[$id >> 4][$id & 0xF] =
+                    |k, x| $handler(k, <$req as SyscallRequest>::from_raw(x));
+            )?)*
+            table
+        };
+    };
+}
+
+hx_services!(dispatch_table);
+
+/// Same as the driver. Every slot that isn't [`INV`] counts as implemented.
+fn capabilities() -> Capabilities {
+    let mut capabilities = Capabilities::default();
+
+    for (category, row) in DISPATCH_TABLE.iter().enumerate() {
+        for (func, handler) in row.iter().enumerate() {
+            if !core::ptr::fn_addr_eq(*handler, INV) {
+                capabilities.set(category, func);
+            }
+        }
+    }
+
+    capabilities
+}
+
+pub(crate) fn dispatch(kernel: &mut SimKernel, request: &HxRequest) -> HxResponse {
+    // don't go through HxCall::func(). unknown ids must not become a ServiceFunction.
+    let function = (request.call.into_bits() & 0xFFFF) as usize;
+    const CATEGORY_MASK: usize = 0xF0;
+    const FUNCTION_MASK: usize = 0x0F;
+
+    let category = (function & CATEGORY_MASK) >> 4;
+    let func = function & FUNCTION_MASK;
+
+    if function > 0xFF || category >= DISPATCH_TABLE_MAX {
+        return HxResponse::not_found_what(NotFoundReason::ServiceFunction);
+    }
+
+    // before anything else, like syscall_handler. batches come through here too.
+    let result = match kernel
+        .permissions
+        .require(request.call.func().permissions())
+    {
+        Err(e) => HxResponse::not_allowed(e),
+        Ok(_) => match kernel.take_fault(request.call.func()) {
+            Some(response) => response,
+            None if request.call.is_slow() => async_services::queue(kernel, request),
+            None => DISPATCH_TABLE[category][func](kernel, request),
+        },
+    };
+
+    // the driver leaves the registers alone. an empty response is the closest we get.
+    match request.call.ignore_result() {
+        true => HxResponse::default(),
+        false => result,
+    }
+}
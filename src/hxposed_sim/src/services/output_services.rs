@@ -0,0 +1,124 @@
+use crate::SimKernel;
+use crate::memory::SharedMemory;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::{OutputBufferHeader, OutputSlice};
+use hxposed_core::hxposed::requests::output::RegisterOutputBufferRequest;
+use hxposed_core::hxposed::responses::output::RegisterOutputBufferResponse;
+use hxposed_core::hxposed::responses::{HxResponse, SyscallResponse};
+use std::sync::Mutex;
+
+struct SimOutputBuffer {
+    // dropping this leaves the mapping alone. replaced buffers stay mapped, like in the driver.
+    _memory: SharedMemory,
+    address: u64,
+    // our copy. the one in the buffer is only a mirror, like in the driver.
+    header: OutputBufferHeader,
+}
+
+// the driver keeps this per process. the caller is this very process in every world,
+// and core registers once per process, so the worlds share it.
+static OUTPUT_BUFFER: Mutex<Option<SimOutputBuffer>> = Mutex::new(None);
+
+pub(crate) fn register_output_buffer(
+    _kernel: &mut SimKernel,
+    request: RegisterOutputBufferRequest,
+) -> HxResponse {
+    if request.size > OutputBufferHeader::MAX_SIZE {
+        return HxResponse::invalid_params(0);
+    }
+
+    let mut output = OUTPUT_BUFFER.lock().unwrap();
+
+    // the one we have is big enough. just empty it.
+    if let Some(x) = output.as_mut().filter(|x| request.size <= x.header.size) {
+        x.header = OutputBufferHeader::new(x.header.size);
+        unsafe {
+            (x.address as *mut OutputBufferHeader).write_volatile(x.header);
+        }
+
+        return RegisterOutputBufferResponse {
+            address: x.address,
+            size: x.header.size,
+        }
+        .into_raw();
+    }
+
+    // STATUS_INSUFFICIENT_RESOURCES
+    let memory = match SharedMemory::new(request.size.max(OutputBufferHeader::SIZE)) {
+        Ok(x) => x,
+        Err(_) => return HxResponse::nt_error(0xC000009A),
+    };
+    let address = match memory.map_anywhere() {
+        Ok(x) => x,
+        Err(_) => return HxResponse::nt_error(0xC000009A),
+    };
+
+    let header = OutputBufferHeader::new(memory.size() as _);
+    unsafe {
+        (address as *mut OutputBufferHeader).write_volatile(header);
+    }
+
+    *output = Some(SimOutputBuffer {
+        _memory: memory,
+        address,
+        header,
+    });
+
+    RegisterOutputBufferResponse {
+        address,
+        size: header.size,
+    }
+    .into_raw()
+}
+
+///
+/// # Output Remaining
+///
+/// Bytes left in the output buffer of the caller. For results that can be cut to fit.
+///
+/// ## Return
+/// * [`u32`] - Bytes left.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has not registered one.
+pub(crate) fn output_remaining() -> Result<u32, HxResponse> {
+    match OUTPUT_BUFFER.lock().unwrap().as_ref() {
+        Some(x) => Ok(x.header.remaining()),
+        None => Err(HxResponse::not_found_what(NotFoundReason::OutputBuffer)),
+    }
+}
+
+///
+/// # Write Output
+///
+/// Writes a variable-length result to the output buffer of the caller.
+///
+/// ## Return
+/// * [`OutputSlice`] - Where the result was written.
+/// * [`HxResponse::not_found_what`] - [`NotFoundReason::OutputBuffer`]. Caller has not registered one.
+/// * [`HxResponse::not_allowed`] - [`NotAllowedReason::BufferTooSmall`]. Result does not fit.
+pub(crate) fn write_output<T: Copy>(data: &[T]) -> Result<OutputSlice, HxResponse> {
+    let mut output = OUTPUT_BUFFER.lock().unwrap();
+    let output = match output.as_mut() {
+        Some(x) => x,
+        None => return Err(HxResponse::not_found_what(NotFoundReason::OutputBuffer)),
+    };
+
+    let length = size_of_val(data);
+    let slice = match u32::try_from(length)
+        .ok()
+        .and_then(|x| output.header.reserve(x))
+    {
+        Some(x) => x,
+        None => return Err(HxResponse::not_allowed(NotAllowedReason::BufferTooSmall)),
+    };
+
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            data.as_ptr() as *const u8,
+            (output.address + slice.offset() as u64) as *mut u8,
+            length,
+        );
+        (output.address as *mut OutputBufferHeader).write_volatile(output.header);
+    }
+
+    Ok(slice)
+}
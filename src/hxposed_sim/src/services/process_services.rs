@@ -0,0 +1,329 @@
+use crate::SimKernel;
+use crate::services::output_services::{output_remaining, write_output};
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::output::OutputSlice;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::requests::process::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::process::*;
+use hxposed_core::hxposed::responses::thread::SuspendCountResponse;
+use hxposed_core::hxposed::responses::{HxResponse, OpenObjectResponse, SyscallResponse};
+
+pub(crate) fn open_process(kernel: &mut SimKernel, request: OpenProcessRequest) -> HxResponse {
+    let address = match kernel.process_address(request.process_id as _) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    OpenObjectResponse {
+        object: ObjectType::Process(kernel.tracker.add_open_process(address)),
+    }
+    .into_raw()
+}
+
+pub(crate) fn close_process(kernel: &mut SimKernel, request: CloseProcessRequest) -> HxResponse {
+    match kernel.tracker.pop_open_process(request.process) {
+        None => HxResponse::not_found_what(NotFoundReason::Process),
+        Some(_) => EmptyResponse::default(),
+    }
+}
+
+pub(crate) fn kill_process(kernel: &mut SimKernel, request: KillProcessRequest) -> HxResponse {
+    const STATUS_ACCESS_DENIED: u32 = 0xC000_0022;
+    const STATUS_PROCESS_IS_TERMINATING: u32 = 0xC000_010A;
+
+    let address = match kernel.tracker.get_open_process(request.process) {
+        Some(x) if kernel.processes.contains_key(&x) => x,
+        _ => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // nt refuses System. and the caller can't die for real, it's running the tests.
+    if address == kernel.caller || kernel.processes[&address].id == 4 {
+        return HxResponse::nt_error(STATUS_ACCESS_DENIED);
+    }
+
+    let process = kernel.processes.get_mut(&address).unwrap();
+    if process.exit_status.is_some() {
+        return HxResponse::nt_error(STATUS_PROCESS_IS_TERMINATING);
+    }
+
+    // protection doesn't matter, same as the driver.
+    process.exit_status = Some(request.exit_code);
+
+    EmptyResponse::default()
+}
+
+pub(crate) fn suspend_process(kernel: &mut SimKernel, request: SuspendProcessRequest) -> HxResponse {
+    const STATUS_PROCESS_IS_TERMINATING: u32 = 0xC000_010A;
+    const STATUS_SUSPEND_COUNT_EXCEEDED: u32 = 0xC000_004A;
+    const MAXIMUM_SUSPEND_COUNT: u32 = 0x7F;
+
+    let address = match kernel.tracker.get_open_process(request.process) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+    if address == kernel.caller {
+        return HxResponse::not_allowed(NotAllowedReason::CurrentProcess);
+    }
+
+    let process = match kernel.processes.get(&address) {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+    if process.exit_status.is_some() {
+        return HxResponse::nt_error(STATUS_PROCESS_IS_TERMINATING);
+    }
+
+    // the driver goes thread by thread, and undoes it all when one fails.
+    let id = process.id;
+    if kernel
+        .threads
+        .values()
+        .any(|t| t.process_id == id && t.suspend_count == MAXIMUM_SUSPEND_COUNT)
+    {
+        return HxResponse::nt_error(STATUS_SUSPEND_COUNT_EXCEEDED);
+    }
+
+    let mut previous_count = 0;
+    kernel
+        .threads
+        .values_mut()
+        .filter(|t| t.process_id == id)
+        .for_each(|t| {
+            previous_count = previous_count.max(t.suspend_count);
+            t.suspend_count += 1;
+        });
+
+    SuspendCountResponse { previous_count }.into_raw()
+}
+
+pub(crate) fn resume_process(kernel: &mut SimKernel, request: ResumeProcessRequest) -> HxResponse {
+    let id = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get(&x))
+    {
+        Some(x) => x.id,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let mut previous_count = 0;
+    kernel
+        .threads
+        .values_mut()
+        .filter(|t| t.process_id == id)
+        .for_each(|t| {
+            previous_count = previous_count.max(t.suspend_count);
+            t.suspend_count = t.suspend_count.saturating_sub(1);
+        });
+
+    SuspendCountResponse { previous_count }.into_raw()
+}
+
+pub(crate) fn enumerate_processes(
+    kernel: &mut SimKernel,
+    _request: EnumerateProcessesRequest,
+) -> HxResponse {
+    // killed ones are off the active list, even if someone still holds them.
+    let entries = kernel
+        .processes
+        .values()
+        .filter(|x| x.exit_status.is_none())
+        .map(|process| {
+            let mut image_file_name = [0u8; 15];
+            // nt keeps the first 15 bytes.
+            for (to, from) in image_file_name.iter_mut().zip(process.name.bytes()) {
+                *to = from;
+            }
+
+            ProcessEntry {
+                id: process.id,
+                parent_id: process.parent_id,
+                create_time: process.create_time,
+                protection: process.protection,
+                signers: process.signers,
+                image_file_name,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    match write_output(&entries) {
+        Ok(processes) => EnumerateProcessesResponse { processes }.into_raw(),
+        Err(e) => e,
+    }
+}
+
+pub(crate) fn enumerate_modules(
+    kernel: &mut SimKernel,
+    request: EnumerateModulesRequest,
+) -> HxResponse {
+    if !kernel.on_worker {
+        return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+    }
+
+    let process = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    // same layout as the driver. entries, then paths.
+    let entries_size = process.modules.len() * size_of::<ModuleEntry>();
+    let mut paths = Vec::<u8>::new();
+    let entries = process
+        .modules
+        .iter()
+        .map(|module| {
+            let start = paths.len();
+            paths.extend(module.path.encode_utf16().flat_map(|x| x.to_le_bytes()));
+
+            ModuleEntry {
+                base: module.base,
+                entry_point: module.entry_point,
+                path: OutputSlice::new()
+                    .with_offset((entries_size + start) as _)
+                    .with_length((paths.len() - start) as _),
+                size: module.size,
+                wow64: module.wow64 as _,
+                reserved: [0; 3],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut blob = Vec::<u8>::with_capacity(entries_size + paths.len());
+    blob.extend_from_slice(unsafe {
+        core::slice::from_raw_parts(entries.as_ptr() as *const u8, entries_size)
+    });
+    blob.extend_from_slice(&paths);
+
+    match write_output(&blob) {
+        Ok(modules) => EnumerateModulesResponse {
+            modules,
+            count: entries.len() as _,
+        }
+        .into_raw(),
+        Err(e) => e,
+    }
+}
+
+pub(crate) fn get_process_field_sync(
+    kernel: &mut SimKernel,
+    request: GetProcessFieldRequest,
+) -> HxResponse {
+    let process = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    let mut truncated = false;
+    let field = match request.field {
+        ProcessField::NtPath(_) => {
+            let field = process.nt_path.encode_utf16().collect::<Vec<_>>();
+            match write_output(&field) {
+                Ok(slice) => ProcessField::NtPath(slice),
+                Err(e) => return e,
+            }
+        }
+        ProcessField::Protection(_) => ProcessField::Protection(process.protection),
+        ProcessField::Signers(_) => ProcessField::Signers(process.signers),
+        ProcessField::MitigationFlags(_) => ProcessField::MitigationFlags(process.mitigations),
+        ProcessField::MitigationFlags3(_) => ProcessField::MitigationFlags3(process.mitigations3),
+        ProcessField::Token(_) => ProcessField::Token(process.token),
+        ProcessField::Threads(_) => match write_output(&process.threads) {
+            Ok(slice) => ProcessField::Threads(slice),
+            Err(e) => return e,
+        },
+        ProcessField::DirectoryTableBase(_) => {
+            ProcessField::DirectoryTableBase(process.directory_table_base)
+        }
+        ProcessField::UserDirectoryTableBase(_) => {
+            ProcessField::UserDirectoryTableBase(process.user_directory_table_base)
+        }
+        ProcessField::CommandLine(_) => match write_string(&process.command_line) {
+            Ok(slice) => ProcessField::CommandLine(slice),
+            Err(e) => return e,
+        },
+        ProcessField::CurrentDirectory(_) => match write_string(&process.current_directory) {
+            Ok(slice) => ProcessField::CurrentDirectory(slice),
+            Err(e) => return e,
+        },
+        ProcessField::WindowTitle(_) => match write_string(&process.window_title) {
+            Ok(slice) => ProcessField::WindowTitle(slice),
+            Err(e) => return e,
+        },
+        ProcessField::Environment(_) => {
+            let mut block = process
+                .environment
+                .iter()
+                .map(|(name, value)| format!("{}={}\0", name, value))
+                .collect::<String>()
+                .encode_utf16()
+                .collect::<Vec<_>>();
+            block.push(0);
+
+            // same as the driver. cut to what's left, not failed.
+            let limit = match output_remaining() {
+                Ok(x) => x as usize / 2,
+                Err(e) => return e,
+            };
+            if block.len() > limit {
+                block.truncate(limit);
+                truncated = true;
+            }
+
+            match write_output(&block) {
+                Ok(slice) => ProcessField::Environment(slice),
+                Err(e) => return e,
+            }
+        }
+        ProcessField::Unknown => ProcessField::Unknown,
+    };
+
+    GetProcessFieldResponse { field, truncated }.into_raw()
+}
+
+fn write_string(string: &str) -> Result<OutputSlice, HxResponse> {
+    write_output(&string.encode_utf16().collect::<Vec<_>>())
+}
+
+pub(crate) fn set_process_field_sync(
+    kernel: &mut SimKernel,
+    request: SetProcessFieldRequest,
+) -> HxResponse {
+    let token = match request.field {
+        ProcessField::Token(token) => match kernel.tracker.get_open_token(token) {
+            Some(x) => x,
+            None => return HxResponse::not_found_what(NotFoundReason::Token),
+        },
+        _ => 0,
+    };
+
+    let process = match kernel
+        .tracker
+        .get_open_process(request.process)
+        .and_then(|x| kernel.processes.get_mut(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Process),
+    };
+
+    match request.field {
+        ProcessField::Protection(protection) => process.protection = protection,
+        ProcessField::Signers(signers) => process.signers = signers,
+        ProcessField::MitigationFlags(flags) => process.mitigations = flags,
+        ProcessField::MitigationFlags3(flags) => process.mitigations3 = flags,
+        ProcessField::DirectoryTableBase(base) => process.directory_table_base = base,
+        ProcessField::UserDirectoryTableBase(base) => process.user_directory_table_base = base,
+        ProcessField::Token(_) => process.token = token,
+        _ => return HxResponse::not_found_what(NotFoundReason::Field),
+    }
+
+    EmptyResponse::default()
+}
@@ -0,0 +1,96 @@
+use crate::SimKernel;
+use crate::services::output_services::write_output;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::security::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::security::*;
+use hxposed_core::hxposed::responses::{HxResponse, OpenObjectResponse, SyscallResponse};
+
+pub(crate) fn open_token_sync(kernel: &mut SimKernel, request: OpenTokenRequest) -> HxResponse {
+    let token = match request.token {
+        0 => kernel.system_token,
+        x => x,
+    };
+
+    // the driver would happily reference anything. we at least know what a token is.
+    if !kernel.tokens.contains_key(&token) {
+        return HxResponse::not_found_what(NotFoundReason::Token);
+    }
+
+    OpenObjectResponse {
+        object: ObjectType::Token(kernel.tracker.add_open_token(token)),
+    }
+    .into_raw()
+}
+
+pub(crate) fn close_token_sync(kernel: &mut SimKernel, request: CloseTokenRequest) -> HxResponse {
+    match kernel.tracker.pop_open_token(request.token) {
+        None => HxResponse::not_found_what(NotFoundReason::Token),
+        Some(_) => EmptyResponse::default(),
+    }
+}
+
+pub(crate) fn get_token_field_sync(kernel: &mut SimKernel, request: GetTokenFieldRequest) -> HxResponse {
+    let token = match kernel
+        .tracker
+        .get_open_token(request.token)
+        .and_then(|x| kernel.tokens.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Token),
+    };
+
+    match request.field {
+        TokenField::Unknown => return HxResponse::invalid_params(0),
+        TokenField::SourceName(_) => {
+            GetTokenFieldResponse::SourceName(u64::from_le_bytes(token.source_name))
+        }
+        TokenField::AccountName(_) => {
+            let field = token.account_name.encode_utf16().collect::<Vec<_>>();
+            match write_output(&field) {
+                Ok(slice) => GetTokenFieldResponse::AccountName(slice),
+                Err(e) => return e,
+            }
+        }
+        TokenField::Type(_) => GetTokenFieldResponse::Type(token.token_type),
+        TokenField::IntegrityLevelIndex(_) => {
+            GetTokenFieldResponse::IntegrityLevelIndex(token.integrity_level_index)
+        }
+        TokenField::MandatoryPolicy(_) => {
+            GetTokenFieldResponse::MandatoryPolicy(token.mandatory_policy)
+        }
+        TokenField::ImpersonationLevel(_) => {
+            GetTokenFieldResponse::ImpersonationLevel(token.impersonation_level)
+        }
+        TokenField::EnabledPrivileges(_) => {
+            GetTokenFieldResponse::EnabledPrivileges(token.enabled_privileges)
+        }
+        TokenField::PresentPrivileges(_) => {
+            GetTokenFieldResponse::PresentPrivileges(token.present_privileges)
+        }
+        TokenField::EnabledByDefaultPrivileges(_) => {
+            GetTokenFieldResponse::EnabledByDefaultPrivileges(token.enabled_by_default_privileges)
+        }
+    }
+    .into_raw()
+}
+
+pub(crate) fn set_token_field_sync(kernel: &mut SimKernel, request: SetTokenFieldRequest) -> HxResponse {
+    let token = match kernel
+        .tracker
+        .get_open_token(request.token)
+        .and_then(|x| kernel.tokens.get_mut(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Token),
+    };
+
+    match request.field {
+        TokenField::EnabledPrivileges(privs) => token.enabled_privileges = privs,
+        TokenField::PresentPrivileges(privs) => token.present_privileges = privs,
+        _ => return HxResponse::invalid_params(0),
+    }
+
+    EmptyResponse::default()
+}
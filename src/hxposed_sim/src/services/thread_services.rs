@@ -0,0 +1,129 @@
+use crate::SimKernel;
+use crate::services::output_services::write_output;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::requests::thread::*;
+use hxposed_core::hxposed::responses::empty::EmptyResponse;
+use hxposed_core::hxposed::responses::thread::*;
+use hxposed_core::hxposed::responses::{HxResponse, OpenObjectResponse, SyscallResponse};
+
+pub(crate) fn open_thread_sync(kernel: &mut SimKernel, request: OpenThreadRequest) -> HxResponse {
+    let address = match kernel
+        .threads
+        .iter()
+        .find(|(_, t)| t.id as u64 == request.tid)
+    {
+        Some((address, _)) => *address,
+        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    OpenObjectResponse {
+        object: ObjectType::Thread(kernel.tracker.add_open_thread(address)),
+    }
+    .into_raw()
+}
+
+pub(crate) fn close_thread_sync(kernel: &mut SimKernel, request: CloseThreadRequest) -> HxResponse {
+    match kernel.tracker.pop_open_thread(request.thread) {
+        None => HxResponse::not_found_what(NotFoundReason::Thread),
+        Some(_) => EmptyResponse::default(),
+    }
+}
+
+pub(crate) fn get_thread_field_sync(kernel: &mut SimKernel, request: GetThreadFieldRequest) -> HxResponse {
+    let thread = match kernel
+        .tracker
+        .get_open_thread(request.thread)
+        .and_then(|x| kernel.threads.get(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    match request.field {
+        ThreadField::Unknown => return HxResponse::invalid_params(0),
+        ThreadField::ActiveImpersonationInfo(_) => {
+            GetThreadFieldResponse::ActiveImpersonationInfo(thread.impersonating)
+        }
+        ThreadField::AdjustedClientToken(_) => {
+            GetThreadFieldResponse::AdjustedClientToken(thread.adjusted_client_token)
+        }
+        ThreadField::Context(_) if !kernel.on_worker => {
+            return HxResponse::not_allowed(NotAllowedReason::AsyncOnly);
+        }
+        ThreadField::Context(_) => match write_output(&[thread.context]) {
+            Ok(slice) => GetThreadFieldResponse::Context(slice),
+            Err(e) => return e,
+        },
+    }
+    .into_raw()
+}
+
+pub(crate) fn set_thread_field_sync(kernel: &mut SimKernel, request: SetThreadFieldRequest) -> HxResponse {
+    let token = match request.field {
+        ThreadField::AdjustedClientToken(token) => match kernel.tracker.get_open_token(token) {
+            Some(x) => x,
+            None => return HxResponse::not_found_what(NotFoundReason::Token),
+        },
+        _ => return HxResponse::invalid_params(0),
+    };
+
+    match kernel
+        .tracker
+        .get_open_thread(request.thread)
+        .and_then(|x| kernel.threads.get_mut(&x))
+    {
+        Some(thread) => {
+            thread.adjusted_client_token = token;
+            EmptyResponse::default()
+        }
+        None => HxResponse::not_found_what(NotFoundReason::Thread),
+    }
+}
+
+pub(crate) fn suspend_thread(kernel: &mut SimKernel, request: SuspendThreadRequest) -> HxResponse {
+    const STATUS_SUSPEND_COUNT_EXCEEDED: u32 = 0xC000_004A;
+    const STATUS_THREAD_IS_TERMINATING: u32 = 0xC000_004B;
+    const MAXIMUM_SUSPEND_COUNT: u32 = 0x7F;
+
+    let address = match kernel.tracker.get_open_thread(request.thread) {
+        Some(x) if kernel.threads.contains_key(&x) => x,
+        _ => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    // threads die with their process.
+    let process_id = kernel.threads[&address].process_id;
+    if kernel
+        .process(process_id)
+        .is_some_and(|p| p.exit_status.is_some())
+    {
+        return HxResponse::nt_error(STATUS_THREAD_IS_TERMINATING);
+    }
+
+    let thread = kernel.threads.get_mut(&address).unwrap();
+    if thread.suspend_count == MAXIMUM_SUSPEND_COUNT {
+        return HxResponse::nt_error(STATUS_SUSPEND_COUNT_EXCEEDED);
+    }
+
+    thread.suspend_count += 1;
+    SuspendCountResponse {
+        previous_count: thread.suspend_count - 1,
+    }
+    .into_raw()
+}
+
+pub(crate) fn resume_thread(kernel: &mut SimKernel, request: ResumeThreadRequest) -> HxResponse {
+    let thread = match kernel
+        .tracker
+        .get_open_thread(request.thread)
+        .and_then(|x| kernel.threads.get_mut(&x))
+    {
+        Some(x) => x,
+        None => return HxResponse::not_found_what(NotFoundReason::Thread),
+    };
+
+    let previous_count = thread.suspend_count;
+    thread.suspend_count = previous_count.saturating_sub(1);
+
+    SuspendCountResponse { previous_count }.into_raw()
+}
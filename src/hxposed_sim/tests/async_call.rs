@@ -0,0 +1,143 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::async_call::AsyncInfo;
+use hxposed_core::hxposed::requests::{HxRequest, SyscallRequest};
+use hxposed_core::hxposed::requests::process::*;
+use hxposed_core::services::async_call::SyscallAsync;
+use hxposed_core::services::batch::HxBatch;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::types::process_fields::*;
+use hxposed_sim::{Sim, SimProcess};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::mpsc::channel;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+mod common;
+use common::{ChannelWaker, block_on};
+
+/// Marks any request slow, without going through `send_async`.
+struct SlowRequest<T>(T);
+
+impl<T: SyscallRequest> SyscallRequest for SlowRequest<T> {
+    type Response = T::Response;
+
+    fn into_raw(self) -> HxRequest {
+        let mut raw = self.0.into_raw();
+        AsyncInfo {
+            event: 1,
+            completion: 1,
+        }
+        .attach(&mut raw);
+        raw
+    }
+
+    fn from_raw(request: &HxRequest) -> Self {
+        Self(T::from_raw(request))
+    }
+}
+
+#[test]
+fn completes_immediately() {
+    let sim = Sim::new();
+    let protection = sim.with(|k| k.caller().protection);
+
+    let call = GetProcessFieldRequest {
+        process: 0,
+        field: ProcessField::Protection(ProcessProtection::new()),
+    }
+    .send_async()
+    .unwrap();
+
+    assert!(call.is_completed());
+    assert_eq!(call.wait().unwrap().field, ProcessField::Protection(protection));
+}
+
+#[test]
+fn woken_by_event() {
+    let sim = Sim::new();
+    sim.with(|k| k.defer_async(true));
+
+    let call = GetProcessFieldRequest {
+        process: 0,
+        field: ProcessField::DirectoryTableBase(0),
+    }
+    .send_async()
+    .unwrap();
+    assert!(!call.is_completed());
+    assert_eq!(sim.with(|k| k.pending_async().len()), 1);
+
+    let (tx, rx) = channel();
+    let waker = Waker::from(Arc::new(ChannelWaker(Mutex::new(tx))));
+    let mut cx = Context::from_waker(&waker);
+    let mut call = pin!(call);
+
+    assert!(call.as_mut().poll(&mut cx).is_pending());
+
+    assert_eq!(sim.with(|k| k.run_async()), 1);
+    rx.recv_timeout(Duration::from_secs(5)).expect("never woken");
+
+    let dtb = sim.with(|k| k.caller().directory_table_base);
+    match call.as_mut().poll(&mut cx) {
+        Poll::Ready(x) => assert_eq!(x.unwrap().field, ProcessField::DirectoryTableBase(dtb)),
+        Poll::Pending => panic!("completed call is pending"),
+    }
+}
+
+#[test]
+fn errors_are_delivered() {
+    let _sim = Sim::new();
+
+    let call = GetProcessFieldRequest {
+        process: 0x1337,
+        field: ProcessField::Protection(ProcessProtection::new()),
+    }
+    .send_async()
+    .unwrap();
+
+    assert_eq!(
+        block_on(call).err(),
+        Some(HxError::NotFound(NotFoundReason::Process))
+    );
+}
+
+#[test]
+fn enumerations_are_slow() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    sim.with(|k| k.defer_async(true));
+
+    let (tx, rx) = channel();
+    let waker = Waker::from(Arc::new(ChannelWaker(Mutex::new(tx))));
+    let mut cx = Context::from_waker(&waker);
+    let mut processes = pin!(HxProcess::enumerate());
+
+    assert!(processes.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(sim.with(|k| k.pending_async().len()), 1);
+
+    // completing it runs the wait callback. no thread was waiting.
+    assert_eq!(sim.with(|k| k.run_async()), 1);
+    rx.recv_timeout(Duration::from_secs(5)).expect("never woken");
+
+    match processes.as_mut().poll(&mut cx) {
+        Poll::Ready(x) => assert!(x.unwrap().any(|x| x.id == pid)),
+        Poll::Pending => panic!("completed call is pending"),
+    }
+}
+
+#[test]
+fn slow_calls_are_refused_in_batches() {
+    let _sim = Sim::new();
+
+    let mut batch = HxBatch::new();
+    let entry = batch.push(SlowRequest(GetProcessFieldRequest {
+        process: 0,
+        field: ProcessField::Protection(ProcessProtection::new()),
+    }));
+    batch.submit().unwrap();
+
+    assert_eq!(batch.get(&entry).err(), Some(HxError::InvalidParameters(0)));
+}
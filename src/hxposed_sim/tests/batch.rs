@@ -0,0 +1,132 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::requests::{Syscall, SyscallRequest};
+use hxposed_core::hxposed::requests::batch::BatchRequest;
+use hxposed_core::hxposed::requests::process::*;
+use hxposed_core::hxposed::requests::status::StatusRequest;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::status::HypervisorStatus;
+use hxposed_core::services::batch::HxBatch;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::types::process_fields::*;
+use hxposed_sim::{Sim, SimProcess};
+
+#[test]
+fn typed_responses() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("csrss.exe")));
+    let process = HxProcess::open(pid).unwrap();
+
+    let mut batch = HxBatch::new();
+    let status = batch.push(StatusRequest);
+    let protection = batch.push(GetProcessFieldRequest {
+        process: process.object(),
+        field: ProcessField::Protection(ProcessProtection::new()),
+    });
+    let missing = batch.push(GetProcessFieldRequest {
+        process: 0x1337,
+        field: ProcessField::Protection(ProcessProtection::new()),
+    });
+    batch.submit().unwrap();
+
+    assert_eq!(batch.get(&status).unwrap().state, HypervisorStatus::SystemVirtualized);
+    assert_eq!(
+        batch.get(&protection).unwrap().field,
+        ProcessField::Protection(sim.with(|k| k.process(pid).unwrap().protection))
+    );
+    assert_eq!(
+        batch.get(&missing).err(),
+        Some(HxError::NotFound(NotFoundReason::Process))
+    );
+}
+
+#[test]
+fn large_batches_are_split() {
+    let sim = Sim::new();
+    let process = HxProcess::current();
+
+    let mut batch = HxBatch::new();
+    let entries = (0..BatchRequest::MAX_COUNT * 2 + 1)
+        .map(|x| {
+            batch.push(SetProcessFieldRequest {
+                process: process.object(),
+                field: ProcessField::MitigationFlags(MitigationOptions::from_bits(x)),
+            })
+        })
+        .collect::<Vec<_>>();
+    batch.submit().unwrap();
+
+    assert!(entries.iter().all(|x| batch.get(x).is_ok()));
+    assert_eq!(
+        sim.with(|k| k.caller().mitigations),
+        MitigationOptions::from_bits(BatchRequest::MAX_COUNT * 2)
+    );
+}
+
+#[test]
+fn nested_batch() {
+    let _sim = Sim::new();
+
+    let mut batch = HxBatch::new();
+    let nested = batch.push(BatchRequest::default());
+    batch.submit().unwrap();
+
+    assert_eq!(batch.get(&nested).unwrap_err(), HxError::InvalidParameters(0));
+}
+
+#[test]
+fn too_many_requests() {
+    let _sim = Sim::new();
+
+    let mut responses = [HxResponse::default(); 1];
+    assert_eq!(
+        BatchRequest {
+            requests: 0,
+            responses: responses.as_mut_ptr() as _,
+            count: BatchRequest::MAX_COUNT + 1,
+        }
+        .send()
+        .unwrap_err(),
+        HxError::InvalidParameters(2)
+    );
+}
+
+#[test]
+fn kernel_buffers() {
+    let _sim = Sim::new();
+
+    let requests = [StatusRequest.into_raw()];
+    let mut responses = [HxResponse::default(); 1];
+
+    assert_eq!(
+        BatchRequest {
+            requests: 0xFFFF_F800_0000_0000,
+            responses: responses.as_mut_ptr() as _,
+            count: 1,
+        }
+        .send()
+        .unwrap_err(),
+        HxError::InvalidParameters(0)
+    );
+    assert_eq!(
+        BatchRequest {
+            requests: requests.as_ptr() as _,
+            responses: 0xFFFF_F800_0000_0000,
+            count: 1,
+        }
+        .send()
+        .unwrap_err(),
+        HxError::InvalidParameters(1)
+    );
+    // ends past the limit.
+    assert_eq!(
+        BatchRequest {
+            requests: requests.as_ptr() as _,
+            responses: 0x7FFF_FFFF_0000 - size_of::<HxResponse>() as u64 + 1,
+            count: 1,
+        }
+        .send()
+        .unwrap_err(),
+        HxError::InvalidParameters(1)
+    );
+}
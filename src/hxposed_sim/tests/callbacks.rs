@@ -0,0 +1,54 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::ObjectType;
+use hxposed_core::hxposed::requests::notify::ObjectState;
+use hxposed_core::services::callbacks::HxCallback;
+use hxposed_sim::{Sim, SimProcess, SimThread};
+
+#[test]
+fn process_created_and_deleted() {
+    let sim = Sim::new();
+
+    let callback = HxCallback::new(ObjectType::Process(0)).unwrap();
+
+    let pid = sim.with(|k| k.add_process(SimProcess::new("explorer.exe")));
+    let info = callback.wait_for_callback().unwrap();
+    assert_eq!(ObjectType::from_raw_enum(info.object_type, info.object_value), ObjectType::Process(pid as _));
+    assert_eq!(info.object_state, ObjectState::Created);
+
+    sim.with(|k| k.remove_process(pid));
+    let info = callback.wait_for_callback().unwrap();
+    assert_eq!(info.object_state, ObjectState::Deleted);
+}
+
+#[test]
+fn thread_callbacks_ignore_processes() {
+    let sim = Sim::new();
+
+    let callback = HxCallback::new(ObjectType::Thread(0)).unwrap();
+
+    sim.with(|k| k.add_process(SimProcess::new("explorer.exe")));
+    assert_eq!(callback.wait_for_callback().unwrap_err(), HxError::TimedOut);
+
+    let tid = sim.with(|k| k.add_thread(4, SimThread::default()).unwrap());
+    let info = callback.wait_for_callback().unwrap();
+    assert_eq!(info.object_value, tid as u64);
+}
+
+#[test]
+fn unregistered_on_drop() {
+    let sim = Sim::new();
+
+    drop(HxCallback::new(ObjectType::Process(0)).unwrap());
+
+    assert!(sim.with(|k| k.tracker().callbacks.is_empty()));
+}
+
+#[test]
+fn token_callbacks_are_invalid() {
+    let _sim = Sim::new();
+
+    assert_eq!(
+        HxCallback::new(ObjectType::Token(0)).err(),
+        Some(HxError::InvalidParameters(0))
+    );
+}
@@ -0,0 +1,36 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc::{Sender, channel};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+pub struct ChannelWaker(pub Mutex<Sender<()>>);
+
+impl Wake for ChannelWaker {
+    fn wake(self: Arc<Self>) {
+        let _ = self.0.lock().unwrap().send(());
+    }
+}
+
+///
+/// # Block On
+///
+/// Polls `future` until it's ready, sleeping until woken in between.
+///
+/// ## Panic
+/// - If nothing wakes it for 5 seconds.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let (tx, rx) = channel();
+    let waker = Waker::from(Arc::new(ChannelWaker(Mutex::new(tx))));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        if let Poll::Ready(x) = future.as_mut().poll(&mut cx) {
+            return x;
+        }
+        rx.recv_timeout(Duration::from_secs(5)).expect("never woken");
+    }
+}
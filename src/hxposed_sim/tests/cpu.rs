@@ -0,0 +1,99 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::requests::{Syscall, SyscallRequest};
+use hxposed_core::hxposed::requests::status::StatusRequest;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::responses::SyscallResponse;
+use hxposed_core::hxposed::responses::status::StatusResponse;
+use hxposed_core::hxposed::status::{HypervisorStatus, PROTOCOL_VERSION};
+use hxposed_core::hxposed::transport::transport;
+use hxposed_core::services::cpu::HxCpu;
+use hxposed_core::services::status::HxPosed;
+use hxposed_sim::Sim;
+
+const IA32_LSTAR: u32 = 0xC000_0082;
+
+#[test]
+fn status() {
+    let _sim = Sim::new();
+
+    let status = StatusRequest.send().unwrap();
+
+    assert_eq!(status.state, HypervisorStatus::SystemVirtualized);
+}
+
+#[test]
+fn read_write_msr() {
+    let sim = Sim::new();
+    sim.with(|k| k.set_msr(IA32_LSTAR, 0xFFFF_F800_1234_0000));
+
+    assert_eq!(HxCpu::read_msr(IA32_LSTAR).unwrap(), 0xFFFF_F800_1234_0000);
+
+    HxCpu::write_msr(IA32_LSTAR, 0x2009).unwrap();
+    assert_eq!(sim.with(|k| k.msr(IA32_LSTAR)), Some(0x2009));
+}
+
+#[test]
+fn missing_msr() {
+    let _sim = Sim::new();
+
+    assert_eq!(
+        HxCpu::read_msr(0x1337).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::AccessViolation)
+    );
+}
+
+#[test]
+fn capabilities() {
+    let _sim = Sim::new();
+
+    let status = HxPosed::status().unwrap();
+    assert_eq!(status.version, PROTOCOL_VERSION);
+
+    let capabilities = status.capabilities;
+    assert!(capabilities.supports(ServiceFunction::GetState));
+    assert!(capabilities.supports(ServiceFunction::DescribePhysicalMemory));
+    assert!(!capabilities.supports(ServiceFunction::InterProcessorInterrupt));
+    assert_eq!(capabilities.category(6), 0b11);
+
+    assert_eq!(
+        HxPosed::require(ServiceFunction::InterProcessorInterrupt).unwrap_err(),
+        HxError::NotSupported(ServiceFunction::InterProcessorInterrupt)
+    );
+    HxPosed::require(ServiceFunction::OpenProcess).unwrap();
+}
+
+#[test]
+fn capabilities_in_xmm() {
+    let _sim = Sim::new();
+
+    let raw = transport().send(&mut StatusRequest.into_raw());
+    assert!(raw.result.extended_results_present());
+
+    let capabilities = HxPosed::status().unwrap().capabilities;
+    assert_eq!(raw.extended_arg1, capabilities.into_bits());
+
+    // older drivers only split it over arg2 and arg3.
+    let mut split = raw;
+    split.result = split.result.with_extended_results_present(false);
+    split.extended_arg1 = 0;
+    assert_eq!(StatusResponse::from_raw(split).capabilities, capabilities);
+}
+
+#[test]
+fn unknown_service_function() {
+    let _sim = Sim::new();
+
+    assert_eq!(ServiceFunction::from_bits(0x7F), ServiceFunction::Unknown);
+    assert_eq!(
+        ServiceFunction::try_from(0x7F).unwrap_err(),
+        HxError::NotFound(NotFoundReason::ServiceFunction)
+    );
+    assert_eq!(
+        ServiceFunction::try_from(0x34).unwrap(),
+        ServiceFunction::TranslateAddress
+    );
+
+    let capabilities = HxPosed::status().unwrap().capabilities;
+    assert!(!capabilities.supports(ServiceFunction::Unknown));
+}
@@ -0,0 +1,293 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::requests::Syscall;
+use hxposed_core::hxposed::requests::handle::EnumerateHandlesRequest;
+use hxposed_core::hxposed::requests::process::OpenProcessRequest;
+use hxposed_core::hxposed::responses::handle::HandleAttributes;
+use hxposed_core::services::handle::HxHandle;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::thread::HxThread;
+use hxposed_sim::{Sim, SimHandle, SimProcess};
+
+mod common;
+use common::block_on;
+
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+#[test]
+fn upgrade_and_swap() {
+    let sim = Sim::new();
+    let (pid, caller, system) = sim.with(|k| {
+        let pid = k.caller().id;
+        (pid, k.process_address(pid).unwrap(), k.process_address(4).unwrap())
+    });
+    let value = sim.with(|k| {
+        k.add_handle(
+            pid,
+            SimHandle {
+                object: caller,
+                granted_access: PROCESS_QUERY_LIMITED_INFORMATION,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+
+    let mut handle = HxHandle::from_handle(value);
+    handle.upgrade(HxHandle::HANDLE_ALL_ACCESS).unwrap();
+    handle.set_object(system).unwrap();
+
+    let entry = sim.with(|k| k.caller().handles[&value].clone());
+    assert_eq!(entry.granted_access, HxHandle::HANDLE_ALL_ACCESS);
+    assert_eq!(entry.object, system);
+}
+
+#[test]
+fn get_object() {
+    let sim = Sim::new();
+    let (pid, system) = sim.with(|k| (k.caller().id, k.process_address(4).unwrap()));
+    let value = sim.with(|k| {
+        k.add_handle(
+            pid,
+            SimHandle {
+                object: system,
+                granted_access: PROCESS_QUERY_LIMITED_INFORMATION,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+
+    let object = HxHandle::from_handle(value).get_object().unwrap();
+    assert_eq!(object.object, system);
+    assert_eq!(object.granted_access, PROCESS_QUERY_LIMITED_INFORMATION);
+
+    // must not have been upgraded along the way.
+    let entry = sim.with(|k| k.caller().handles[&value].clone());
+    assert_eq!(entry.granted_access, PROCESS_QUERY_LIMITED_INFORMATION);
+}
+
+#[test]
+fn missing_handle() {
+    let _sim = Sim::new();
+
+    assert_eq!(
+        HxHandle::from_handle(0x1234).upgrade(0).unwrap_err(),
+        HxError::NotFound(NotFoundReason::Handle)
+    );
+}
+
+#[test]
+fn enumerate() {
+    const KEY_READ: u32 = 0x20019;
+
+    let sim = Sim::new();
+    let system = sim.with(|k| k.process_address(4).unwrap());
+    let pid = sim.with(|k| k.add_process(SimProcess::new("svchost.exe")));
+    let (process_handle, key_handle) = sim.with(|k| {
+        let process = k
+            .add_handle(
+                pid,
+                SimHandle {
+                    object: system,
+                    granted_access: PROCESS_QUERY_LIMITED_INFORMATION,
+                    attributes: HandleAttributes::new().with_inherit(true),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // nothing the sim models. no type, but still a name.
+        let key = k
+            .add_handle(
+                pid,
+                SimHandle {
+                    object: 0xFFFF_A000_1234_0000,
+                    granted_access: KEY_READ,
+                    attributes: HandleAttributes::new().with_protect_from_close(true),
+                    name: Some("\\REGISTRY\\MACHINE\\SOFTWARE".into()),
+                },
+            )
+            .unwrap();
+        (process, key)
+    });
+
+    let handles = block_on(HxProcess::open(pid).unwrap().handles())
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(handles.len(), 2);
+
+    assert_eq!(handles[0].handle, process_handle);
+    assert_eq!(handles[0].object, system);
+    assert_eq!(handles[0].type_name, "Process");
+    assert_eq!(handles[0].name, None);
+    assert!(handles[0].attributes.inherit());
+
+    assert_eq!(handles[1].handle, key_handle);
+    assert_eq!(handles[1].type_name, "");
+    assert_eq!(handles[1].name.as_deref(), Some("\\REGISTRY\\MACHINE\\SOFTWARE"));
+    assert_eq!(handles[1].granted_access, KEY_READ);
+    assert!(handles[1].attributes.protect_from_close());
+    assert!(!handles[1].attributes.inherit());
+}
+
+#[test]
+fn enumerate_needs_async() {
+    let _sim = Sim::new();
+    let process = OpenProcessRequest { process_id: 4 }.send().unwrap().object;
+
+    // names can block. not inline in the handler.
+    assert_eq!(
+        EnumerateHandlesRequest {
+            process: process.into(),
+        }
+        .send()
+        .err(),
+        Some(HxError::NotAllowed(NotAllowedReason::AsyncOnly))
+    );
+}
+
+#[test]
+fn create() {
+    const TOKEN_QUERY: u32 = 0x8;
+
+    let sim = Sim::new();
+    let lsass = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    let (lsass_address, token_address) = sim.with(|k| {
+        (
+            k.process_address(lsass).unwrap(),
+            k.process(lsass).unwrap().token,
+        )
+    });
+
+    let process = HxProcess::open(lsass).unwrap();
+    let process_handle = process.to_handle(HxHandle::HANDLE_ALL_ACCESS).unwrap();
+    let token_handle = process
+        .get_primary_token()
+        .unwrap()
+        .to_handle(TOKEN_QUERY)
+        .unwrap();
+    let thread_handle = HxThread::current().unwrap().to_handle(0).unwrap();
+
+    let caller = sim.with(|k| k.caller().handles.clone());
+    assert_eq!(caller[&process_handle.handle].object, lsass_address);
+    assert_eq!(
+        caller[&process_handle.handle].granted_access,
+        HxHandle::HANDLE_ALL_ACCESS
+    );
+    assert_eq!(caller[&token_handle.handle].object, token_address);
+    assert_eq!(caller[&token_handle.handle].granted_access, TOKEN_QUERY);
+    assert!(caller.contains_key(&thread_handle.handle));
+
+    // a real handle. the other services take it like any other.
+    assert_eq!(
+        HxHandle::from_handle(process_handle.handle)
+            .get_object()
+            .unwrap()
+            .object,
+        lsass_address
+    );
+}
+
+#[test]
+fn remote_downgrade_and_close() {
+    const PROCESS_VM_READ: u32 = 0x10;
+
+    let sim = Sim::new();
+    let system = sim.with(|k| k.process_address(4).unwrap());
+    let pid = sim.with(|k| k.add_process(SimProcess::new("cheat.exe")));
+    let (first, second) = sim.with(|k| {
+        let mut add = |attributes| {
+            k.add_handle(
+                pid,
+                SimHandle {
+                    object: system,
+                    granted_access: HxHandle::HANDLE_ALL_ACCESS,
+                    attributes,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        (
+            add(HandleAttributes::new()),
+            add(HandleAttributes::new().with_protect_from_close(true)),
+        )
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    HxHandle::from_remote(&process, first)
+        .downgrade(PROCESS_VM_READ | PROCESS_QUERY_LIMITED_INFORMATION)
+        .unwrap();
+    // only takes away.
+    HxHandle::from_remote(&process, first)
+        .downgrade(PROCESS_VM_READ | 0x1)
+        .unwrap();
+    assert_eq!(
+        HxHandle::from_remote(&process, first)
+            .get_object()
+            .unwrap()
+            .granted_access,
+        PROCESS_VM_READ
+    );
+
+    // the owner has to lift the protection first.
+    assert_eq!(
+        HxHandle::from_remote(&process, second)
+            .force_close()
+            .unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::HandleProtected)
+    );
+
+    HxHandle::from_remote(&process, first).force_close().unwrap();
+    let handles = block_on(process.handles()).unwrap().collect::<Vec<_>>();
+    assert_eq!(handles.len(), 1);
+    assert_eq!(handles[0].handle, second);
+
+    assert_eq!(
+        HxHandle::from_remote(&process, first)
+            .force_close()
+            .unwrap_err(),
+        HxError::NotFound(NotFoundReason::Handle)
+    );
+    // the caller's own table wasn't touched.
+    assert!(sim.with(|k| k.caller().handles.is_empty()));
+}
+
+#[test]
+fn swap_checks_type() {
+    let sim = Sim::new();
+    let (pid, caller, system, token) = sim.with(|k| {
+        let pid = k.caller().id;
+        (
+            pid,
+            k.process_address(pid).unwrap(),
+            k.process_address(4).unwrap(),
+            k.process(4).unwrap().token,
+        )
+    });
+    let value = sim.with(|k| {
+        k.add_handle(
+            pid,
+            SimHandle {
+                object: caller,
+                granted_access: PROCESS_QUERY_LIMITED_INFORMATION,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+    let object = || sim.with(|k| k.caller().handles[&value].object);
+
+    let mut handle = HxHandle::from_handle(value);
+    let mismatch = HxError::NotAllowed(NotAllowedReason::TypeMismatch);
+    assert_eq!(handle.set_object(token).unwrap_err(), mismatch);
+    assert_eq!(handle.set_object(0xDEAD_BEEF).unwrap_err(), mismatch);
+    assert_eq!(handle.force_set_object(0xDEAD_BEEF).unwrap_err(), mismatch);
+    assert_eq!(object(), caller);
+
+    handle.set_object(system).unwrap();
+    assert_eq!(object(), system);
+
+    handle.force_set_object(token).unwrap();
+    assert_eq!(object(), token);
+}
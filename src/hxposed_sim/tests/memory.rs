@@ -0,0 +1,292 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotAllowedReason;
+use hxposed_core::hxposed::requests::Syscall;
+use hxposed_core::hxposed::requests::memory::*;
+use hxposed_core::hxposed::requests::process::OpenProcessRequest;
+use hxposed_core::hxposed::responses::memory::VadType;
+use hxposed_core::services::memory::HxMemory;
+use hxposed_core::services::process::HxProcess;
+use hxposed_sim::{Sim, SimProcess, SimVad};
+use std::ops::DerefMut;
+
+mod common;
+use common::block_on;
+
+// every test maps at its own address. they all share one address space.
+
+#[test]
+fn allocate_map_and_write() {
+    let sim = Sim::new();
+
+    let process = HxProcess::current();
+    let descriptor = HxMemory::alloc::<u64>(MemoryType::NonPagedPool).unwrap();
+
+    {
+        let mut guard = descriptor.map(&process, 0x2009_0000_0000).unwrap();
+        *guard.deref_mut() = 0x2009;
+    }
+
+    let mut bytes = [0u8; 8];
+    sim.with(|k| {
+        let rmd = &k.tracker().rmds[descriptor.rmd as usize];
+        assert!(rmd.mappings.is_empty());
+        rmd.memory.as_ref().unwrap().read(0, &mut bytes).unwrap();
+    });
+
+    assert_eq!(u64::from_le_bytes(bytes), 0x2009);
+}
+
+#[test]
+fn mappings_share_memory() {
+    let _sim = Sim::new();
+
+    let process = HxProcess::current();
+    let descriptor = HxMemory::alloc::<u32>(MemoryType::ContiguousPhysical).unwrap();
+
+    let mut first = descriptor.map(&process, 0x2009_0001_0000).unwrap();
+    let second = descriptor.map(&process, 0x2009_0002_0000).unwrap();
+
+    *first = 0x1337;
+
+    assert_eq!(*second, 0x1337);
+}
+
+#[test]
+fn free_with_mappings() {
+    let sim = Sim::new();
+
+    let process = HxProcess::current();
+    let descriptor = HxMemory::alloc::<u8>(MemoryType::NonPagedPool).unwrap();
+    let _guard = descriptor.map(&process, 0x2009_0003_0000).unwrap();
+
+    match (FreeMemoryRequest {
+        obj: descriptor.rmd,
+    })
+    .send()
+    {
+        Err(e) => assert_eq!(e, HxError::NotAllowed(NotAllowedReason::MappingsExist)),
+        Ok(_) => panic!("freed memory that is still mapped"),
+    }
+
+    assert_eq!(sim.with(|k| k.tracker().rmds.len()), 1);
+}
+
+#[test]
+fn map_twice_at_same_address() {
+    let _sim = Sim::new();
+
+    let process = HxProcess::current();
+    let descriptor = HxMemory::alloc::<u8>(MemoryType::NonPagedPool).unwrap();
+    let _guard = descriptor.map(&process, 0x2009_0004_0000).unwrap();
+
+    match descriptor.map(&process, 0x2009_0004_0000) {
+        Err(e) => assert_eq!(e, HxError::NotAllowed(NotAllowedReason::MappingsExist)),
+        Ok(_) => panic!("mapped over an existing mapping"),
+    }
+}
+
+#[test]
+fn translate_mapped_address() {
+    let sim = Sim::new();
+
+    let process = HxProcess::current();
+    let descriptor = HxMemory::alloc::<[u8; 0x2000]>(MemoryType::NonPagedPool).unwrap();
+    let _guard = descriptor.map(&process, 0x2009_0005_0000).unwrap();
+
+    let pa = sim.with(|k| k.tracker().rmds[descriptor.rmd as usize].pa);
+
+    assert_eq!(
+        HxMemory::translate_addr(HxProcess::current(), 0x2009_0005_1234).unwrap(),
+        pa + 0x1234
+    );
+}
+
+fn with_region(sim: &Sim, base: u64, region: Vec<u8>) -> u32 {
+    sim.with(|k| {
+        let mut process = SimProcess::new("game.exe");
+        process.memory.insert(base, region);
+        k.add_process(process)
+    })
+}
+
+#[test]
+fn read_write_remote() {
+    let sim = Sim::new();
+    let pid = with_region(&sim, 0x1000, (0..0x20).collect());
+    let process = HxProcess::open(pid).unwrap();
+
+    let mut bytes = [0u8; 4];
+    assert_eq!(process.memory.read_bytes(0x1004, &mut bytes).unwrap(), 4);
+    assert_eq!(bytes, [4, 5, 6, 7]);
+
+    assert_eq!(process.memory.write_bytes(0x1000, &[0xAA; 2]).unwrap(), 2);
+    sim.with(|k| assert_eq!(k.process(pid).unwrap().memory[&0x1000][..3], [0xAA, 0xAA, 2]));
+
+    assert_eq!(process.memory.read::<u16>(0x1000).unwrap(), 0xAAAA);
+}
+
+#[test]
+fn partial_copy() {
+    let sim = Sim::new();
+    let pid = with_region(&sim, 0x1000, vec![0x11; 0x10]);
+    let process = HxProcess::open(pid).unwrap();
+
+    // region ends 6 bytes in.
+    let mut bytes = [0u8; 16];
+    assert_eq!(process.memory.read_bytes(0x100A, &mut bytes).unwrap(), 6);
+    assert_eq!(process.memory.write_bytes(0x100C, &[0; 8]).unwrap(), 4);
+    assert_eq!(process.memory.read_bytes(0x2000, &mut bytes).unwrap(), 0);
+
+    assert_eq!(
+        process.memory.read::<u64>(0x100C).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::AccessViolation)
+    );
+    assert_eq!(
+        process.memory.write(0x100C, &0u64).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::AccessViolation)
+    );
+}
+
+#[test]
+fn kernel_range() {
+    let sim = Sim::new();
+    let pid = with_region(&sim, 0x1000, vec![0; 0x10]);
+    let process = HxProcess::open(pid).unwrap();
+
+    let mut bytes = [0u8; 8];
+    assert_eq!(
+        process
+            .memory
+            .read_bytes(0xFFFF_F780_0000_0000, &mut bytes)
+            .unwrap_err(),
+        HxError::InvalidParameters(1)
+    );
+    // straddles the limit.
+    assert_eq!(
+        process
+            .memory
+            .write_bytes(0x7FFF_FFFE_FFFC, &bytes)
+            .unwrap_err(),
+        HxError::InvalidParameters(1)
+    );
+    // wraps around.
+    assert_eq!(
+        process
+            .memory
+            .read_bytes(u64::MAX - 3, &mut bytes)
+            .unwrap_err(),
+        HxError::InvalidParameters(1)
+    );
+
+    // the caller's side is checked the same way.
+    let request = ReadVirtualMemoryRequest {
+        addr_space: process.memory.process,
+        address: 0x1000,
+        buffer: 0xFFFF_F780_0000_0000,
+        size: 8,
+    };
+    assert_eq!(request.send().err(), Some(HxError::InvalidParameters(2)));
+}
+
+#[test]
+fn remote_ptr() {
+    let sim = Sim::new();
+    let pid = with_region(&sim, 0x1000, vec![0; 0x10]);
+    let process = HxProcess::open(pid).unwrap();
+
+    let counters = process.memory.ptr::<u32>(0x1000);
+    counters.add(1).write(&41).unwrap();
+    counters.add(1).write(&(counters.add(1).read().unwrap() + 1)).unwrap();
+
+    assert_eq!(counters.add(1).address(), 0x1004);
+    assert_eq!(counters.cast::<u64>().read().unwrap(), 42 << 32);
+    assert!(counters.add(4).read().is_err());
+}
+
+#[test]
+fn read_write_self() {
+    let _sim = Sim::new();
+    let process = HxProcess::current();
+
+    let mut value = 0x2014u64;
+    let address = &mut value as *mut u64 as u64;
+    assert_eq!(process.memory.read::<u64>(address).unwrap(), 0x2014);
+
+    process.memory.write(address, &0x4102u64).unwrap();
+    assert_eq!(unsafe { (address as *const u64).read_volatile() }, 0x4102);
+}
+
+#[test]
+fn memory_regions() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("game.exe");
+        // out of order on purpose. walk is in address order.
+        process.vads.insert(
+            0x7FF6_0000,
+            SimVad {
+                start_vpn: 0x7FF6_0000,
+                end_vpn: 0x7FF6_0010,
+                commit_charge: 3,
+                protection: 7, // MM_EXECUTE_WRITECOPY
+                vad_type: VadType::ImageMap,
+                private: false,
+                file_name: Some("\\Windows\\System32\\game.exe".into()),
+            },
+        );
+        process.vads.insert(
+            0x100,
+            SimVad {
+                start_vpn: 0x100,
+                end_vpn: 0x10F,
+                commit_charge: 16,
+                protection: 4 | 0x10, // MM_READWRITE | MM_GUARD
+                vad_type: VadType::None,
+                private: true,
+                file_name: None,
+            },
+        );
+        k.add_process(process)
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    let regions = block_on(process.memory_regions()).unwrap();
+    assert!(!regions.truncated);
+
+    let regions = regions.regions;
+    assert_eq!(regions.len(), 2);
+
+    let heap = &regions[0];
+    assert_eq!(heap.base_address(), 0x10_0000);
+    assert_eq!(heap.size(), 0x1_0000);
+    assert!(heap.contains(0x10_FFFF) && !heap.contains(0x11_0000));
+    assert_eq!(heap.protection, 0x04 | 0x100); // PAGE_READWRITE | PAGE_GUARD
+    assert!(heap.private);
+    assert_eq!(heap.file_name, None);
+
+    let image = &regions[1];
+    assert_eq!(image.vad_type, VadType::ImageMap);
+    assert_eq!(image.protection, 0x80); // PAGE_EXECUTE_WRITECOPY
+    assert_eq!(image.commit_charge, 3);
+    assert!(!image.private);
+    assert_eq!(
+        image.file_name.as_deref(),
+        Some("\\Windows\\System32\\game.exe")
+    );
+}
+
+#[test]
+fn memory_regions_need_async() {
+    let _sim = Sim::new();
+    let process = OpenProcessRequest { process_id: 4 }.send().unwrap().object;
+
+    // holds the address space lock of the target while names are copied. not inline in the handler.
+    assert_eq!(
+        EnumerateMemoryRegionsRequest {
+            addr_space: process.into(),
+        }
+        .send()
+        .err(),
+        Some(HxError::NotAllowed(NotAllowedReason::AsyncOnly))
+    );
+}
@@ -0,0 +1,403 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::responses::memory::VadType;
+use hxposed_core::services::minidump::*;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::types::thread_fields::ThreadContext;
+use hxposed_sim::{Sim, SimModule, SimProcess, SimThread, SimVad};
+use minidump::{
+    MinidumpMemory64List, MinidumpMemoryList, MinidumpModuleList, MinidumpRawContext,
+    MinidumpThreadList, Module,
+};
+
+mod common;
+use common::block_on;
+
+fn synthetic() -> Minidump {
+    let context = ThreadContext {
+        rip: 0x7FF6_0000_1234,
+        rsp: 0x10_3F00,
+        ..Default::default()
+    };
+
+    Minidump {
+        system_info: MinidumpSystemInfo {
+            number_of_processors: 8,
+            product_type: 1,
+            major_version: 10,
+            build_number: 26100,
+            ..Default::default()
+        },
+        threads: vec![
+            MinidumpThread {
+                id: 0x104,
+                suspend_count: 1,
+                priority_class: 0x20,
+                priority: 8,
+                teb: 0x3000,
+                context,
+                stack: MinidumpMemory {
+                    address: 0x10_3F00,
+                    data: vec![0xAA; 0x100],
+                },
+            },
+            MinidumpThread {
+                id: 0x108,
+                suspend_count: 0,
+                priority_class: 0,
+                priority: 0,
+                teb: 0,
+                context: ThreadContext::default(),
+                stack: MinidumpMemory::default(),
+            },
+        ],
+        modules: vec![
+            MinidumpModule {
+                base: 0x7FF6_0000_0000,
+                size: 0x2000,
+                checksum: 0x1234,
+                timestamp: 0x6500_0000,
+                name: "C:\\Windows\\System32\\lsass.exe".into(),
+            },
+            MinidumpModule {
+                base: 0x7FFE_0000_0000,
+                size: 0x1000,
+                name: "C:\\Windows\\System32\\ntdll.dll".into(),
+                ..Default::default()
+            },
+        ],
+        memory: vec![
+            MinidumpMemory {
+                address: 0x10_0000,
+                data: vec![1; 0x1000],
+            },
+            MinidumpMemory {
+                address: 0x7FF6_0000_0000,
+                data: (0..=255).collect(),
+            },
+        ],
+        timestamp: 0x6700_0000,
+    }
+}
+
+#[test]
+fn header_and_directory() {
+    let bytes = synthetic().to_bytes();
+    let dump = minidump::Minidump::read(&bytes[..]).unwrap();
+
+    assert_eq!(dump.header.version & 0xFFFF, 0xA793);
+    assert_eq!(dump.header.stream_count, 5);
+    assert_eq!(dump.header.time_date_stamp, 0x6700_0000);
+
+    dump.get_stream::<minidump::MinidumpSystemInfo>().unwrap();
+    dump.get_stream::<MinidumpThreadList>().unwrap();
+    dump.get_stream::<MinidumpModuleList>().unwrap();
+    dump.get_stream::<MinidumpMemoryList>().unwrap();
+    dump.get_stream::<MinidumpMemory64List>().unwrap();
+}
+
+#[test]
+fn system_info() {
+    let bytes = synthetic().to_bytes();
+    let dump = minidump::Minidump::read(&bytes[..]).unwrap();
+    let info = dump.get_stream::<minidump::MinidumpSystemInfo>().unwrap();
+
+    assert_eq!(info.cpu, minidump::system_info::Cpu::X86_64);
+    assert_eq!(info.os, minidump::system_info::Os::Windows);
+    assert_eq!(info.raw.number_of_processors, 8);
+    assert_eq!(info.raw.product_type, 1);
+    assert_eq!(info.raw.major_version, 10);
+    assert_eq!(info.raw.build_number, 26100);
+    assert_eq!(info.csd_version().as_deref(), Some(""));
+}
+
+#[test]
+fn threads_and_stacks() {
+    let source = synthetic();
+    let bytes = source.to_bytes();
+    let dump = minidump::Minidump::read(&bytes[..]).unwrap();
+    let info = dump.get_stream::<minidump::MinidumpSystemInfo>().unwrap();
+    let memory = dump.get_memory().unwrap();
+    let threads = dump.get_stream::<MinidumpThreadList>().unwrap().threads;
+
+    assert_eq!(threads.len(), 2);
+
+    let thread = &threads[0];
+    assert_eq!(thread.raw.thread_id, 0x104);
+    assert_eq!(thread.raw.suspend_count, 1);
+    assert_eq!(thread.raw.priority_class, 0x20);
+    assert_eq!(thread.raw.priority, 8);
+    assert_eq!(thread.raw.teb, 0x3000);
+
+    let stack = thread.stack_memory(&memory).unwrap();
+    assert_eq!(stack.base_address(), 0x10_3F00);
+    assert_eq!(stack.bytes(), &[0xAA; 0x100][..]);
+
+    let context = thread.context(&info, None).unwrap();
+    assert!(matches!(context.raw, MinidumpRawContext::Amd64(_)));
+    assert_eq!(context.get_instruction_pointer(), 0x7FF6_0000_1234);
+    assert_eq!(context.get_stack_pointer(), 0x10_3F00);
+    assert_eq!(thread.raw.thread_context.data_size, 1232);
+    // debuggers want it aligned.
+    assert_eq!(thread.raw.thread_context.rva % 16, 0);
+
+    let empty = &threads[1];
+    assert_eq!(empty.raw.thread_id, 0x108);
+    assert!(empty.stack_memory(&memory).is_none());
+    assert_eq!(
+        empty
+            .context(&info, None)
+            .unwrap()
+            .get_instruction_pointer(),
+        0
+    );
+
+    // memory list has the same stacks, pointing at the same bytes. parsers drop the empty one.
+    let list = dump.get_stream::<MinidumpMemoryList>().unwrap();
+    let stacks = list.iter().collect::<Vec<_>>();
+    assert_eq!(stacks.len(), 1);
+    assert_eq!(stacks[0].base_address, 0x10_3F00);
+    assert_eq!(stacks[0].desc.memory.rva, thread.raw.stack.memory.rva);
+    assert_eq!(stacks[0].bytes, &[0xAA; 0x100][..]);
+}
+
+#[test]
+fn modules() {
+    let bytes = synthetic().to_bytes();
+    let dump = minidump::Minidump::read(&bytes[..]).unwrap();
+    let modules = dump.get_stream::<MinidumpModuleList>().unwrap();
+    let modules = modules.iter().collect::<Vec<_>>();
+
+    assert_eq!(modules.len(), 2);
+
+    assert_eq!(modules[0].base_address(), 0x7FF6_0000_0000);
+    assert_eq!(modules[0].size(), 0x2000);
+    assert_eq!(modules[0].raw.checksum, 0x1234);
+    assert_eq!(modules[0].raw.time_date_stamp, 0x6500_0000);
+    assert_eq!(modules[0].code_file(), "C:\\Windows\\System32\\lsass.exe");
+    assert_eq!(modules[1].code_file(), "C:\\Windows\\System32\\ntdll.dll");
+}
+
+#[test]
+fn memory64() {
+    let source = synthetic();
+    let bytes = source.to_bytes();
+    let dump = minidump::Minidump::read(&bytes[..]).unwrap();
+    let list = dump.get_stream::<MinidumpMemory64List>().unwrap();
+    let ranges = list.iter().collect::<Vec<_>>();
+
+    assert_eq!(ranges.len(), source.memory.len());
+    for (range, memory) in ranges.iter().zip(&source.memory) {
+        assert_eq!(range.base_address, memory.address);
+        assert_eq!(range.bytes, &memory.data[..]);
+    }
+
+    // ranges are back to back and end the file.
+    for pair in ranges.windows(2) {
+        assert_eq!(pair[0].bytes.as_ptr_range().end, pair[1].bytes.as_ptr());
+    }
+    let last = ranges.last().unwrap();
+    assert_eq!(last.bytes.as_ptr_range().end, bytes.as_ptr_range().end);
+}
+
+#[test]
+fn dump_process() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("lsass.exe");
+
+        // stack. committed, private.
+        process.vads.insert(
+            0x100,
+            SimVad {
+                start_vpn: 0x100,
+                end_vpn: 0x103,
+                commit_charge: 4,
+                protection: 4, // MM_READWRITE
+                private: true,
+                ..Default::default()
+            },
+        );
+        process
+            .memory
+            .insert(0x10_0000, (0..0x4000).map(|x| x as u8).collect());
+
+        // image. only the headers are paged in.
+        process.vads.insert(
+            0x400,
+            SimVad {
+                start_vpn: 0x400,
+                end_vpn: 0x401,
+                protection: 7, // MM_EXECUTE_WRITECOPY
+                vad_type: VadType::ImageMap,
+                file_name: Some("\\Windows\\System32\\lsass.exe".into()),
+                ..Default::default()
+            },
+        );
+        let mut headers = vec![0u8; 0x1000];
+        headers[0x3C..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        headers[0x88..0x8C].copy_from_slice(&0x6500_0000u32.to_le_bytes());
+        headers[0xD8..0xDC].copy_from_slice(&0x1234u32.to_le_bytes());
+        process.memory.insert(0x40_0000, headers);
+        process.modules.push(SimModule {
+            base: 0x40_0000,
+            size: 0x2000,
+            path: "C:\\Windows\\System32\\lsass.exe".into(),
+            ..Default::default()
+        });
+
+        // reserved, nothing committed. not worth dumping.
+        process.vads.insert(
+            0x1000,
+            SimVad {
+                start_vpn: 0x1000,
+                end_vpn: 0x1FFF,
+                private: true,
+                ..Default::default()
+            },
+        );
+
+        let pid = k.add_process(process);
+
+        let context = ThreadContext {
+            rsp: 0x10_3F00,
+            rip: 0x40_1000,
+            ..Default::default()
+        };
+        k.add_thread(
+            pid,
+            SimThread {
+                context,
+                suspend_count: 1,
+                ..Default::default()
+            },
+        );
+        pid
+    });
+
+    let mut process = HxProcess::open(pid).unwrap();
+    let dump = block_on(process.minidump()).unwrap();
+
+    assert_eq!(dump.threads.len(), 1);
+    let thread = &dump.threads[0];
+    assert_eq!(thread.suspend_count, 1);
+    assert_eq!(thread.context.rip, 0x40_1000);
+    assert_eq!(thread.stack.address, 0x10_3F00);
+    assert_eq!(
+        thread.stack.data,
+        (0x3F00..0x4000).map(|x| x as u8).collect::<Vec<_>>()
+    );
+    // frozen for the dump, then put back.
+    let tid = thread.id;
+    assert_eq!(sim.with(|k| k.thread(tid).unwrap().suspend_count), 1);
+
+    assert_eq!(
+        dump.modules,
+        vec![MinidumpModule {
+            base: 0x40_0000,
+            size: 0x2000,
+            checksum: 0x1234,
+            timestamp: 0x6500_0000,
+            name: "C:\\Windows\\System32\\lsass.exe".into(),
+        }]
+    );
+
+    let ranges = dump
+        .memory
+        .iter()
+        .map(|x| (x.address, x.data.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, vec![(0x10_0000, 0x4000), (0x40_0000, 0x1000)]);
+
+    let bytes = dump.to_bytes();
+    let parsed = minidump::Minidump::read(&bytes[..]).unwrap();
+    let threads = parsed.get_stream::<MinidumpThreadList>().unwrap().threads;
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].raw.thread_id, tid);
+    assert_eq!(
+        parsed
+            .get_stream::<MinidumpModuleList>()
+            .unwrap()
+            .iter()
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn dump_fails_loudly() {
+    const STATUS_SUSPEND_COUNT_EXCEEDED: u32 = 0xC000004A;
+
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let pid = k.add_process(SimProcess::new("lsass.exe"));
+        k.add_thread(
+            pid,
+            SimThread {
+                suspend_count: 0x7F,
+                ..Default::default()
+            },
+        );
+        pid
+    });
+
+    // can't freeze it. a dump of it running would be a lie.
+    let mut process = HxProcess::open(pid).unwrap();
+    assert_eq!(
+        block_on(process.minidump()).err(),
+        Some(HxError::NtError(STATUS_SUSPEND_COUNT_EXCEEDED))
+    );
+}
+
+#[test]
+fn dump_reports_resume() {
+    const STATUS_UNSUCCESSFUL: u32 = 0xC0000001;
+
+    let sim = Sim::new();
+    let (pid, tid) = sim.with(|k| {
+        let pid = k.add_process(SimProcess::new("lsass.exe"));
+        let tid = k.add_thread(pid, SimThread::default()).unwrap();
+        (pid, tid)
+    });
+    sim.with(|k| {
+        k.fail_call(
+            ServiceFunction::ResumeProcess,
+            0,
+            HxResponse::nt_error(STATUS_UNSUCCESSFUL),
+        )
+    });
+
+    // the dump is fine. the process staying suspended is not.
+    let mut process = HxProcess::open(pid).unwrap();
+    assert_eq!(
+        block_on(process.minidump()).err(),
+        Some(HxError::NtError(STATUS_UNSUCCESSFUL))
+    );
+    assert_eq!(sim.with(|k| k.thread(tid).unwrap().suspend_count), 1);
+}
+
+#[test]
+fn dump_needs_freeze() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let pid = k.add_process(SimProcess::new("lsass.exe"));
+        k.add_thread(pid, SimThread::default());
+        pid
+    });
+    // like drivers that can't suspend on this build.
+    sim.with(|k| {
+        k.fail_call(
+            ServiceFunction::SuspendProcess,
+            0,
+            HxResponse::not_found_what(NotFoundReason::ServiceFunction),
+        )
+    });
+
+    let mut process = HxProcess::open(pid).unwrap();
+    assert_eq!(
+        block_on(process.minidump()).err(),
+        Some(HxError::NotFound(NotFoundReason::ServiceFunction))
+    );
+}
@@ -0,0 +1,101 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotAllowedReason;
+use hxposed_core::hxposed::output::OutputBufferHeader;
+use hxposed_core::services::output::HxOutputBuffer;
+use hxposed_core::services::process::HxProcess;
+use hxposed_sim::{Sim, SimProcess};
+use std::sync::{Mutex, MutexGuard};
+
+// the output buffer belongs to the process, so every test here shares it.
+// growing it moves it, so they take turns.
+static BUFFER: Mutex<()> = Mutex::new(());
+
+fn serial() -> MutexGuard<'static, ()> {
+    BUFFER.lock().unwrap_or_else(|x| x.into_inner())
+}
+
+#[test]
+fn header() {
+    let _serial = serial();
+    let _sim = Sim::new();
+
+    let header = HxOutputBuffer::header().unwrap();
+
+    assert!(header.size >= HxOutputBuffer::DEFAULT_SIZE);
+    assert!((OutputBufferHeader::SIZE..=header.size).contains(&header.cursor));
+}
+
+#[test]
+fn address_is_picked() {
+    let _serial = serial();
+    let _sim = Sim::new();
+
+    let address = HxOutputBuffer::register(HxOutputBuffer::DEFAULT_SIZE).unwrap();
+    assert_ne!(address, 0);
+
+    // emptied, and still where it was.
+    let header = unsafe { (address as *const OutputBufferHeader).read_volatile() };
+    assert_eq!(header.cursor, OutputBufferHeader::SIZE);
+    assert_eq!(HxOutputBuffer::header().unwrap(), header);
+    assert_eq!(
+        HxOutputBuffer::register(HxOutputBuffer::DEFAULT_SIZE).unwrap(),
+        address
+    );
+
+    assert_eq!(
+        HxOutputBuffer::register(HxOutputBuffer::MAX_SIZE + 1).unwrap_err(),
+        HxError::InvalidParameters(0)
+    );
+}
+
+#[test]
+fn results_reuse_the_buffer() {
+    let _serial = serial();
+    let sim = Sim::new();
+    let name = "a".repeat(1000);
+    let pid = sim.with(|k| k.add_process(SimProcess::new(&name)));
+
+    let process = HxProcess::open(pid).unwrap();
+    let expected = sim.with(|k| k.process(pid).unwrap().nt_path.clone());
+    let address = HxOutputBuffer::register(HxOutputBuffer::DEFAULT_SIZE).unwrap();
+    let size = HxOutputBuffer::header().unwrap().size;
+
+    // ~2 KiB each. fills it many times over, but each one fits an empty buffer.
+    for _ in 0..100 {
+        assert_eq!(process.get_nt_path().unwrap(), expected);
+    }
+
+    assert_eq!(HxOutputBuffer::header().unwrap().size, size);
+    assert_eq!(HxOutputBuffer::register(size).unwrap(), address);
+}
+
+#[test]
+fn results_grow_the_buffer() {
+    let _serial = serial();
+    let sim = Sim::new();
+    let size = HxOutputBuffer::header().unwrap().size;
+    // UTF-16. more than the whole buffer.
+    let name = "a".repeat(size as usize / 2);
+    let pid = sim.with(|k| k.add_process(SimProcess::new(&name)));
+
+    let process = HxProcess::open(pid).unwrap();
+    let expected = sim.with(|k| k.process(pid).unwrap().nt_path.clone());
+
+    assert_eq!(process.get_nt_path().unwrap(), expected);
+    assert!(HxOutputBuffer::header().unwrap().size > size);
+}
+
+#[test]
+fn result_too_big() {
+    let _serial = serial();
+    let sim = Sim::new();
+    let name = "a".repeat(HxOutputBuffer::MAX_SIZE as usize);
+    let pid = sim.with(|k| k.add_process(SimProcess::new(&name)));
+
+    let process = HxProcess::open(pid).unwrap();
+
+    assert_eq!(
+        process.get_nt_path().unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::BufferTooSmall)
+    );
+}
@@ -0,0 +1,131 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotAllowedReason;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::permissions::PluginPermissions;
+use hxposed_core::hxposed::requests::process::*;
+use hxposed_core::hxposed::requests::status::StatusRequest;
+use hxposed_core::services::batch::HxBatch;
+use hxposed_core::services::output::HxOutputBuffer;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::status::HxPosed;
+use hxposed_core::services::types::process_fields::*;
+use hxposed_sim::{Sim, SimProcess};
+
+mod common;
+use common::block_on;
+
+#[test]
+fn missing_permission() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    sim.with(|k| k.set_permissions(PluginPermissions::NONE));
+
+    assert_eq!(
+        HxProcess::open(pid).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::MissingPermission(
+            PluginPermissions::PROCESS_EXECUTIVE
+        ))
+    );
+
+    // status needs nothing.
+    HxPosed::status().unwrap();
+}
+
+#[test]
+fn executive_is_not_security() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    sim.with(|k| k.set_permissions(PluginPermissions::PROCESS_EXECUTIVE));
+
+    let mut process = HxProcess::open(pid).unwrap();
+    process.get_protection().unwrap();
+
+    assert_eq!(
+        process.set_protection(ProcessProtection::new()).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::MissingPermission(
+            PluginPermissions::PROCESS_SECURITY
+        ))
+    );
+
+    // killing is not a query.
+    assert_eq!(
+        block_on(process.kill(0)).unwrap_err(),
+        HxError::NotAllowed(NotAllowedReason::MissingPermission(
+            PluginPermissions::PROCESS_SECURITY
+        ))
+    );
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().exit_status), None);
+}
+
+#[test]
+fn output_buffer_needs_nothing_else() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    sim.with(|k| k.set_permissions(PluginPermissions::PROCESS_EXECUTIVE));
+
+    // the hypervisor allocates and maps it. no memory permissions involved.
+    HxOutputBuffer::register(HxOutputBuffer::DEFAULT_SIZE).unwrap();
+
+    let process = HxProcess::open(pid).unwrap();
+    assert!(process.get_nt_path().unwrap().ends_with("lsass.exe"));
+}
+
+#[test]
+fn checked_per_batch_entry() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    let process = HxProcess::open(pid).unwrap();
+    sim.with(|k| k.set_permissions(PluginPermissions::NONE));
+
+    let mut batch = HxBatch::new();
+    let status = batch.push(StatusRequest);
+    let protection = batch.push(GetProcessFieldRequest {
+        process: process.object(),
+        field: ProcessField::Protection(ProcessProtection::new()),
+    });
+    batch.submit().unwrap();
+
+    batch.get(&status).unwrap();
+    assert_eq!(
+        batch.get(&protection).err(),
+        Some(HxError::NotAllowed(NotAllowedReason::MissingPermission(
+            PluginPermissions::PROCESS_EXECUTIVE
+        )))
+    );
+}
+
+#[test]
+fn reason_round_trip() {
+    let reason = NotAllowedReason::MissingPermission(PluginPermissions::HANDLE_MANAGE);
+    assert_eq!(NotAllowedReason::from_bits(reason.into_bits()), reason);
+    assert_eq!(
+        NotAllowedReason::from_bits(NotAllowedReason::TypeMismatch.into_bits()),
+        NotAllowedReason::TypeMismatch
+    );
+    assert_eq!(
+        NotAllowedReason::from_bits(NotAllowedReason::BufferTooSmall.into_bits()),
+        NotAllowedReason::BufferTooSmall
+    );
+    assert_eq!(
+        NotAllowedReason::from_bits(NotAllowedReason::CurrentProcess.into_bits()),
+        NotAllowedReason::CurrentProcess
+    );
+    assert_eq!(
+        NotAllowedReason::from_bits(NotAllowedReason::AsyncOnly.into_bits()),
+        NotAllowedReason::AsyncOnly
+    );
+    assert_eq!(
+        NotAllowedReason::from_bits(NotAllowedReason::HandleProtected.into_bits()),
+        NotAllowedReason::HandleProtected
+    );
+
+    assert_eq!(
+        ServiceFunction::SetProcessField.permissions(),
+        PluginPermissions::PROCESS_SECURITY
+    );
+    assert_eq!(ServiceFunction::GetState.permissions(), PluginPermissions::NONE);
+    assert_eq!(
+        PluginPermissions::all().require(ServiceFunction::MsrIo.permissions()),
+        Ok(())
+    );
+}
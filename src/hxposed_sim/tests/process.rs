@@ -0,0 +1,587 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::requests::Syscall;
+use hxposed_core::hxposed::requests::process::*;
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::services::output::HxOutputBuffer;
+use hxposed_core::services::process::{HxProcess, HxProcessSnapshot};
+use hxposed_core::services::security::HxToken;
+use hxposed_core::services::types::process_fields::*;
+use hxposed_sim::{Sim, SimModule, SimProcess, SimThread};
+
+mod common;
+use common::block_on;
+
+#[test]
+fn open_missing_process() {
+    let _sim = Sim::new();
+
+    match HxProcess::open(0x1337) {
+        Err(e) => assert_eq!(e, HxError::NotFound(NotFoundReason::Process)),
+        Ok(_) => panic!("opened a process that does not exist"),
+    }
+}
+
+#[test]
+fn requests_fail_without_kernel() {
+    let sim = Sim::new();
+    drop(sim);
+
+    match HxProcess::open(4) {
+        Err(e) => assert_eq!(e, HxError::HvNotLoaded),
+        Ok(_) => panic!("opened a process without a kernel"),
+    }
+}
+
+#[test]
+fn protection_round_trip() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("MsMpEng.exe")));
+
+    let mut process = HxProcess::open(pid).unwrap();
+    let protection = ProcessProtection::new()
+        .with_protection_type(ProtectionType::Light)
+        .with_signer(ProtectionSigner::AntiMalware);
+
+    process.set_protection(protection).unwrap();
+
+    assert_eq!(process.get_protection().unwrap(), protection);
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().protection), protection);
+}
+
+#[test]
+fn signers_and_mitigations_round_trip() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+
+    let mut process = HxProcess::open(pid).unwrap();
+    let levels = ProcessSignatureLevels::new()
+        .with_signature_level(ProcessSignatureLevel::AntiMalware)
+        .with_section_signature_level(ProcessSignatureLevel::Microsoft.into_bits());
+    process.set_signature_levels(levels).unwrap();
+    assert_eq!(process.get_signature_levels().unwrap(), levels);
+
+    let options = MitigationOptions::from_bits(0x0000_0010_0000_0001);
+    process.set_mitigation_options(options).unwrap();
+    assert_eq!(process.get_mitigation_options().unwrap(), options);
+}
+
+#[test]
+fn mitigation_policies() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    sim.with(|k| {
+        k.process_mut(pid).unwrap().mitigations = MitigationOptions::new()
+            .with_cet(CetRules {
+                shadow_stacks: true,
+                ..Default::default()
+            })
+            .with_control_flow_guard(ControlFlowGuard {
+                enabled: true,
+                ..Default::default()
+            })
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    let diff = process
+        .update_mitigation_options(|x| {
+            x.set_dynamic_code(DynamicCode {
+                disabled: true,
+                ..x.dynamic_code()
+            });
+            x.set_control_flow_guard(ControlFlowGuard {
+                strict: true,
+                ..Default::default()
+            });
+        })
+        .unwrap();
+    assert_eq!(
+        diff.to_string(),
+        "-control_flow_guard.enabled, +control_flow_guard.strict, +dynamic_code.disabled"
+    );
+
+    let policies = process.get_mitigation_policies().unwrap();
+    assert!(policies.dynamic_code.disabled);
+    assert!(policies.control_flow_guard.strict);
+    // not touched by the update.
+    assert!(policies.cet.shadow_stacks);
+    assert_eq!(
+        MitigationOptions::from(policies),
+        process.get_mitigation_options().unwrap()
+    );
+
+    let old = process.get_mitigation_options3().unwrap();
+    let new = old.with_core_sharing(CoreSharingRules {
+        restrict: true,
+        ..Default::default()
+    });
+    process
+        .set_mitigation_options3(new.with_reserved(1 << 3))
+        .unwrap();
+    assert!(process.get_mitigation_options3().unwrap().restrict_core_sharing());
+    assert_eq!(
+        old.diff(&process.get_mitigation_options3().unwrap()).to_string(),
+        "+core_sharing.restrict, +bit69"
+    );
+    assert_eq!(old.diff(&old).to_string(), "none");
+}
+
+#[test]
+fn snapshot_and_apply() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("lsass.exe");
+        process.protection = ProcessProtection::new()
+            .with_protection_type(ProtectionType::Light)
+            .with_signer(ProtectionSigner::Lsa);
+        k.add_process(process)
+    });
+
+    let mut process = HxProcess::open(pid).unwrap();
+    let snapshot = process.snapshot().unwrap();
+    // the token isn't written. it's only good while it's open.
+    assert_eq!(
+        HxProcessSnapshot::from_bytes(&snapshot.to_bytes()),
+        Some(HxProcessSnapshot {
+            token: None,
+            ..snapshot.clone()
+        })
+    );
+    assert_eq!(HxProcessSnapshot::from_bytes(&[0; 4]), None);
+
+    process.set_protection(ProcessProtection::new()).unwrap();
+    process
+        .set_mitigation_options(MitigationOptions::from_bits(0xFF))
+        .unwrap();
+    process.swap_token(&HxToken::get_system_token()).unwrap();
+    assert_ne!(process.snapshot().unwrap(), snapshot);
+
+    process.apply(&snapshot).unwrap();
+    assert_eq!(process.snapshot().unwrap(), snapshot);
+}
+
+#[test]
+fn apply_rolls_back() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+
+    let process = HxProcess::open(pid).unwrap();
+    let before = process.snapshot().unwrap();
+    let target = HxProcessSnapshot {
+        protection: ProcessProtection::new().with_protection_type(ProtectionType::Light),
+        signature_levels: ProcessSignatureLevels::new()
+            .with_signature_level(ProcessSignatureLevel::Microsoft),
+        mitigations: MitigationOptions::from_bits(0xFF),
+        ..before.clone()
+    };
+
+    // mitigations go through, signers don't.
+    sim.with(|k| {
+        k.fail_call(
+            ServiceFunction::SetProcessField,
+            1,
+            HxResponse::nt_error(0xC0000022),
+        )
+    });
+
+    assert_eq!(
+        process.apply(&target).unwrap_err(),
+        HxError::NtError(0xC0000022)
+    );
+    assert_eq!(process.snapshot().unwrap(), before);
+
+    process.apply(&target).unwrap();
+    assert_eq!(process.snapshot().unwrap(), target);
+}
+
+#[test]
+fn apply_outlives_token() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+
+    let process = HxProcess::open(pid).unwrap();
+    let token = sim.with(|k| k.process(pid).unwrap().token);
+    let snapshot = process.snapshot().unwrap();
+    let bytes = snapshot.to_bytes();
+
+    // the process moves on. the snapshot keeps its old token around.
+    process.swap_token(&HxToken::get_system_token()).unwrap();
+    process.apply(&snapshot).unwrap();
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().token), token);
+
+    // a snapshot read back has no token to hold. it leaves the current one alone.
+    process.swap_token(&HxToken::get_system_token()).unwrap();
+    let system = sim.with(|k| k.process(pid).unwrap().token);
+    process
+        .apply(&HxProcessSnapshot::from_bytes(&bytes).unwrap())
+        .unwrap();
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().token), system);
+}
+
+#[test]
+fn directory_bases() {
+    let sim = Sim::new();
+    let (dtb, user_dtb) = sim.with(|k| {
+        let p = k.process(4).unwrap();
+        (p.directory_table_base, p.user_directory_table_base)
+    });
+
+    let process = HxProcess::system();
+
+    assert_eq!(process.get_directory_base().unwrap(), dtb);
+    assert_eq!(process.get_user_directory_base().unwrap(), user_dtb);
+}
+
+#[test]
+fn swap_primary_token() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("cmd.exe")));
+    let system_token = sim.with(|k| k.system_token());
+
+    let process = HxProcess::open(pid).unwrap();
+    let system = HxProcess::system();
+    let token = system.get_primary_token().unwrap();
+
+    process.swap_token(&token).unwrap();
+
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().token), system_token);
+}
+
+#[test]
+fn closing_releases_objects() {
+    let sim = Sim::new();
+
+    {
+        let _current = HxProcess::current();
+        let _system = HxProcess::system();
+        assert_eq!(sim.with(|k| k.tracker().processes.len()), 3);
+    }
+
+    // only the caller itself remains.
+    assert_eq!(sim.with(|k| k.tracker().processes.len()), 1);
+}
+
+#[test]
+fn close_without_result() {
+    let sim = Sim::new();
+
+    let object = OpenProcessRequest { process_id: 4 }
+        .send()
+        .unwrap()
+        .object;
+    assert_eq!(sim.with(|k| k.tracker().processes.len()), 2);
+
+    CloseProcessRequest {
+        process: object.into(),
+    }
+    .send_forget();
+    assert_eq!(sim.with(|k| k.tracker().processes.len()), 1);
+
+    // nobody hears about the failure.
+    CloseProcessRequest { process: 0x1337 }.send_forget();
+}
+
+#[test]
+fn nt_path() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("explorer.exe")));
+
+    let process = HxProcess::open(pid).unwrap();
+
+    assert_eq!(
+        process.get_nt_path().unwrap(),
+        "\\Device\\HarddiskVolume3\\Windows\\System32\\explorer.exe"
+    );
+}
+
+#[test]
+fn threads() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("svchost.exe")));
+    let threads = sim.with(|k| {
+        (0..3)
+            .map(|_| k.add_thread(pid, SimThread::default()).unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+
+    assert_eq!(process.get_threads().unwrap(), threads);
+}
+
+#[test]
+fn kill() {
+    let sim = Sim::new();
+    let mut lsass = SimProcess::new("lsass.exe");
+    lsass.protection = ProcessProtection::new()
+        .with_protection_type(ProtectionType::Light)
+        .with_signer(ProtectionSigner::Lsa);
+    let pid = sim.with(|k| k.add_process(lsass));
+
+    let process = HxProcess::open(pid).unwrap();
+    block_on(process.kill(0xC000_013A)).unwrap();
+
+    assert_eq!(sim.with(|k| k.process(pid).unwrap().exit_status), Some(0xC000_013A));
+    assert_eq!(
+        block_on(process.kill(0)).unwrap_err(),
+        HxError::NtError(0xC000_010A)
+    );
+}
+
+#[test]
+fn kill_system() {
+    let _sim = Sim::new();
+
+    assert_eq!(
+        block_on(HxProcess::system().kill(0)).unwrap_err(),
+        HxError::NtError(0xC000_0022)
+    );
+}
+
+#[test]
+fn enumerate() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("a_very_long_image_name.exe")));
+    let killed = sim.with(|k| k.add_process(SimProcess::new("killed.exe")));
+    block_on(HxProcess::open(killed).unwrap().kill(0)).unwrap();
+
+    let processes = block_on(HxProcess::enumerate()).unwrap().collect::<Vec<_>>();
+
+    let system = processes.iter().find(|x| x.id == 4).unwrap();
+    assert_eq!(system.name(), "System");
+    assert_eq!(
+        system.protection,
+        sim.with(|k| k.process(4).unwrap().protection)
+    );
+
+    let process = processes.iter().find(|x| x.id == pid).unwrap();
+    assert_eq!(process.name(), "a_very_long_ima");
+    assert_eq!(
+        process.create_time,
+        sim.with(|k| k.process(pid).unwrap().create_time)
+    );
+
+    assert!(processes.iter().any(|x| x.id == sim.with(|k| k.caller().id)));
+    assert!(!processes.iter().any(|x| x.id == killed));
+}
+
+#[test]
+fn tree() {
+    let sim = Sim::new();
+    // parent id points to a process created after it. that's a reused id, not a parent.
+    let orphan = sim.with(|k| {
+        let mut orphan = SimProcess::new("orphan.exe");
+        orphan.parent_id = 0x2000;
+        k.add_process(orphan)
+    });
+    let (parent, child) = sim.with(|k| {
+        let mut parent = SimProcess::new("explorer.exe");
+        parent.id = 0x2000;
+        let parent = k.add_process(parent);
+
+        let mut child = SimProcess::new("notepad.exe");
+        child.parent_id = parent;
+        (parent, k.add_process(child))
+    });
+
+    let roots = block_on(HxProcess::tree()).unwrap();
+
+    let system = roots.iter().find(|x| x.entry.id == 4).unwrap();
+    assert!(system.children.iter().any(|x| x.entry.id == sim.with(|k| k.caller().id)));
+
+    assert!(roots.iter().any(|x| x.entry.id == orphan));
+
+    let parent = roots.iter().find(|x| x.entry.id == parent).unwrap();
+    assert_eq!(parent.children.len(), 1);
+    assert_eq!(parent.children[0].entry.id, child);
+}
+
+#[test]
+fn modules() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("legacy.exe");
+        process.modules = vec![
+            SimModule {
+                base: 0x7FFA_0000_0000,
+                size: 0x1F_0000,
+                entry_point: 0,
+                path: "C:\\Windows\\System32\\ntdll.dll".into(),
+                wow64: false,
+            },
+            SimModule {
+                base: 0x40_0000,
+                size: 0x2_0000,
+                entry_point: 0x40_1000,
+                path: "C:\\Games\\legacy.exe".into(),
+                wow64: true,
+            },
+        ];
+        k.add_process(process)
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    let modules = block_on(process.modules()).unwrap().collect::<Vec<_>>();
+    assert_eq!(modules.len(), 2);
+
+    assert_eq!(modules[0].path, "C:\\Windows\\System32\\ntdll.dll");
+    assert!(!modules[0].wow64);
+
+    let legacy = modules.iter().find(|x| x.contains(0x40_1234)).unwrap();
+    assert_eq!(legacy.path, "C:\\Games\\legacy.exe");
+    assert_eq!(legacy.entry_point, 0x40_1000);
+    assert!(legacy.wow64);
+    assert!(!legacy.contains(0x42_0000));
+}
+
+#[test]
+fn modules_need_async() {
+    let _sim = Sim::new();
+    let process = OpenProcessRequest { process_id: 4 }.send().unwrap().object;
+
+    // attaches and reads user memory. not inline in the handler.
+    assert_eq!(
+        EnumerateModulesRequest {
+            process: process.into(),
+        }
+        .send()
+        .err(),
+        Some(HxError::NotAllowed(NotAllowedReason::AsyncOnly))
+    );
+}
+
+#[test]
+fn process_parameters() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("notepad.exe");
+        process.command_line = "notepad.exe C:\\notes.txt".into();
+        process.current_directory = "C:\\Users\\user\\".into();
+        process.window_title = "C:\\Windows\\System32\\notepad.exe".into();
+        process.environment = vec![
+            ("=C:".into(), "C:\\Users\\user".into()),
+            ("PATH".into(), "C:\\Windows;C:\\Windows\\System32".into()),
+            ("EMPTY".into(), "".into()),
+        ];
+        k.add_process(process)
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    assert_eq!(process.command_line().unwrap(), "notepad.exe C:\\notes.txt");
+    assert_eq!(process.current_directory().unwrap(), "C:\\Users\\user\\");
+    assert_eq!(
+        process.window_title().unwrap(),
+        "C:\\Windows\\System32\\notepad.exe"
+    );
+
+    let environment = process.environment().unwrap();
+    assert!(!environment.truncated);
+    let environment = environment.variables;
+    assert_eq!(environment.len(), 3);
+    assert_eq!(environment[0], ("=C:".into(), "C:\\Users\\user".into()));
+    assert_eq!(environment[1].1, "C:\\Windows;C:\\Windows\\System32");
+    assert_eq!(environment[2], ("EMPTY".into(), "".into()));
+
+    // no PEB.
+    let system = HxProcess::open(4).unwrap();
+    assert_eq!(system.command_line().unwrap(), "");
+    assert!(system.environment().unwrap().variables.is_empty());
+
+    assert_eq!(
+        SetProcessFieldRequest {
+            process: system.object(),
+            field: ProcessField::CommandLine(Default::default()),
+        }
+        .send()
+        .unwrap_err(),
+        HxError::NotFound(NotFoundReason::Field)
+    );
+}
+
+#[test]
+fn environment_truncated() {
+    let sim = Sim::new();
+    // more than the biggest output buffer there is.
+    let count = HxOutputBuffer::MAX_SIZE as usize / 0x800;
+    let pid = sim.with(|k| {
+        let mut process = SimProcess::new("bloated.exe");
+        process.environment = (0..count)
+            .map(|i| (format!("VAR{}", i), "x".repeat(0x400)))
+            .collect();
+        k.add_process(process)
+    });
+
+    let process = HxProcess::open(pid).unwrap();
+    let environment = process.environment().unwrap();
+
+    assert!(environment.truncated);
+    assert!(!environment.variables.is_empty());
+    assert!(environment.variables.len() < count);
+    // whole variables only. the one cut in half is left out.
+    for (i, (name, value)) in environment.variables.iter().enumerate() {
+        assert_eq!(*name, format!("VAR{}", i));
+        assert_eq!(value.len(), 0x400);
+    }
+}
+
+#[test]
+fn suspend_resume() {
+    let sim = Sim::new();
+    let mut lsass = SimProcess::new("lsass.exe");
+    lsass.protection = ProcessProtection::new()
+        .with_protection_type(ProtectionType::Light)
+        .with_signer(ProtectionSigner::Lsa);
+    let pid = sim.with(|k| k.add_process(lsass));
+    let tids: Vec<u32> = sim.with(|k| {
+        (0..2)
+            .map(|_| k.add_thread(pid, SimThread::default()).unwrap())
+            .collect()
+    });
+    let counts = || {
+        sim.with(|k| {
+            tids.iter()
+                .map(|t| k.thread(*t).unwrap().suspend_count)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let process = HxProcess::open(pid).unwrap();
+    assert_eq!(process.suspend().unwrap(), 0);
+    assert_eq!(process.suspend().unwrap(), 1);
+    assert_eq!(counts(), [2, 2]);
+
+    assert_eq!(process.resume().unwrap(), 2);
+    assert_eq!(counts(), [1, 1]);
+
+    block_on(process.kill(0)).unwrap();
+    assert_eq!(process.suspend().unwrap_err(), HxError::NtError(0xC000_010A));
+}
+
+#[test]
+fn freeze() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+    let tid = sim.with(|k| k.add_thread(pid, SimThread::default()).unwrap());
+    let count = || sim.with(|k| k.thread(tid).unwrap().suspend_count);
+
+    let mut process = HxProcess::open(pid).unwrap();
+    let protection = ProcessProtection::new()
+        .with_protection_type(ProtectionType::Protected)
+        .with_signer(ProtectionSigner::WinTcb);
+    {
+        let mut frozen = process.freeze().unwrap();
+        assert_eq!(count(), 1);
+
+        frozen.set_protection(protection).unwrap();
+    }
+
+    assert_eq!(count(), 0);
+    assert_eq!(process.get_protection().unwrap(), protection);
+
+    // it would be freezing itself, this very thread included.
+    let mut current = HxProcess::current();
+    let current_process = Some(HxError::NotAllowed(NotAllowedReason::CurrentProcess));
+    assert_eq!(current.freeze().err(), current_process);
+    assert_eq!(current.suspend().err(), current_process);
+}
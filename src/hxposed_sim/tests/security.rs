@@ -0,0 +1,88 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::NotFoundReason;
+use hxposed_core::services::process::HxProcess;
+use hxposed_core::services::security::HxToken;
+use hxposed_core::services::types::security_fields::TokenPrivilege;
+use hxposed_sim::Sim;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[test]
+fn system_token_privileges() {
+    let sim = Sim::new();
+    let expected = sim.with(|k| {
+        let token = k.system_token();
+        k.token(token).unwrap().enabled_privileges
+    });
+
+    let token = HxToken::get_system_token();
+
+    assert_eq!(token.get_enabled_privileges().unwrap(), expected);
+    assert_eq!(token.get_source_name().unwrap(), "*SYSTEM*");
+}
+
+#[test]
+fn elevate_primary_token() {
+    let sim = Sim::new();
+
+    let process = HxProcess::current();
+    let token = process.get_primary_token().unwrap();
+    let system = HxToken::get_system_token();
+
+    assert_ne!(
+        token.get_present_privileges().unwrap(),
+        system.get_present_privileges().unwrap()
+    );
+
+    let privileges = system.get_enabled_privileges().unwrap();
+    token.set_present_privileges(privileges).unwrap();
+    token.set_enabled_privileges(privileges).unwrap();
+
+    assert_eq!(token.get_enabled_privileges().unwrap(), privileges);
+
+    let caller_token = sim.with(|k| k.caller().token);
+    assert_eq!(
+        sim.with(|k| k.token(caller_token).unwrap().enabled_privileges),
+        privileges
+    );
+    assert!(privileges.contains(TokenPrivilege::SeDebugPrivilege));
+}
+
+#[test]
+fn tokens_are_closed_on_drop() {
+    let sim = Sim::new();
+
+    {
+        let _token = HxToken::get_system_token();
+        assert_eq!(sim.with(|k| k.tracker().tokens.len()), 1);
+    }
+
+    assert_eq!(sim.with(|k| k.tracker().tokens.len()), 0);
+}
+
+#[test]
+fn primary_token_of_missing_process() {
+    let sim = Sim::new();
+    let pid = sim.with(|k| k.add_process(hxposed_sim::SimProcess::new("a.exe")));
+
+    let process = HxProcess::open(pid).unwrap();
+    sim.with(|k| k.remove_process(pid));
+
+    match process.get_primary_token() {
+        Err(e) => assert_eq!(e, HxError::NotFound(NotFoundReason::Process)),
+        Ok(_) => panic!("process is gone"),
+    }
+}
+
+#[test]
+fn account_name() {
+    let _sim = Sim::new();
+
+    let token = HxToken::get_system_token();
+    let mut cx = Context::from_waker(Waker::noop());
+
+    match pin!(token.get_account_name()).poll(&mut cx) {
+        Poll::Ready(name) => assert_eq!(name.unwrap(), "SYSTEM"),
+        Poll::Pending => panic!("account name is not a slow call"),
+    }
+}
@@ -0,0 +1,147 @@
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::error::{NotAllowedReason, NotFoundReason};
+use hxposed_core::hxposed::output::OutputSlice;
+use hxposed_core::hxposed::requests::Syscall;
+use hxposed_core::hxposed::requests::thread::{GetThreadFieldRequest, ThreadField};
+use hxposed_core::services::batch::HxBatch;
+use hxposed_core::services::security::HxToken;
+use hxposed_core::services::thread::HxThread;
+use hxposed_core::services::types::thread_fields::ThreadContext;
+use hxposed_sim::{Sim, SimThread};
+
+mod common;
+use common::block_on;
+
+#[test]
+fn open_current_thread() {
+    let sim = Sim::new();
+
+    let thread = HxThread::current().unwrap();
+
+    assert!(!thread.is_impersonating().unwrap());
+    assert_eq!(sim.with(|k| k.tracker().threads.len()), 1);
+}
+
+#[test]
+fn impersonation_info() {
+    let sim = Sim::new();
+    let tid = sim.with(|k| {
+        k.add_thread(
+            4,
+            SimThread {
+                impersonating: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+
+    let thread = HxThread::open(tid).unwrap();
+
+    assert!(thread.is_impersonating().unwrap());
+}
+
+#[test]
+fn open_missing_thread() {
+    let _sim = Sim::new();
+
+    match HxThread::open(0x7FFF_FFFF) {
+        Err(e) => assert_eq!(e, HxError::NotFound(NotFoundReason::Thread)),
+        Ok(_) => panic!("opened a thread that does not exist"),
+    }
+}
+
+#[test]
+fn suspend_count() {
+    let sim = Sim::new();
+    let tid = sim.with(|k| k.add_thread(4, SimThread::default()).unwrap());
+
+    let thread = HxThread::open(tid).unwrap();
+    assert_eq!(thread.suspend().unwrap(), 0);
+    assert_eq!(thread.suspend().unwrap(), 1);
+    assert_eq!(sim.with(|k| k.thread(tid).unwrap().suspend_count), 2);
+
+    assert_eq!(thread.resume().unwrap(), 2);
+    assert_eq!(thread.resume().unwrap(), 1);
+    // already running. nt says 0 and does nothing.
+    assert_eq!(thread.resume().unwrap(), 0);
+}
+
+#[test]
+fn suspend_count_exceeded() {
+    let sim = Sim::new();
+    let tid = sim.with(|k| {
+        k.add_thread(
+            4,
+            SimThread {
+                suspend_count: 0x7F,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+
+    assert_eq!(
+        HxThread::open(tid).unwrap().suspend().unwrap_err(),
+        HxError::NtError(0xC000_004A)
+    );
+}
+
+#[test]
+fn impersonation_token() {
+    let sim = Sim::new();
+
+    let thread = HxThread::current().unwrap();
+    let token = HxToken::get_system_token();
+
+    thread.swap_impersonation_token(&token).unwrap();
+    let impersonation = thread.get_impersonation_token().unwrap();
+
+    assert_eq!(
+        impersonation.get_source_name().unwrap(),
+        token.get_source_name().unwrap()
+    );
+    assert_eq!(
+        sim.with(|k| k.thread(thread.id).unwrap().adjusted_client_token),
+        sim.with(|k| k.system_token())
+    );
+}
+
+#[test]
+fn context_needs_async() {
+    let sim = Sim::new();
+    let context = ThreadContext {
+        rip: 0x7FF6_0000_1234,
+        ..Default::default()
+    };
+    let tid = sim.with(|k| {
+        k.add_thread(
+            4,
+            SimThread {
+                context,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    });
+    let thread = HxThread::open(tid).unwrap();
+    let request = GetThreadFieldRequest {
+        thread: thread.object(),
+        field: ThreadField::Context(OutputSlice::new()),
+    };
+
+    // waits on the thread. never inline in the handler, batched or not.
+    assert_eq!(
+        request.clone().send().err(),
+        Some(HxError::NotAllowed(NotAllowedReason::AsyncOnly))
+    );
+    let mut batch = HxBatch::new();
+    let entry = batch.push(request);
+    batch.submit().unwrap();
+    assert_eq!(
+        batch.get(&entry).err(),
+        Some(HxError::NotAllowed(NotAllowedReason::AsyncOnly))
+    );
+
+    assert_eq!(block_on(thread.get_context()).unwrap().rip, 0x7FF6_0000_1234);
+}
@@ -0,0 +1,87 @@
+use core::cell::RefCell;
+use hxposed_core::error::HxError;
+use hxposed_core::hxposed::func::ServiceFunction;
+use hxposed_core::hxposed::requests::status::StatusRequest;
+use hxposed_core::hxposed::requests::{HxRequest, Syscall};
+use hxposed_core::hxposed::responses::HxResponse;
+use hxposed_core::hxposed::transport::{Transport, set_transport, transport};
+use hxposed_core::services::process::HxProcess;
+use hxposed_sim::{Sim, SimProcess, SimTransport};
+use std::sync::Once;
+
+std::thread_local! {
+    static SEEN: RefCell<Vec<HxRequest>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Writes down every request, then hands it to the simulator.
+struct RecordingTransport;
+
+impl Transport for RecordingTransport {
+    fn send(&self, request: &mut HxRequest) -> HxResponse {
+        SEEN.with_borrow_mut(|x| x.push(request.clone()));
+        SimTransport.send(request)
+    }
+}
+
+static RECORDER: RecordingTransport = RecordingTransport;
+static INSTALL: Once = Once::new();
+
+fn recorded() -> Sim {
+    // the first Sim installs its own transport. ours must come after it.
+    let sim = Sim::new();
+    INSTALL.call_once(|| set_transport(&RECORDER));
+    SEEN.with_borrow_mut(|x| x.clear());
+    sim
+}
+
+fn seen() -> Vec<HxRequest> {
+    SEEN.with_borrow(|x| x.clone())
+}
+
+#[test]
+fn requests_go_through_transport() {
+    let _sim = recorded();
+
+    StatusRequest.send().unwrap();
+
+    let seen = seen();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].call.func(), ServiceFunction::GetState);
+}
+
+#[test]
+fn raw_request_is_encoded() {
+    let sim = recorded();
+    let pid = sim.with(|k| k.add_process(SimProcess::new("lsass.exe")));
+
+    let process = HxProcess::open(pid).unwrap();
+
+    let open = &seen()[0];
+    assert_eq!(open.call.func(), ServiceFunction::OpenProcess);
+    assert_eq!(open.arg1, pid as u64);
+    assert!(!open.call.ignore_result());
+
+    // closing on drop doesn't want an answer.
+    drop(process);
+    let close = seen().pop().unwrap();
+    assert_eq!(close.call.func(), ServiceFunction::CloseProcess);
+    assert!(close.call.ignore_result());
+}
+
+#[test]
+fn missing_backend() {
+    let _sim = recorded();
+
+    // the transport is global, the simulated kernel isn't.
+    let result = std::thread::spawn(|| {
+        (
+            transport().send(&mut HxRequest::default()),
+            StatusRequest.send(),
+        )
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(HxError::from_response(&result.0), HxError::HvNotLoaded);
+    assert_eq!(result.1.unwrap_err(), HxError::HvNotLoaded);
+}